@@ -0,0 +1,115 @@
+//! Demonstrates use of a custom view with the ViewDispatcher.
+//!
+//! This app shows a counter, changed with the Up and Down keys. Holding OK resets it,
+//! and Back exits.
+//!
+//! Drawing text still uses unsafe `sys` bindings, as `Canvas` has no text API yet.
+
+#![no_main]
+#![no_std]
+
+// Required for panic handler
+extern crate flipperzero_rt;
+
+// Required for allocator
+extern crate flipperzero_alloc;
+
+use core::ffi::CStr;
+use core::fmt::Write;
+
+use flipperzero::furi::string::FuriString;
+use flipperzero::gui::canvas::Canvas;
+use flipperzero::gui::input::{InputEvent, InputType, Key};
+use flipperzero::gui::view::{CustomView, Model, View};
+use flipperzero::gui::view_dispatcher::{
+    CustomEvent, DispatcherEvent, EventSender, ViewDispatcher, ViewDispatcherType, ViewId,
+};
+use flipperzero_rt::{entry, manifest};
+use flipperzero_sys as sys;
+
+manifest!(name = "Rust custom view example");
+entry!(main);
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum AppView {
+    Counter,
+}
+
+impl ViewId for AppView {
+    fn to_raw(self) -> u32 {
+        self as u32
+    }
+}
+
+#[derive(Clone, Copy)]
+enum AppEvent {
+    Reset,
+}
+
+impl CustomEvent for AppEvent {
+    fn to_raw(self) -> u32 {
+        self as u32
+    }
+
+    fn from_raw(raw: u32) -> Option<Self> {
+        (raw == AppEvent::Reset as u32).then_some(AppEvent::Reset)
+    }
+}
+
+struct Counter {
+    events: EventSender<AppEvent>,
+}
+
+impl View for Counter {
+    type Model = i32;
+
+    fn draw(count: &i32, canvas: &mut Canvas) {
+        let mut text = FuriString::new();
+        let _ = write!(text, "Count: {count}");
+        unsafe {
+            sys::canvas_draw_str_aligned(
+                canvas.as_ptr(),
+                64,
+                32,
+                sys::AlignCenter,
+                sys::AlignCenter,
+                text.as_c_ptr(),
+            );
+        }
+    }
+
+    fn input(&mut self, mut model: Model<'_, i32>, event: InputEvent) -> bool {
+        match (event.key, event.kind) {
+            (Key::Up, InputType::Short | InputType::Repeat) => *model.lock() += 1,
+            (Key::Down, InputType::Short | InputType::Repeat) => *model.lock() -= 1,
+            (Key::Ok, InputType::Long) => self.events.send(AppEvent::Reset),
+            _ => return false,
+        }
+        true
+    }
+}
+
+fn main(_args: Option<&CStr>) -> i32 {
+    let mut view_dispatcher =
+        ViewDispatcher::<AppEvent, AppView>::new(ViewDispatcherType::Fullscreen);
+
+    let counter = Counter {
+        events: view_dispatcher.sender(),
+    };
+    view_dispatcher.add_view(AppView::Counter, CustomView::new(counter, 0));
+    view_dispatcher.switch_to_view(AppView::Counter);
+
+    view_dispatcher.run(|view_dispatcher, event| match event {
+        DispatcherEvent::Custom(AppEvent::Reset) => {
+            if let Some(view) = view_dispatcher.view_mut::<CustomView<Counter>>(AppView::Counter) {
+                *view.model().lock() = 0;
+            }
+            true
+        }
+        // Stop the dispatcher.
+        DispatcherEvent::Back => false,
+        DispatcherEvent::Tick => true,
+    });
+
+    0
+}
@@ -0,0 +1,87 @@
+//! Flipper Format example for Flipper Zero.
+//!
+//! Saves a settings struct to the SD card using `#[derive(FlipperFormat)]`, then loads it back.
+
+#![no_main]
+#![no_std]
+
+// Required for panic handler
+extern crate flipperzero_rt;
+
+// Required for allocator
+extern crate alloc;
+extern crate flipperzero_alloc;
+
+use alloc::string::String;
+use core::ffi::CStr;
+
+use flipperzero::flipper_format::{FlipperFormat, OpenMode};
+use flipperzero::println;
+use flipperzero::storage::Storage;
+use flipperzero_format::{Load, Save};
+use flipperzero_rt::{entry, manifest};
+
+manifest!(name = "Rust Flipper Format example");
+entry!(main);
+
+const PATH: &CStr = c"/ext/flipperzero-rs-example.settings";
+
+#[derive(flipperzero_format::FlipperFormat, Clone, Copy, Debug)]
+enum Theme {
+    Light,
+    Dark,
+}
+
+#[derive(flipperzero_format::FlipperFormat, Debug)]
+#[flipper_format(filetype = "Rust Example Settings", version = 1)]
+struct Settings {
+    #[flipper_format(rename = "Name")]
+    name: String,
+    #[flipper_format(rename = "Volume")]
+    volume: u32,
+    #[flipper_format(rename = "Theme")]
+    theme: Theme,
+    #[flipper_format(rename = "Key")]
+    key: [u8; 4],
+}
+
+fn main(_args: Option<&CStr>) -> i32 {
+    let storage = Storage::open();
+
+    let settings = Settings {
+        name: String::from("Flipper"),
+        volume: 7,
+        theme: Theme::Dark,
+        key: [0xDE, 0xAD, 0xBE, 0xEF],
+    };
+
+    let mut file = FlipperFormat::file(&storage);
+    let saved = file
+        .open(PATH, OpenMode::Always)
+        .map_err(flipperzero_format::Error::from)
+        .and_then(|()| settings.save(&mut file));
+    if let Err(e) = saved {
+        println!("failed to save settings: {}", e);
+        return 1;
+    }
+    drop(file);
+
+    let mut file = FlipperFormat::file(&storage);
+    let loaded = file
+        .open(PATH, OpenMode::Existing)
+        .map_err(flipperzero_format::Error::from)
+        .and_then(|()| Settings::load(&mut file));
+    match loaded {
+        Ok(settings) => {
+            println!("name: {}", settings.name.as_str());
+            println!("volume: {}", settings.volume);
+            println!("key: {:?}", settings.key);
+        }
+        Err(e) => {
+            println!("failed to load settings: {}", e);
+            return 1;
+        }
+    }
+
+    0
+}
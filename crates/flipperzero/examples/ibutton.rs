@@ -0,0 +1,63 @@
+//! iButton example for Flipper Zero.
+//!
+//! This app waits for an iButton key to touch the Flipper's contact pad,
+//! prints its protocol and data, then saves it to the SD card.
+
+#![no_main]
+#![no_std]
+
+// Required for panic handler
+extern crate flipperzero_rt;
+
+// Required for allocator
+extern crate flipperzero_alloc;
+
+extern crate alloc;
+
+use alloc::sync::Arc;
+use core::ffi::CStr;
+
+use flipperzero::furi::message_queue::MessageQueue;
+use flipperzero::furi::string::FuriString;
+use flipperzero::furi::time::FuriDuration;
+use flipperzero::ibutton::{IButtonKey, IButtonWorker};
+use flipperzero::println;
+use flipperzero_rt::{entry, manifest};
+
+manifest!(name = "Rust iButton example");
+entry!(main);
+
+fn main(_args: Option<&CStr>) -> i32 {
+    let mut worker = IButtonWorker::new();
+    let key = IButtonKey::new(worker.protocols());
+    let queue = Arc::new(MessageQueue::new(1));
+
+    println!("Touch the Flipper with a key...");
+    let sender = queue.clone();
+    let reader = worker.read(key, move || {
+        let _ = sender.put((), FuriDuration::ZERO);
+    });
+    let found = queue.get(FuriDuration::from_secs(10)).is_ok();
+    let key = reader.stop();
+
+    if !found {
+        println!("no key found");
+        return 1;
+    }
+
+    let protocols = worker.protocols();
+    let id = key.protocol_id().unwrap();
+    println!(
+        "{}: {:?}",
+        FuriString::from(protocols.name(id)),
+        key.data(protocols)
+    );
+    println!("{}", protocols.render_data(&key));
+
+    match key.save(protocols, c"/ext/ibutton/Rust.ibtn") {
+        Ok(()) => println!("saved to /ext/ibutton/Rust.ibtn"),
+        Err(e) => println!("{}", e),
+    }
+
+    0
+}
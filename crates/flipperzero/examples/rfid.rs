@@ -0,0 +1,60 @@
+//! LF RFID example for Flipper Zero.
+//!
+//! This app waits for a 125 kHz tag to be held against the back of the Flipper,
+//! prints its protocol and data, then saves it to the SD card.
+
+#![no_main]
+#![no_std]
+
+// Required for panic handler
+extern crate flipperzero_rt;
+
+// Required for allocator
+extern crate flipperzero_alloc;
+
+extern crate alloc;
+
+use alloc::sync::Arc;
+use core::ffi::CStr;
+
+use flipperzero::furi::message_queue::MessageQueue;
+use flipperzero::furi::string::FuriString;
+use flipperzero::furi::time::FuriDuration;
+use flipperzero::println;
+use flipperzero::rfid::{ReadEvent, ReadType, Worker};
+use flipperzero_rt::{entry, manifest};
+
+manifest!(name = "Rust LF RFID example");
+entry!(main);
+
+fn main(_args: Option<&CStr>) -> i32 {
+    let mut worker = Worker::new();
+    let queue = Arc::new(MessageQueue::new(1));
+
+    println!("Hold a tag against the Flipper...");
+    let found = {
+        let sender = queue.clone();
+        let _reader = worker.read(ReadType::Auto, move |event| {
+            if let ReadEvent::Done(tag) = event {
+                let _ = sender.put(tag, FuriDuration::ZERO);
+            }
+        });
+
+        queue.get(FuriDuration::from_secs(10)).ok()
+    };
+
+    let Some(tag) = found else {
+        println!("no tag found");
+        return 1;
+    };
+
+    println!("{}: {:?}", FuriString::from(tag.name()), tag.data());
+    println!("{}", worker.dict().render_data(&tag));
+
+    match tag.save(c"/ext/lfrfid/Rust.rfid") {
+        Ok(()) => println!("saved to /ext/lfrfid/Rust.rfid"),
+        Err(e) => println!("{}", e),
+    }
+
+    0
+}
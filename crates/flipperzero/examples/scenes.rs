@@ -0,0 +1,133 @@
+//! Demonstrates use of the SceneManager with the ViewDispatcher.
+//!
+//! This app has two screens: pressing OK on the first opens the second, which counts
+//! how many times it was opened. Back goes back, and exits from the first screen.
+//!
+//! Drawing text still uses unsafe `sys` bindings, as `Canvas` has no text API yet.
+
+#![no_main]
+#![no_std]
+
+// Required for panic handler
+extern crate flipperzero_rt;
+
+// Required for allocator
+extern crate flipperzero_alloc;
+
+use core::ffi::CStr;
+use core::fmt::Write;
+
+use flipperzero::furi::string::FuriString;
+use flipperzero::gui::canvas::Canvas;
+use flipperzero::gui::input::{InputEvent, InputType, Key};
+use flipperzero::gui::scene_manager::{Scene, SceneManager, SceneManagerHandle};
+use flipperzero::gui::view::{CustomView, Model, View};
+use flipperzero::gui::view_dispatcher::{
+    DispatcherEvent, EventSender, ViewDispatcher, ViewDispatcherType,
+};
+use flipperzero_rt::{entry, manifest};
+use flipperzero_sys as sys;
+
+manifest!(name = "Rust scenes example");
+entry!(main);
+
+const OPEN: u32 = 0;
+
+flipperzero::scenes! {
+    enum AppScene {
+        Start,
+        Counter,
+    }
+}
+
+struct App {
+    opened: u32,
+}
+
+impl Scene for AppScene {
+    type App = App;
+    type Event = u32;
+    type View = u32;
+
+    fn on_enter(self, app: &mut App, scenes: &mut SceneManagerHandle<'_, Self>) {
+        if self == AppScene::Counter {
+            app.opened += 1;
+            let view = scenes
+                .view_dispatcher()
+                .view_mut::<CustomView<Text>>(AppScene::Counter as u32)
+                .unwrap();
+            *view.model().lock() = Some(app.opened);
+        }
+        scenes.view_dispatcher().switch_to_view(self as u32);
+    }
+
+    fn on_event(
+        self,
+        _app: &mut App,
+        scenes: &mut SceneManagerHandle<'_, Self>,
+        event: DispatcherEvent<u32>,
+    ) -> bool {
+        match (self, event) {
+            (AppScene::Start, DispatcherEvent::Custom(OPEN)) => {
+                scenes.next_scene(AppScene::Counter);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// A view showing how many times the counter was opened, or a prompt to press OK,
+/// which sends [`OPEN`].
+struct Text {
+    events: Option<EventSender<u32>>,
+}
+
+impl View for Text {
+    type Model = Option<u32>;
+
+    fn draw(opened: &Option<u32>, canvas: &mut Canvas) {
+        let mut text = FuriString::new();
+        let _ = match opened {
+            Some(opened) => write!(text, "Opened {opened} times"),
+            None => write!(text, "Press OK"),
+        };
+        unsafe {
+            sys::canvas_draw_str_aligned(
+                canvas.as_ptr(),
+                64,
+                32,
+                sys::AlignCenter,
+                sys::AlignCenter,
+                text.as_c_ptr(),
+            );
+        }
+    }
+
+    fn input(&mut self, _model: Model<'_, Option<u32>>, event: InputEvent) -> bool {
+        match (&self.events, event.key, event.kind) {
+            (Some(events), Key::Ok, InputType::Short) => {
+                events.send(OPEN);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+fn main(_args: Option<&CStr>) -> i32 {
+    let mut view_dispatcher = ViewDispatcher::new(ViewDispatcherType::Fullscreen);
+    let start = Text {
+        events: Some(view_dispatcher.sender()),
+    };
+    view_dispatcher.add_view(AppScene::Start as u32, CustomView::new(start, None));
+    view_dispatcher.add_view(
+        AppScene::Counter as u32,
+        CustomView::new(Text { events: None }, None),
+    );
+
+    let mut scenes = SceneManager::new(view_dispatcher);
+    scenes.run(&mut App { opened: 0 }, AppScene::Start);
+
+    0
+}
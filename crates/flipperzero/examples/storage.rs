@@ -1,6 +1,6 @@
 //! Storage example for Flipper Zero.
-//! This app writes "Hello, Rust!" to a file on the SD card. Then it opens a file browser dialog and
-//! lets the user select the file. Finally, it reads the file back and prints the contents to the console.
+//! This app writes "Hello, Rust!" to a file on the SD card and lists the files in the SD card's
+//! root directory. Then it opens a file browser dialog and lets the user select the file. Finally, it reads the file back and prints the contents to the console.
 
 #![no_main]
 #![no_std]
@@ -40,6 +40,21 @@ fn main(_args: Option<&CStr>) -> i32 {
         Err(e) => println!("couldn't open path: {}", e),
     }
 
+    // List the files next to it.
+    let storage = Storage::open();
+    match storage.read_dir(c"/ext") {
+        Ok(entries) => {
+            for entry in entries.flatten() {
+                if entry.is_dir() {
+                    println!("{}/", entry.name());
+                } else {
+                    println!("{} ({} bytes)", entry.name(), entry.size());
+                }
+            }
+        }
+        Err(e) => println!("couldn't list directory: {}", e),
+    }
+
     // Next, we'll open a file browser dialog and let the user select the file.
     let mut dialogs_app = DialogsApp::open();
     let file_browser_options = DialogFileBrowserOptions::new().set_hide_ext(false);
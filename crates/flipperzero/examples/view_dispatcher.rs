@@ -5,137 +5,96 @@
 #![no_main]
 #![no_std]
 
-extern crate alloc;
-extern crate flipperzero_alloc;
+// Required for panic handler
 extern crate flipperzero_rt;
 
-use alloc::boxed::Box;
-use core::ffi::{c_char, c_void, CStr};
-use core::ptr::NonNull;
+// Required for allocator
+extern crate flipperzero_alloc;
+
+use core::ffi::CStr;
+
 use flipperzero::furi::string::FuriString;
+use flipperzero::gui::canvas::{Align, Font};
+use flipperzero::gui::text_input::{IllegalChars, TextInput};
+use flipperzero::gui::view_dispatcher::{
+    CustomEvent, DispatcherEvent, ViewDispatcher, ViewDispatcherType, ViewId,
+};
+use flipperzero::gui::widget::Widget;
 use flipperzero_rt::{entry, manifest};
-use flipperzero_sys as sys;
-use flipperzero_sys::furi::UnsafeRecord;
 
 manifest!(name = "Rust ViewDispatcher example");
 entry!(main);
 
+#[derive(Clone, Copy, PartialEq, Eq)]
 enum AppView {
-    Widget = 0,
-    TextInput = 1,
+    Widget,
+    TextInput,
 }
 
-struct App {
-    name: [c_char; 16],
-    view_dispatcher: NonNull<sys::ViewDispatcher>,
-    widget: NonNull<sys::Widget>,
-    text_input: NonNull<sys::TextInput>,
-}
-
-impl App {
-    pub fn new() -> Box<Self> {
-        Box::new(App {
-            name: Default::default(),
-            view_dispatcher: unsafe { NonNull::new_unchecked(sys::view_dispatcher_alloc()) },
-            widget: unsafe { NonNull::new_unchecked(sys::widget_alloc()) },
-            text_input: unsafe { NonNull::new_unchecked(sys::text_input_alloc()) },
-        })
+impl ViewId for AppView {
+    fn to_raw(self) -> u32 {
+        self as u32
     }
 }
 
-impl Drop for App {
-    fn drop(&mut self) {
-        unsafe {
-            sys::view_dispatcher_free(self.view_dispatcher.as_ptr());
-            sys::widget_free(self.widget.as_ptr());
-            sys::text_input_free(self.text_input.as_ptr());
-        }
-    }
+#[derive(Clone, Copy)]
+enum AppEvent {
+    NameEntered,
 }
 
-pub unsafe extern "C" fn text_input_callback(context: *mut c_void) {
-    let app = context as *mut App;
-    let mut message = FuriString::from("Hello ");
-    unsafe {
-        message.push_c_str(CStr::from_ptr((*app).name.as_ptr()));
-        sys::widget_add_string_element(
-            (*app).widget.as_ptr(),
-            128 / 2,
-            64 / 2,
-            sys::AlignCenter,
-            sys::AlignCenter,
-            sys::FontPrimary,
-            message.as_c_ptr(),
-        );
-        sys::view_dispatcher_switch_to_view(
-            (*app).view_dispatcher.as_ptr(),
-            AppView::Widget as u32,
-        );
+impl CustomEvent for AppEvent {
+    fn to_raw(self) -> u32 {
+        self as u32
     }
-}
 
-pub unsafe extern "C" fn navigation_event_callback(context: *mut c_void) -> bool {
-    let view_dispatcher = context as *mut sys::ViewDispatcher;
-    unsafe {
-        sys::view_dispatcher_stop(view_dispatcher);
-        sys::view_dispatcher_remove_view(view_dispatcher, AppView::Widget as u32);
-        sys::view_dispatcher_remove_view(view_dispatcher, AppView::TextInput as u32);
+    fn from_raw(raw: u32) -> Option<Self> {
+        (raw == AppEvent::NameEntered as u32).then_some(AppEvent::NameEntered)
     }
-
-    true
 }
 
 fn main(_args: Option<&CStr>) -> i32 {
-    let mut app = App::new();
-
-    unsafe {
-        sys::view_dispatcher_enable_queue(app.view_dispatcher.as_ptr());
-        sys::view_dispatcher_set_event_callback_context(
-            app.view_dispatcher.as_ptr(),
-            app.view_dispatcher.as_ptr() as *mut c_void,
-        );
-        sys::view_dispatcher_set_navigation_event_callback(
-            app.view_dispatcher.as_ptr(),
-            Some(navigation_event_callback),
-        );
-        sys::view_dispatcher_add_view(
-            app.view_dispatcher.as_ptr(),
-            AppView::Widget as u32,
-            sys::widget_get_view(app.widget.as_ptr()),
-        );
-        sys::view_dispatcher_add_view(
-            app.view_dispatcher.as_ptr(),
-            AppView::TextInput as u32,
-            sys::text_input_get_view(app.text_input.as_ptr()),
-        );
-    }
-
-    unsafe {
-        let gui = UnsafeRecord::open(c"gui");
-        sys::view_dispatcher_attach_to_gui(
-            app.view_dispatcher.as_ptr(),
-            gui.as_ptr(),
-            sys::ViewDispatcherTypeFullscreen,
-        );
-
-        sys::text_input_reset(app.text_input.as_ptr());
-        sys::text_input_set_header_text(app.text_input.as_ptr(), c"Enter your name".as_ptr());
-
-        sys::text_input_set_result_callback(
-            app.text_input.as_ptr(),
-            Some(text_input_callback),
-            &*app as *const App as *mut c_void,
-            app.name.as_mut_ptr(),
-            app.name.len(),
-            true,
-        );
-
-        sys::view_dispatcher_switch_to_view(
-            app.view_dispatcher.as_ptr(),
-            AppView::TextInput as u32,
-        );
-        sys::view_dispatcher_run(app.view_dispatcher.as_ptr());
-    }
+    let mut view_dispatcher =
+        ViewDispatcher::<AppEvent, AppView>::new(ViewDispatcherType::Fullscreen);
+
+    let mut text_input = TextInput::new(15);
+    text_input.set_header(c"Enter your name");
+    text_input.set_minimum_length(1);
+    text_input.set_illegal_chars(IllegalChars::Custom(c"!?"));
+    text_input.set_validator(|name| match name.to_bytes() {
+        b"Flipper" => Err(FuriString::from("That's my name!")),
+        _ => Ok(()),
+    });
+    let events = view_dispatcher.sender();
+    text_input.set_result_callback(c"", true, move |_| events.send(AppEvent::NameEntered));
+
+    view_dispatcher.add_view(AppView::Widget, Widget::new());
+    view_dispatcher.add_view(AppView::TextInput, text_input);
+    view_dispatcher.switch_to_view(AppView::TextInput);
+
+    view_dispatcher.run(|view_dispatcher, event| match event {
+        DispatcherEvent::Custom(AppEvent::NameEntered) => {
+            let mut message = FuriString::from("Hello ");
+            if let Some(text_input) = view_dispatcher.view::<TextInput>(AppView::TextInput) {
+                message.push_c_str(text_input.text());
+            }
+            if let Some(widget) = view_dispatcher.view_mut::<Widget>(AppView::Widget) {
+                widget.reset();
+                widget.add_string(
+                    128 / 2,
+                    64 / 2,
+                    Align::Center,
+                    Align::Center,
+                    Font::Primary,
+                    message.as_c_str(),
+                );
+            }
+            view_dispatcher.switch_to_view(AppView::Widget);
+            true
+        }
+        // Stop the dispatcher.
+        DispatcherEvent::Back => false,
+        DispatcherEvent::Tick => true,
+    });
 
     0
 }
@@ -353,6 +353,12 @@ impl<'a> DialogFileBrowserOptions<'a> {
         self
     }
 
+    /// Returns the raw options.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn as_sys(&self) -> &sys::DialogsFileBrowserOptions {
+        &self.data
+    }
+
     /// Set callback function and context for providing custom icon and entry name.
     pub fn set_item_loader_callback(
         mut self,
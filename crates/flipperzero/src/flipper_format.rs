@@ -0,0 +1,727 @@
+//! Flipper Format key-value files.
+//!
+//! Flipper Format is the line-based `Key: value` text format used for `.sub`, `.ir`,
+//! `.nfc`, `.rfid` and settings files on the device:
+//!
+//! ```text
+//! Filetype: Flipper SubGhz Key File
+//! Version: 1
+//! # Comment
+//! Frequency: 433920000
+//! Key: 00 00 00 00 00 12 34 56
+//! ```
+//!
+//! Reads search for the key starting at the current position, so keys should be read in
+//! the order they appear in the file, or [`FlipperFormat::rewind`] should be called first.
+
+use core::ffi::{c_char, CStr};
+use core::fmt;
+use core::ptr::NonNull;
+
+use flipperzero_sys as sys;
+
+use crate::furi::string::FuriString;
+use crate::storage::Storage;
+
+/// How [`FlipperFormat::open`] opens a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpenMode {
+    /// Open an existing file, failing if it doesn't exist.
+    Existing,
+    /// Open an existing file for writing, appending to its end.
+    Append,
+    /// Create a new file, truncating it if it already exists.
+    Always,
+    /// Create a new file, failing if it already exists.
+    New,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Backing {
+    File,
+    BufferedFile,
+    String,
+}
+
+/// Flipper Format reader and writer, backed by a file or by memory.
+pub struct FlipperFormat {
+    raw: NonNull<sys::FlipperFormat>,
+    backing: Backing,
+    _storage: Option<Storage>,
+}
+
+impl FlipperFormat {
+    /// Creates an empty memory-backed Flipper Format.
+    pub fn string() -> Self {
+        // SAFETY: Alloc always returns a valid non-null pointer or `furi_panic`s.
+        let raw = unsafe { NonNull::new_unchecked(sys::flipper_format_string_alloc()) };
+
+        Self {
+            raw,
+            backing: Backing::String,
+            _storage: None,
+        }
+    }
+
+    /// Creates a file-backed Flipper Format.
+    ///
+    /// A file must be opened with [`FlipperFormat::open`] before it can be used.
+    pub fn file(storage: &Storage) -> Self {
+        // SAFETY: Alloc always returns a valid non-null pointer or `furi_panic`s.
+        let raw =
+            unsafe { NonNull::new_unchecked(sys::flipper_format_file_alloc(storage.as_ptr())) };
+
+        Self {
+            raw,
+            backing: Backing::File,
+            _storage: Some(storage.clone()),
+        }
+    }
+
+    /// Creates a file-backed Flipper Format that buffers file access.
+    ///
+    /// Buffered files can only be opened with [`OpenMode::Existing`] or
+    /// [`OpenMode::Always`].
+    pub fn buffered_file(storage: &Storage) -> Self {
+        // SAFETY: Alloc always returns a valid non-null pointer or `furi_panic`s.
+        let raw = unsafe {
+            NonNull::new_unchecked(sys::flipper_format_buffered_file_alloc(storage.as_ptr()))
+        };
+
+        Self {
+            raw,
+            backing: Backing::BufferedFile,
+            _storage: Some(storage.clone()),
+        }
+    }
+
+    /// Obtain raw Flipper Format.
+    ///
+    /// This pointer must not be `free`d or otherwise invalidated.
+    /// It must not be referenced after [`FlipperFormat`] has been dropped.
+    #[inline]
+    pub fn as_ptr(&self) -> *mut sys::FlipperFormat {
+        self.raw.as_ptr()
+    }
+
+    /// Opens the file at `path`.
+    ///
+    /// Fails if the Flipper Format is memory-backed.
+    pub fn open(&mut self, path: &CStr, mode: OpenMode) -> Result<(), Error> {
+        let raw = self.as_ptr();
+        let path = path.as_ptr();
+
+        let opened = unsafe {
+            match (self.backing, mode) {
+                (Backing::File, OpenMode::Existing) => {
+                    sys::flipper_format_file_open_existing(raw, path)
+                }
+                (Backing::File, OpenMode::Append) => {
+                    sys::flipper_format_file_open_append(raw, path)
+                }
+                (Backing::File, OpenMode::Always) => {
+                    sys::flipper_format_file_open_always(raw, path)
+                }
+                (Backing::File, OpenMode::New) => sys::flipper_format_file_open_new(raw, path),
+                (Backing::BufferedFile, OpenMode::Existing) => {
+                    sys::flipper_format_buffered_file_open_existing(raw, path)
+                }
+                (Backing::BufferedFile, OpenMode::Always) => {
+                    sys::flipper_format_buffered_file_open_always(raw, path)
+                }
+                _ => false,
+            }
+        };
+
+        if opened {
+            Ok(())
+        } else {
+            Err(Error::Open)
+        }
+    }
+
+    /// Closes the file.
+    ///
+    /// Files are also closed when the Flipper Format is dropped.
+    pub fn close(&mut self) -> Result<(), Error> {
+        let closed = unsafe {
+            match self.backing {
+                Backing::File => sys::flipper_format_file_close(self.as_ptr()),
+                Backing::BufferedFile => sys::flipper_format_buffered_file_close(self.as_ptr()),
+                Backing::String => true,
+            }
+        };
+
+        if closed {
+            Ok(())
+        } else {
+            Err(Error::Io)
+        }
+    }
+
+    /// Sets strict mode, in which reads fail instead of skipping over malformed lines.
+    pub fn set_strict_mode(&mut self, strict: bool) {
+        unsafe { sys::flipper_format_set_strict_mode(self.as_ptr(), strict) }
+    }
+
+    /// Moves the read/write position back to the start.
+    pub fn rewind(&mut self) -> Result<(), Error> {
+        if unsafe { sys::flipper_format_rewind(self.as_ptr()) } {
+            Ok(())
+        } else {
+            Err(Error::Io)
+        }
+    }
+
+    /// Moves the read/write position to the end.
+    pub fn seek_to_end(&mut self) -> Result<(), Error> {
+        if unsafe { sys::flipper_format_seek_to_end(self.as_ptr()) } {
+            Ok(())
+        } else {
+            Err(Error::Io)
+        }
+    }
+
+    /// Checks whether `key` exists anywhere in the file.
+    ///
+    /// The read/write position is left unchanged.
+    pub fn key_exists(&mut self, key: &CStr) -> bool {
+        unsafe { sys::flipper_format_key_exist(self.as_ptr(), key.as_ptr()) }
+    }
+
+    /// Counts the values stored under the next occurrence of `key`.
+    pub fn value_count(&mut self, key: &CStr) -> Result<usize, Error> {
+        let mut count = 0;
+        self.read_with(key, |raw, key| unsafe {
+            sys::flipper_format_get_value_count(raw, key, &mut count)
+        })?;
+
+        Ok(count as usize)
+    }
+
+    /// Reads the `Filetype` and `Version` header.
+    pub fn read_header(&mut self) -> Result<(FuriString, u32), Error> {
+        let filetype = self.read_string(c"Filetype")?;
+        let version = self.read_u32(c"Version")?;
+
+        Ok((filetype, version))
+    }
+
+    /// Writes the `Filetype` and `Version` header.
+    pub fn write_header(&mut self, filetype: &CStr, version: u32) -> Result<(), Error> {
+        self.write_string(c"Filetype", filetype)?;
+        self.write_u32(c"Version", version)
+    }
+
+    /// Reads the string value of `key`.
+    pub fn read_string(&mut self, key: &CStr) -> Result<FuriString, Error> {
+        let mut value = FuriString::new();
+        self.read_with(key, |raw, key| unsafe {
+            sys::flipper_format_read_string(raw, key, value.as_mut_ptr())
+        })?;
+
+        Ok(value)
+    }
+
+    /// Reads the unsigned integer value of `key`.
+    pub fn read_u32(&mut self, key: &CStr) -> Result<u32, Error> {
+        let mut value = 0;
+        self.read_u32_array(key, core::slice::from_mut(&mut value))?;
+        Ok(value)
+    }
+
+    /// Reads `values.len()` unsigned integer values of `key`.
+    pub fn read_u32_array(&mut self, key: &CStr, values: &mut [u32]) -> Result<(), Error> {
+        let len = array_len(key, values.len(), Error::InvalidValue)?;
+        self.read_with(key, |raw, key| unsafe {
+            sys::flipper_format_read_uint32(raw, key, values.as_mut_ptr(), len)
+        })
+    }
+
+    /// Reads the signed integer value of `key`.
+    pub fn read_i32(&mut self, key: &CStr) -> Result<i32, Error> {
+        let mut value = 0;
+        self.read_i32_array(key, core::slice::from_mut(&mut value))?;
+        Ok(value)
+    }
+
+    /// Reads `values.len()` signed integer values of `key`.
+    pub fn read_i32_array(&mut self, key: &CStr, values: &mut [i32]) -> Result<(), Error> {
+        let len = array_len(key, values.len(), Error::InvalidValue)?;
+        self.read_with(key, |raw, key| unsafe {
+            sys::flipper_format_read_int32(raw, key, values.as_mut_ptr(), len)
+        })
+    }
+
+    /// Reads the floating-point value of `key`.
+    pub fn read_float(&mut self, key: &CStr) -> Result<f32, Error> {
+        let mut value = 0.0;
+        self.read_float_array(key, core::slice::from_mut(&mut value))?;
+        Ok(value)
+    }
+
+    /// Reads `values.len()` floating-point values of `key`.
+    pub fn read_float_array(&mut self, key: &CStr, values: &mut [f32]) -> Result<(), Error> {
+        let len = array_len(key, values.len(), Error::InvalidValue)?;
+        self.read_with(key, |raw, key| unsafe {
+            sys::flipper_format_read_float(raw, key, values.as_mut_ptr(), len)
+        })
+    }
+
+    /// Reads the boolean (`true` or `false`) value of `key`.
+    pub fn read_bool(&mut self, key: &CStr) -> Result<bool, Error> {
+        let mut value = false;
+        self.read_bool_array(key, core::slice::from_mut(&mut value))?;
+        Ok(value)
+    }
+
+    /// Reads `values.len()` boolean values of `key`.
+    pub fn read_bool_array(&mut self, key: &CStr, values: &mut [bool]) -> Result<(), Error> {
+        let len = array_len(key, values.len(), Error::InvalidValue)?;
+        self.read_with(key, |raw, key| unsafe {
+            sys::flipper_format_read_bool(raw, key, values.as_mut_ptr(), len)
+        })
+    }
+
+    /// Reads `bytes.len()` hex-encoded bytes (e.g. `DE AD BE EF`) of `key`.
+    pub fn read_hex(&mut self, key: &CStr, bytes: &mut [u8]) -> Result<(), Error> {
+        let len = array_len(key, bytes.len(), Error::InvalidValue)?;
+        self.read_with(key, |raw, key| unsafe {
+            sys::flipper_format_read_hex(raw, key, bytes.as_mut_ptr(), len)
+        })
+    }
+
+    /// Writes a string value.
+    pub fn write_string(&mut self, key: &CStr, value: &CStr) -> Result<(), Error> {
+        self.write_with(key, |raw, key| unsafe {
+            sys::flipper_format_write_string_cstr(raw, key, value.as_ptr())
+        })
+    }
+
+    /// Writes an unsigned integer value.
+    pub fn write_u32(&mut self, key: &CStr, value: u32) -> Result<(), Error> {
+        self.write_u32_array(key, &[value])
+    }
+
+    /// Writes an array of unsigned integer values.
+    pub fn write_u32_array(&mut self, key: &CStr, values: &[u32]) -> Result<(), Error> {
+        let len = array_len(key, values.len(), Error::Write)?;
+        self.write_with(key, |raw, key| unsafe {
+            sys::flipper_format_write_uint32(raw, key, values.as_ptr(), len)
+        })
+    }
+
+    /// Writes a signed integer value.
+    pub fn write_i32(&mut self, key: &CStr, value: i32) -> Result<(), Error> {
+        self.write_i32_array(key, &[value])
+    }
+
+    /// Writes an array of signed integer values.
+    pub fn write_i32_array(&mut self, key: &CStr, values: &[i32]) -> Result<(), Error> {
+        let len = array_len(key, values.len(), Error::Write)?;
+        self.write_with(key, |raw, key| unsafe {
+            sys::flipper_format_write_int32(raw, key, values.as_ptr(), len)
+        })
+    }
+
+    /// Writes a floating-point value.
+    pub fn write_float(&mut self, key: &CStr, value: f32) -> Result<(), Error> {
+        self.write_float_array(key, &[value])
+    }
+
+    /// Writes an array of floating-point values.
+    pub fn write_float_array(&mut self, key: &CStr, values: &[f32]) -> Result<(), Error> {
+        let len = array_len(key, values.len(), Error::Write)?;
+        self.write_with(key, |raw, key| unsafe {
+            sys::flipper_format_write_float(raw, key, values.as_ptr(), len)
+        })
+    }
+
+    /// Writes a boolean value.
+    pub fn write_bool(&mut self, key: &CStr, value: bool) -> Result<(), Error> {
+        self.write_bool_array(key, &[value])
+    }
+
+    /// Writes an array of boolean values.
+    pub fn write_bool_array(&mut self, key: &CStr, values: &[bool]) -> Result<(), Error> {
+        let len = array_len(key, values.len(), Error::Write)?;
+        self.write_with(key, |raw, key| unsafe {
+            sys::flipper_format_write_bool(raw, key, values.as_ptr(), len)
+        })
+    }
+
+    /// Writes bytes as hex (e.g. `DE AD BE EF`).
+    pub fn write_hex(&mut self, key: &CStr, bytes: &[u8]) -> Result<(), Error> {
+        let len = array_len(key, bytes.len(), Error::Write)?;
+        self.write_with(key, |raw, key| unsafe {
+            sys::flipper_format_write_hex(raw, key, bytes.as_ptr(), len)
+        })
+    }
+
+    /// Writes a `# comment` line.
+    pub fn write_comment(&mut self, comment: &CStr) -> Result<(), Error> {
+        if unsafe { sys::flipper_format_write_comment_cstr(self.as_ptr(), comment.as_ptr()) } {
+            Ok(())
+        } else {
+            Err(Error::Io)
+        }
+    }
+
+    /// Writes an empty line.
+    pub fn write_empty_line(&mut self) -> Result<(), Error> {
+        if unsafe { sys::flipper_format_write_empty_line(self.as_ptr()) } {
+            Ok(())
+        } else {
+            Err(Error::Io)
+        }
+    }
+
+    /// Removes the first occurrence of `key` and its value.
+    ///
+    /// The read/write position is left where the deleted line was.
+    pub fn delete_key(&mut self, key: &CStr) -> Result<(), Error> {
+        if unsafe { sys::flipper_format_delete_key(self.as_ptr(), key.as_ptr()) } {
+            Ok(())
+        } else {
+            Err(Error::MissingKey(FuriString::from(key)))
+        }
+    }
+
+    fn read_with(
+        &mut self,
+        key: &CStr,
+        read: impl FnOnce(*mut sys::FlipperFormat, *const c_char) -> bool,
+    ) -> Result<(), Error> {
+        let position = unsafe { sys::flipper_format_tell(self.as_ptr()) };
+
+        if read(self.as_ptr(), key.as_ptr()) {
+            return Ok(());
+        }
+
+        // Tell a missing key apart from a malformed value by looking for the key again,
+        // starting from where the failed read did.
+        let mut count = 0;
+        let found = unsafe {
+            sys::flipper_format_seek(
+                self.as_ptr(),
+                position as i32,
+                sys::FlipperFormatOffsetFromStart,
+            ) && sys::flipper_format_get_value_count(self.as_ptr(), key.as_ptr(), &mut count)
+        };
+        unsafe {
+            sys::flipper_format_seek(
+                self.as_ptr(),
+                position as i32,
+                sys::FlipperFormatOffsetFromStart,
+            )
+        };
+
+        if found {
+            Err(Error::InvalidValue(FuriString::from(key)))
+        } else {
+            Err(Error::MissingKey(FuriString::from(key)))
+        }
+    }
+
+    fn write_with(
+        &mut self,
+        key: &CStr,
+        write: impl FnOnce(*mut sys::FlipperFormat, *const c_char) -> bool,
+    ) -> Result<(), Error> {
+        if write(self.as_ptr(), key.as_ptr()) {
+            Ok(())
+        } else {
+            Err(Error::Write(FuriString::from(key)))
+        }
+    }
+}
+
+/// Converts an array length to the firmware's `u16` value count.
+fn array_len(key: &CStr, len: usize, error: fn(FuriString) -> Error) -> Result<u16, Error> {
+    u16::try_from(len).map_err(|_| error(FuriString::from(key)))
+}
+
+impl Drop for FlipperFormat {
+    fn drop(&mut self) {
+        // Also closes the file, if one is open.
+        unsafe { sys::flipper_format_free(self.as_ptr()) }
+    }
+}
+
+/// Flipper Format errors.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The file could not be opened.
+    Open,
+    /// Seeking in, or closing, the underlying stream failed.
+    Io,
+    /// The key was not found.
+    MissingKey(FuriString),
+    /// The key was found, but its value could not be parsed as the requested type.
+    InvalidValue(FuriString),
+    /// The key and its value could not be written.
+    Write(FuriString),
+}
+
+impl Error {
+    /// Description associated with [`Error`].
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Open => "failed to open file",
+            Self::Io => "stream operation failed",
+            Self::MissingKey(_) => "missing key",
+            Self::InvalidValue(_) => "invalid value for key",
+            Self::Write(_) => "failed to write key",
+        }
+    }
+
+    /// The key this error refers to, if any.
+    pub fn key(&self) -> Option<&FuriString> {
+        match self {
+            Self::MissingKey(key) | Self::InvalidValue(key) | Self::Write(key) => Some(key),
+            Self::Open | Self::Io => None,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.description())?;
+        if let Some(key) = self.key() {
+            write!(f, " `{}`", key)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl ufmt::uDisplay for Error {
+    fn fmt<W>(&self, f: &mut ufmt::Formatter<'_, W>) -> Result<(), W::Error>
+    where
+        W: ufmt::uWrite + ?Sized,
+    {
+        f.write_str(self.description())?;
+        if let Some(key) = self.key() {
+            ufmt::uwrite!(f, " `{}`", key)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "flipperzero-format")]
+#[cfg_attr(docsrs, doc(cfg(feature = "flipperzero-format")))]
+mod backend {
+    use alloc::string::String;
+
+    use flipperzero_format::{Backend, Error};
+
+    use super::FlipperFormat;
+    use crate::furi::string::FuriString;
+
+    impl From<super::Error> for Error {
+        fn from(error: super::Error) -> Self {
+            fn string(key: FuriString) -> String {
+                String::from_utf8_lossy(key.to_bytes()).into_owned()
+            }
+
+            match error {
+                super::Error::Open | super::Error::Io => Error::Io,
+                super::Error::MissingKey(key) => Error::MissingKey(string(key)),
+                super::Error::InvalidValue(key) => Error::InvalidValue(string(key)),
+                super::Error::Write(key) => Error::Write(string(key)),
+            }
+        }
+    }
+
+    impl Backend for FlipperFormat {
+        fn rewind(&mut self) -> Result<(), Error> {
+            Ok(FlipperFormat::rewind(self)?)
+        }
+
+        fn key_exists(&mut self, key: &str) -> bool {
+            FlipperFormat::key_exists(self, FuriString::from(key).as_c_str())
+        }
+
+        fn value_count(&mut self, key: &str) -> Result<usize, Error> {
+            Ok(FlipperFormat::value_count(
+                self,
+                FuriString::from(key).as_c_str(),
+            )?)
+        }
+
+        fn read_string(&mut self, key: &str) -> Result<String, Error> {
+            let value = FlipperFormat::read_string(self, FuriString::from(key).as_c_str())?;
+
+            String::from_utf8(value.to_bytes().to_vec())
+                .map_err(|_| Error::InvalidValue(String::from(key)))
+        }
+
+        fn read_u32_array(&mut self, key: &str, values: &mut [u32]) -> Result<(), Error> {
+            Ok(FlipperFormat::read_u32_array(
+                self,
+                FuriString::from(key).as_c_str(),
+                values,
+            )?)
+        }
+
+        fn read_i32_array(&mut self, key: &str, values: &mut [i32]) -> Result<(), Error> {
+            Ok(FlipperFormat::read_i32_array(
+                self,
+                FuriString::from(key).as_c_str(),
+                values,
+            )?)
+        }
+
+        fn read_float_array(&mut self, key: &str, values: &mut [f32]) -> Result<(), Error> {
+            Ok(FlipperFormat::read_float_array(
+                self,
+                FuriString::from(key).as_c_str(),
+                values,
+            )?)
+        }
+
+        fn read_bool_array(&mut self, key: &str, values: &mut [bool]) -> Result<(), Error> {
+            Ok(FlipperFormat::read_bool_array(
+                self,
+                FuriString::from(key).as_c_str(),
+                values,
+            )?)
+        }
+
+        fn read_hex(&mut self, key: &str, bytes: &mut [u8]) -> Result<(), Error> {
+            Ok(FlipperFormat::read_hex(
+                self,
+                FuriString::from(key).as_c_str(),
+                bytes,
+            )?)
+        }
+
+        fn write_string(&mut self, key: &str, value: &str) -> Result<(), Error> {
+            Ok(FlipperFormat::write_string(
+                self,
+                FuriString::from(key).as_c_str(),
+                FuriString::from(value).as_c_str(),
+            )?)
+        }
+
+        fn write_u32_array(&mut self, key: &str, values: &[u32]) -> Result<(), Error> {
+            Ok(FlipperFormat::write_u32_array(
+                self,
+                FuriString::from(key).as_c_str(),
+                values,
+            )?)
+        }
+
+        fn write_i32_array(&mut self, key: &str, values: &[i32]) -> Result<(), Error> {
+            Ok(FlipperFormat::write_i32_array(
+                self,
+                FuriString::from(key).as_c_str(),
+                values,
+            )?)
+        }
+
+        fn write_float_array(&mut self, key: &str, values: &[f32]) -> Result<(), Error> {
+            Ok(FlipperFormat::write_float_array(
+                self,
+                FuriString::from(key).as_c_str(),
+                values,
+            )?)
+        }
+
+        fn write_bool_array(&mut self, key: &str, values: &[bool]) -> Result<(), Error> {
+            Ok(FlipperFormat::write_bool_array(
+                self,
+                FuriString::from(key).as_c_str(),
+                values,
+            )?)
+        }
+
+        fn write_hex(&mut self, key: &str, bytes: &[u8]) -> Result<(), Error> {
+            Ok(FlipperFormat::write_hex(
+                self,
+                FuriString::from(key).as_c_str(),
+                bytes,
+            )?)
+        }
+
+        fn delete_key(&mut self, key: &str) -> Result<(), Error> {
+            Ok(FlipperFormat::delete_key(
+                self,
+                FuriString::from(key).as_c_str(),
+            )?)
+        }
+    }
+}
+
+#[flipperzero_test::tests]
+mod tests {
+    use super::{Error, FlipperFormat};
+    use crate::furi::string::FuriString;
+
+    #[test]
+    fn write_and_read_back() {
+        let mut ff = FlipperFormat::string();
+        ff.write_header(c"Rust Test File", 3).unwrap();
+        ff.write_comment(c"values").unwrap();
+        ff.write_string(c"Name", c"flipper").unwrap();
+        ff.write_u32(c"Frequency", 433_920_000).unwrap();
+        ff.write_i32_array(c"Offsets", &[-1, 0, 1]).unwrap();
+        ff.write_float(c"Ratio", 0.5).unwrap();
+        ff.write_bool(c"Enabled", true).unwrap();
+        ff.write_hex(c"Key", &[0xDE, 0xAD, 0xBE, 0xEF]).unwrap();
+
+        ff.rewind().unwrap();
+        let (filetype, version) = ff.read_header().unwrap();
+        assert_eq!(filetype, "Rust Test File");
+        assert_eq!(version, 3);
+        assert_eq!(ff.read_string(c"Name").unwrap(), "flipper");
+        assert_eq!(ff.read_u32(c"Frequency").unwrap(), 433_920_000);
+
+        assert_eq!(ff.value_count(c"Offsets").unwrap(), 3);
+        let mut offsets = [0; 3];
+        ff.read_i32_array(c"Offsets", &mut offsets).unwrap();
+        assert_eq!(offsets, [-1, 0, 1]);
+
+        assert_eq!(ff.read_float(c"Ratio").unwrap(), 0.5);
+        assert!(ff.read_bool(c"Enabled").unwrap());
+
+        let mut bytes = [0; 4];
+        ff.read_hex(c"Key", &mut bytes).unwrap();
+        assert_eq!(bytes, [0xDE, 0xAD, 0xBE, 0xEF]);
+    }
+
+    #[test]
+    fn errors_name_the_key() {
+        let mut ff = FlipperFormat::string();
+        ff.write_string(c"Count", c"many").unwrap();
+        ff.rewind().unwrap();
+
+        assert_eq!(
+            ff.read_u32(c"Count"),
+            Err(Error::InvalidValue(FuriString::from("Count")))
+        );
+        assert_eq!(
+            ff.read_u32(c"Missing"),
+            Err(Error::MissingKey(FuriString::from("Missing")))
+        );
+    }
+
+    #[test]
+    fn delete_key() {
+        let mut ff = FlipperFormat::string();
+        ff.write_u32(c"A", 1).unwrap();
+        ff.write_u32(c"B", 2).unwrap();
+
+        assert!(ff.key_exists(c"A"));
+        ff.rewind().unwrap();
+        ff.delete_key(c"A").unwrap();
+        assert!(!ff.key_exists(c"A"));
+        assert!(ff.key_exists(c"B"));
+
+        assert_eq!(
+            ff.delete_key(c"A"),
+            Err(Error::MissingKey(FuriString::from("A")))
+        );
+    }
+}
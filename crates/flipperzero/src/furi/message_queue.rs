@@ -83,6 +83,17 @@ impl<M: Sized> MessageQueue<M> {
     }
 }
 
+// SAFETY:
+// The Furi API does not impose any restrictions on moving a message queue between threads.
+// Messages are moved into and out of the queue, so they must themselves be `Send`.
+unsafe impl<M: Send> Send for MessageQueue<M> {}
+
+// SAFETY:
+// Furi message queues may be accessed from multiple threads (and from interrupts) at once.
+// `put` and `get` move whole messages, so sharing a queue only ever transfers ownership of
+// `Send` messages between threads.
+unsafe impl<M: Send> Sync for MessageQueue<M> {}
+
 impl<M: Sized> Drop for MessageQueue<M> {
     fn drop(&mut self) {
         // Drain any elements from the message queue, so any
@@ -6,6 +6,7 @@ pub mod io;
 pub mod kernel;
 pub mod log;
 pub mod message_queue;
+pub mod pubsub;
 pub mod rng;
 pub mod stream_buffer;
 pub mod string;
@@ -0,0 +1,144 @@
+//! Furi publish/subscribe.
+//!
+//! Services such as storage and input publish events through a `FuriPubSub`. Most apps
+//! subscribe through a typed wrapper, like
+//! [`Storage::subscribe`](crate::storage::Storage::subscribe).
+
+use core::ffi::c_void;
+use core::marker::PhantomData;
+use core::ptr::NonNull;
+
+use flipperzero_sys as sys;
+use flipperzero_sys::furi::FuriBox;
+
+/// A typed message published on a `FuriPubSub`.
+pub trait Message: Sized {
+    /// The message as published by the SDK.
+    type Raw;
+
+    /// Converts a published message, returning `None` for messages that should not be
+    /// passed on to subscribers, such as unknown event types.
+    fn from_raw(raw: &Self::Raw) -> Option<Self>;
+}
+
+/// A subscription to a `FuriPubSub`, which calls a closure with every message
+/// published until it is dropped.
+///
+/// The closure is called on the publisher's thread, one message at a time. It should
+/// return quickly, since the publisher waits for every subscriber; forward the message
+/// to a [`MessageQueue`](crate::furi::message_queue::MessageQueue) to handle it on
+/// another thread.
+///
+/// The closure must be `'static`, because a subscription that is leaked with
+/// [`mem::forget`](core::mem::forget) is never unsubscribed.
+pub struct Subscription<'a, M, F>
+where
+    M: Message,
+    F: FnMut(M) + Send + 'static,
+{
+    pubsub: NonNull<sys::FuriPubSub>,
+    raw: NonNull<sys::FuriPubSubSubscription>,
+    _callback: FuriBox<F>,
+    _lifetime: PhantomData<&'a ()>,
+    _message: PhantomData<fn(M)>,
+}
+
+impl<M, F> Subscription<'_, M, F>
+where
+    M: Message,
+    F: FnMut(M) + Send + 'static,
+{
+    /// Subscribes `callback` to `pubsub`.
+    ///
+    /// # Safety
+    ///
+    /// `pubsub` must remain valid for `'a`, or forever if the subscription is leaked,
+    /// and every message published on it must be a valid `M::Raw`.
+    pub unsafe fn new(pubsub: NonNull<sys::FuriPubSub>, callback: F) -> Self {
+        let mut callback = FuriBox::new(callback);
+        let raw = unsafe {
+            sys::furi_pubsub_subscribe(
+                pubsub.as_ptr(),
+                Some(pubsub_callback::<M, F>),
+                FuriBox::as_mut_ptr(&mut callback).cast(),
+            )
+        };
+
+        Self {
+            pubsub,
+            // SAFETY: Subscribe always returns a valid non-null pointer or `furi_panic`s.
+            raw: unsafe { NonNull::new_unchecked(raw) },
+            _callback: callback,
+            _lifetime: PhantomData,
+            _message: PhantomData,
+        }
+    }
+}
+
+impl<M, F> Drop for Subscription<'_, M, F>
+where
+    M: Message,
+    F: FnMut(M) + Send + 'static,
+{
+    fn drop(&mut self) {
+        // After this, the callback is no longer called, so it can be freed.
+        unsafe { sys::furi_pubsub_unsubscribe(self.pubsub.as_ptr(), self.raw.as_ptr()) };
+    }
+}
+
+unsafe extern "C" fn pubsub_callback<M, F>(message: *const c_void, context: *mut c_void)
+where
+    M: Message,
+    F: FnMut(M),
+{
+    let callback = unsafe { &mut *context.cast::<F>() };
+    if let Some(message) = M::from_raw(unsafe { &*message.cast::<M::Raw>() }) {
+        callback(message);
+    }
+}
+
+#[flipperzero_test::tests]
+mod tests {
+    use core::ptr::{self, NonNull};
+    use core::sync::atomic::{AtomicU32, Ordering};
+
+    use flipperzero_sys as sys;
+
+    use super::{Message, Subscription};
+
+    struct Odd(u32);
+
+    impl Message for Odd {
+        type Raw = u32;
+
+        fn from_raw(raw: &u32) -> Option<Self> {
+            (raw % 2 == 1).then_some(Odd(*raw))
+        }
+    }
+
+    static TOTAL: AtomicU32 = AtomicU32::new(0);
+
+    #[test]
+    fn publish_and_unsubscribe() {
+        let pubsub = unsafe { NonNull::new_unchecked(sys::furi_pubsub_alloc()) };
+        let publish = |mut message: u32| unsafe {
+            sys::furi_pubsub_publish(pubsub.as_ptr(), ptr::from_mut(&mut message).cast())
+        };
+
+        let subscription = unsafe {
+            Subscription::new(pubsub, |Odd(message)| {
+                TOTAL.fetch_add(message, Ordering::Relaxed);
+            })
+        };
+        for message in [1, 2, 3, 4] {
+            publish(message);
+        }
+        assert_eq!(TOTAL.load(Ordering::Relaxed), 4);
+
+        drop(subscription);
+        publish(5);
+        assert_eq!(TOTAL.load(Ordering::Relaxed), 4);
+
+        unsafe { sys::furi_pubsub_free(pubsub.as_ptr()) };
+    }
+}
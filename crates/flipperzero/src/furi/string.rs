@@ -87,6 +87,18 @@ impl FuriString {
         s.0
     }
 
+    /// Construct a [`FuriString`] from a raw [`sys::FuriString`], taking ownership of
+    /// it.
+    ///
+    /// # Safety
+    ///
+    /// `raw` must be a valid string that is not owned elsewhere, such as one returned
+    /// by [`FuriString::into_raw`].
+    #[inline]
+    pub unsafe fn from_raw(raw: NonNull<sys::FuriString>) -> Self {
+        FuriString(raw)
+    }
+
     /// Extracts a pointer to a raw zero-terminated string
     /// containing the entire string slice.
     #[inline]
@@ -0,0 +1,205 @@
+//! Button menu, a vertical list of buttons.
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::cell::Cell;
+use core::ffi::{c_void, CStr};
+use core::ptr::NonNull;
+
+use flipperzero_sys as sys;
+use flipperzero_sys::furi::FuriBox;
+
+use super::input::InputType;
+use super::view::AsView;
+use crate::furi::string::FuriString;
+
+/// How a [`ButtonMenu`] button reports presses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ButtonMenuItemType {
+    /// A button that is held down: its closure is called with [`InputType::Press`]
+    /// and [`InputType::Release`].
+    Common,
+    /// A button that is clicked: its closure is called with [`InputType::Short`].
+    Control,
+}
+
+impl ButtonMenuItemType {
+    pub fn to_sys(&self) -> sys::ButtonMenuItemType {
+        match self {
+            Self::Common => sys::ButtonMenuItemTypeCommon,
+            Self::Control => sys::ButtonMenuItemTypeControl,
+        }
+    }
+}
+
+/// The context of a button's callback.
+struct Item {
+    /// The SDK keeps a pointer to the label.
+    label: FuriString,
+    selected: NonNull<Cell<Option<u32>>>,
+    callback: Box<dyn FnMut(InputType)>,
+}
+
+/// A vertical list of buttons, each calling a closure when OK is pressed on it.
+///
+/// Buttons are numbered in the order they are added, from zero.
+///
+/// The closures are called on the thread running the
+/// [`ViewDispatcher`](super::ViewDispatcher) the menu is added to.
+pub struct ButtonMenu {
+    raw: NonNull<sys::ButtonMenu>,
+    /// The SDK keeps a pointer to the header.
+    header: Option<FuriString>,
+    items: Vec<FuriBox<Item>>,
+    selected: FuriBox<Cell<Option<u32>>>,
+}
+
+impl ButtonMenu {
+    /// Creates an empty button menu.
+    pub fn new() -> Self {
+        Self {
+            // SAFETY: Alloc always returns a valid non-null pointer or `furi_panic`s.
+            raw: unsafe { NonNull::new_unchecked(sys::button_menu_alloc()) },
+            header: None,
+            items: Vec::new(),
+            selected: FuriBox::new(Cell::new(None)),
+        }
+    }
+
+    /// Obtain raw Furi button menu handle.
+    ///
+    /// This pointer must not be `free`d or otherwise invalidated. It must not be
+    /// referenced after [`ButtonMenu`] has been dropped.
+    pub fn as_ptr(&self) -> *mut sys::ButtonMenu {
+        self.raw.as_ptr()
+    }
+
+    /// Adds a button, returning its index. `callback` is called with the presses of the
+    /// button, as described by `kind`.
+    pub fn add_item<F>(&mut self, label: &CStr, kind: ButtonMenuItemType, callback: F) -> u32
+    where
+        F: FnMut(InputType) + 'static,
+    {
+        let index = self.items.len() as u32;
+        let mut item = FuriBox::new(Item {
+            label: FuriString::from(label),
+            selected: NonNull::from(&*self.selected),
+            callback: Box::new(callback),
+        });
+        unsafe {
+            sys::button_menu_add_item(
+                self.as_ptr(),
+                item.label.as_c_ptr(),
+                index as i32,
+                Some(item_callback),
+                kind.to_sys(),
+                FuriBox::as_mut_ptr(&mut item).cast(),
+            )
+        };
+        self.items.push(item);
+        index
+    }
+
+    /// Returns the number of buttons.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns `true` if the menu has no buttons.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Removes all buttons and the header.
+    pub fn reset(&mut self) {
+        unsafe { sys::button_menu_reset(self.as_ptr()) };
+        self.items.clear();
+        self.header = None;
+        self.selected.set(None);
+    }
+
+    /// Returns the index of the button last pressed, or selected with
+    /// [`ButtonMenu::set_selected_item`].
+    ///
+    /// The SDK does not report which button is highlighted, only which are pressed.
+    pub fn selected_item(&self) -> Option<u32> {
+        self.selected.get()
+    }
+
+    /// Highlights the button at `index`.
+    pub fn set_selected_item(&mut self, index: u32) {
+        unsafe { sys::button_menu_set_selected_item(self.as_ptr(), index) };
+        self.selected.set(Some(index));
+    }
+
+    /// Sets the header shown above the buttons.
+    pub fn set_header(&mut self, header: &CStr) {
+        let header = FuriString::from(header);
+        unsafe { sys::button_menu_set_header(self.as_ptr(), header.as_c_ptr()) };
+        // The previous header is no longer used.
+        self.header = Some(header);
+    }
+}
+
+impl Default for ButtonMenu {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+unsafe impl AsView for ButtonMenu {
+    fn as_view_ptr(&self) -> NonNull<sys::View> {
+        // SAFETY: The view is allocated with the button menu.
+        unsafe { NonNull::new_unchecked(sys::button_menu_get_view(self.as_ptr())) }
+    }
+}
+
+impl Drop for ButtonMenu {
+    fn drop(&mut self) {
+        unsafe { sys::button_menu_free(self.as_ptr()) }
+    }
+}
+
+unsafe extern "C" fn item_callback(context: *mut c_void, index: i32, kind: sys::InputType) {
+    let item = unsafe { &mut *context.cast::<Item>() };
+    unsafe { item.selected.as_ref() }.set(Some(index as u32));
+    if let Some(kind) = InputType::from_sys(kind) {
+        (item.callback)(kind);
+    }
+}
+
+#[flipperzero_test::tests]
+mod tests {
+    use alloc::rc::Rc;
+
+    use super::{ButtonMenu, ButtonMenuItemType};
+
+    #[test]
+    fn items() {
+        let pressed = Rc::new(());
+
+        let mut button_menu = ButtonMenu::new();
+        assert!(button_menu.selected_item().is_none());
+        for kind in [ButtonMenuItemType::Common, ButtonMenuItemType::Control] {
+            let pressed = pressed.clone();
+            button_menu.add_item(c"Item", kind, move |_| drop(pressed.clone()));
+        }
+        button_menu.set_header(c"Header");
+        button_menu.set_selected_item(1);
+        assert_eq!(button_menu.selected_item(), Some(1));
+        assert_eq!(button_menu.len(), 2);
+        assert_eq!(Rc::strong_count(&pressed), 3);
+
+        button_menu.reset();
+        assert!(button_menu.is_empty());
+        assert_eq!(Rc::strong_count(&pressed), 1);
+
+        // Dropping the menu drops the button closures.
+        let item_pressed = pressed.clone();
+        button_menu.add_item(c"Item", ButtonMenuItemType::Common, move |_| {
+            drop(item_pressed.clone())
+        });
+        drop(button_menu);
+        assert_eq!(Rc::strong_count(&pressed), 1);
+    }
+}
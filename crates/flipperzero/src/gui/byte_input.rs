@@ -0,0 +1,165 @@
+//! Byte input, a hex editor for a fixed number of bytes.
+
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ffi::{c_void, CStr};
+use core::ptr::NonNull;
+
+use flipperzero_sys as sys;
+use flipperzero_sys::furi::FuriBox;
+
+use super::view::AsView;
+use crate::furi::string::FuriString;
+
+type ResultCallback = Box<dyn FnMut(Vec<u8>)>;
+type ChangedCallback = Box<dyn FnMut(&[u8])>;
+
+/// The context of the callbacks.
+struct State {
+    bytes: Vec<u8>,
+    on_result: Option<ResultCallback>,
+    on_changed: Option<ChangedCallback>,
+}
+
+/// A hex editor, for entering a fixed number of bytes, such as a key or an address.
+///
+/// The bytes are kept in a buffer owned by the input. When the user saves them, the
+/// closure set with [`ByteInput::set_result_callback`] is called with a copy, on the
+/// thread running the [`ViewDispatcher`](super::ViewDispatcher) the input is added to.
+pub struct ByteInput {
+    raw: NonNull<sys::ByteInput>,
+    /// The SDK keeps a pointer to the header.
+    header: Option<FuriString>,
+    state: FuriBox<State>,
+}
+
+impl ByteInput {
+    /// Creates a byte input for `len` bytes, initially zero.
+    pub fn new(len: u8) -> Self {
+        let mut input = Self {
+            // SAFETY: Alloc always returns a valid non-null pointer or `furi_panic`s.
+            raw: unsafe { NonNull::new_unchecked(sys::byte_input_alloc()) },
+            header: None,
+            state: FuriBox::new(State {
+                bytes: vec![0; usize::from(len)],
+                on_result: None,
+                on_changed: None,
+            }),
+        };
+        input.install();
+        input
+    }
+
+    /// Obtain raw Furi byte input handle.
+    ///
+    /// This pointer must not be `free`d or otherwise invalidated. It must not be
+    /// referenced after [`ByteInput`] has been dropped.
+    pub fn as_ptr(&self) -> *mut sys::ByteInput {
+        self.raw.as_ptr()
+    }
+
+    /// Starts editing `bytes`, calling `callback` with the bytes once they are saved.
+    ///
+    /// `bytes` is truncated, or padded with zeroes, to the number of bytes of the input.
+    pub fn set_result_callback<F: FnMut(Vec<u8>) + 'static>(&mut self, bytes: &[u8], callback: F) {
+        let state = &mut *self.state;
+        let len = bytes.len().min(state.bytes.len());
+        state.bytes.fill(0);
+        state.bytes[..len].copy_from_slice(&bytes[..len]);
+        state.on_result = Some(Box::new(callback));
+        self.install();
+    }
+
+    /// Sets `callback` to be called with the bytes whenever one is changed.
+    pub fn set_changed_callback<F: FnMut(&[u8]) + 'static>(&mut self, callback: F) {
+        self.state.on_changed = Some(Box::new(callback));
+        self.install();
+    }
+
+    fn install(&mut self) {
+        unsafe {
+            sys::byte_input_set_result_callback(
+                self.as_ptr(),
+                Some(result_callback),
+                Some(changed_callback),
+                FuriBox::as_mut_ptr(&mut self.state).cast(),
+                self.state.bytes.as_mut_ptr(),
+                self.state.bytes.len() as u8,
+            )
+        };
+    }
+
+    /// Returns the bytes, as last saved or being edited.
+    pub fn bytes(&self) -> &[u8] {
+        &self.state.bytes
+    }
+
+    /// Sets the header shown above the bytes.
+    pub fn set_header(&mut self, header: &CStr) {
+        let header = FuriString::from(header);
+        unsafe { sys::byte_input_set_header_text(self.as_ptr(), header.as_c_ptr()) };
+        // The previous header is no longer used.
+        self.header = Some(header);
+    }
+}
+
+unsafe impl AsView for ByteInput {
+    fn as_view_ptr(&self) -> NonNull<sys::View> {
+        // SAFETY: The view is allocated with the byte input.
+        unsafe { NonNull::new_unchecked(sys::byte_input_get_view(self.as_ptr())) }
+    }
+}
+
+impl Drop for ByteInput {
+    fn drop(&mut self) {
+        unsafe { sys::byte_input_free(self.as_ptr()) }
+    }
+}
+
+unsafe extern "C" fn result_callback(context: *mut c_void) {
+    let state = unsafe { &mut *context.cast::<State>() };
+    if let Some(callback) = &mut state.on_result {
+        callback(state.bytes.clone());
+    }
+}
+
+unsafe extern "C" fn changed_callback(context: *mut c_void) {
+    let state = unsafe { &mut *context.cast::<State>() };
+    if let Some(callback) = &mut state.on_changed {
+        callback(&state.bytes);
+    }
+}
+
+#[flipperzero_test::tests]
+mod tests {
+    use alloc::rc::Rc;
+
+    use super::ByteInput;
+
+    #[test]
+    fn bytes() {
+        let mut byte_input = ByteInput::new(4);
+        assert_eq!(byte_input.bytes(), [0; 4]);
+        byte_input.set_header(c"Header");
+        byte_input.set_result_callback(&[1, 2], |_| {});
+        byte_input.set_changed_callback(|_| {});
+        assert_eq!(byte_input.bytes(), [1, 2, 0, 0]);
+    }
+
+    #[test]
+    fn closures_are_dropped() {
+        let called = Rc::new(());
+
+        let mut byte_input = ByteInput::new(4);
+        for _ in 0..2 {
+            let on_result = called.clone();
+            byte_input.set_result_callback(&[], move |_| drop(on_result.clone()));
+        }
+        let on_changed = called.clone();
+        byte_input.set_changed_callback(move |_| drop(on_changed.clone()));
+        assert_eq!(Rc::strong_count(&called), 3);
+        drop(byte_input);
+        assert_eq!(Rc::strong_count(&called), 1);
+    }
+}
@@ -26,6 +26,25 @@ impl Align {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Font {
+    Primary,
+    Secondary,
+    Keyboard,
+    BigNumbers,
+}
+
+impl Font {
+    pub fn to_sys(&self) -> sys::Font {
+        match self {
+            Self::Primary => sys::FontPrimary,
+            Self::Secondary => sys::FontSecondary,
+            Self::Keyboard => sys::FontKeyboard,
+            Self::BigNumbers => sys::FontBigNumbers,
+        }
+    }
+}
+
 /// Graphics Canvas.
 #[repr(transparent)]
 pub struct Canvas {
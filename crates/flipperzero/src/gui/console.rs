@@ -0,0 +1,325 @@
+//! Console, a scrolling log of text.
+
+use alloc::sync::Arc;
+use core::convert::Infallible;
+use core::fmt;
+use core::ptr::NonNull;
+
+use flipperzero_sys as sys;
+
+use super::view::AsView;
+use crate::furi::string::FuriString;
+use crate::furi::sync::Mutex;
+
+/// The font of a [`Console`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConsoleFont {
+    /// The proportional font used for text.
+    #[default]
+    Text,
+    /// A monospaced font, suited to hex dumps.
+    Hex,
+}
+
+impl ConsoleFont {
+    pub fn to_sys(&self) -> sys::TextBoxFont {
+        match self {
+            Self::Text => sys::TextBoxFontText,
+            Self::Hex => sys::TextBoxFontHex,
+        }
+    }
+}
+
+/// The state shared by a [`Console`] and its writers.
+struct Shared {
+    /// `None` once the console is dropped.
+    text_box: Option<NonNull<sys::TextBox>>,
+    log: FuriString,
+    /// The copy of the log shown by the text box, which keeps a pointer to it.
+    shown: Option<FuriString>,
+    capacity: usize,
+    follow: bool,
+}
+
+// SAFETY: The text box locks its model when its text is set, and the strings are only
+// accessed with the mutex held.
+unsafe impl Send for Shared {}
+
+impl Shared {
+    /// Appends `s` to the log, without showing it.
+    fn push_str(&mut self, s: &str) {
+        self.log.push_str(s);
+        self.trim();
+    }
+
+    /// Appends `s` to the log, and shows it if it ends a line. Formatting writes text in
+    /// several pieces, and showing the log copies all of it, so partial lines wait for
+    /// the rest of the line or a [`Console::flush`].
+    fn push_line_str(&mut self, s: &str) {
+        self.push_str(s);
+        if s.contains('\n') {
+            self.refresh();
+        }
+    }
+
+    /// Appends formatted text to the log, and shows it once it has all been written.
+    fn push_fmt(&mut self, args: fmt::Arguments<'_>) -> fmt::Result {
+        fmt::Write::write_fmt(self, args)?;
+        self.refresh();
+        Ok(())
+    }
+
+    /// Removes the oldest lines until the log fits its capacity.
+    fn trim(&mut self) {
+        let len = self.log.len();
+        if len <= self.capacity {
+            return;
+        }
+        let excess = len - self.capacity;
+        let bytes = self.log.to_bytes();
+        let cut = match bytes[excess - 1..].iter().position(|&b| b == b'\n') {
+            Some(newline) => excess + newline,
+            // A single line longer than the capacity is cut at a character boundary.
+            None => (excess..len)
+                .find(|&i| bytes[i] & 0xC0 != 0x80)
+                .unwrap_or(len),
+        };
+        unsafe { sys::furi_string_right(self.log.as_mut_ptr(), cut) };
+    }
+
+    /// Shows the log, scrolled to its end, if following it.
+    fn refresh(&mut self) {
+        let Some(text_box) = self.text_box else {
+            return;
+        };
+        if !self.follow {
+            return;
+        }
+        let shown = FuriString::from(self.log.as_c_str());
+        unsafe { sys::text_box_set_text(text_box.as_ptr(), shown.as_c_ptr()) };
+        // The previous copy is no longer used.
+        self.shown = Some(shown);
+    }
+}
+
+impl fmt::Write for Shared {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push_str(s);
+        Ok(())
+    }
+}
+
+/// A scrolling log of text, keeping the most recent text up to a capacity.
+///
+/// Text is written with [`core::fmt::Write`] or [`ufmt::uWrite`], to the console or to
+/// a [`ConsoleWriter`] from any thread. While following, the console redraws and
+/// scrolls to the end after each `write!` or `writeln!`, and after each line written
+/// with `ufmt`; otherwise it keeps showing the text it had, which can be scrolled with
+/// Up and Down. Use [`Console::flush`] to show a partial line written with `ufmt`.
+///
+/// ```
+/// # use core::fmt::Write;
+/// # use flipperzero::gui::console::Console;
+/// let mut console = Console::new(1024);
+/// let mut writer = console.writer();
+/// let _ = writeln!(writer, "Ready");
+/// ```
+pub struct Console {
+    raw: NonNull<sys::TextBox>,
+    shared: Arc<Mutex<Shared>>,
+}
+
+impl Console {
+    /// Creates an empty console keeping up to `capacity` bytes of text. When full, the
+    /// oldest lines are removed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "Capacity must not be zero");
+        // SAFETY: Alloc always returns a valid non-null pointer or `furi_panic`s.
+        let raw = unsafe { NonNull::new_unchecked(sys::text_box_alloc()) };
+        unsafe { sys::text_box_set_focus(raw.as_ptr(), sys::TextBoxFocusEnd) };
+
+        let mut shared = Shared {
+            text_box: Some(raw),
+            log: FuriString::with_capacity(capacity),
+            shown: None,
+            capacity,
+            follow: true,
+        };
+        shared.refresh();
+        Self {
+            raw,
+            shared: Arc::new(Mutex::new(shared)),
+        }
+    }
+
+    /// Obtain raw Furi text box handle.
+    ///
+    /// This pointer must not be `free`d or otherwise invalidated. It must not be
+    /// referenced after [`Console`] has been dropped.
+    pub fn as_ptr(&self) -> *mut sys::TextBox {
+        self.raw.as_ptr()
+    }
+
+    /// Returns a handle for writing to the console, which can be sent to other threads.
+    pub fn writer(&self) -> ConsoleWriter {
+        ConsoleWriter {
+            shared: self.shared.clone(),
+        }
+    }
+
+    /// Sets the font of the text.
+    pub fn set_font(&mut self, font: ConsoleFont) {
+        let mut shared = self.shared.lock();
+        unsafe { sys::text_box_set_font(self.as_ptr(), font.to_sys()) };
+        shared.refresh();
+    }
+
+    /// Sets whether the console redraws and scrolls to the end when text is written.
+    /// It does by default.
+    pub fn set_follow(&mut self, follow: bool) {
+        let mut shared = self.shared.lock();
+        shared.follow = follow;
+        shared.refresh();
+    }
+
+    /// Returns a copy of the text.
+    pub fn text(&self) -> FuriString {
+        FuriString::from(self.shared.lock().log.as_c_str())
+    }
+
+    /// Shows all text written so far, if following.
+    pub fn flush(&mut self) {
+        self.shared.lock().refresh();
+    }
+
+    /// Removes all text.
+    pub fn clear(&mut self) {
+        let mut shared = self.shared.lock();
+        shared.log.clear();
+        shared.refresh();
+    }
+}
+
+impl fmt::Write for Console {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.write_fmt(format_args!("{s}"))
+    }
+
+    fn write_fmt(&mut self, args: fmt::Arguments<'_>) -> fmt::Result {
+        self.shared.lock().push_fmt(args)
+    }
+}
+
+impl ufmt::uWrite for Console {
+    type Error = Infallible;
+
+    fn write_str(&mut self, s: &str) -> Result<(), Self::Error> {
+        self.shared.lock().push_line_str(s);
+        Ok(())
+    }
+}
+
+unsafe impl AsView for Console {
+    fn as_view_ptr(&self) -> NonNull<sys::View> {
+        // SAFETY: The view is allocated with the text box.
+        unsafe { NonNull::new_unchecked(sys::text_box_get_view(self.as_ptr())) }
+    }
+}
+
+impl Drop for Console {
+    fn drop(&mut self) {
+        // Writers may outlive the console.
+        let mut shared = self.shared.lock();
+        shared.text_box = None;
+        unsafe { sys::text_box_free(self.as_ptr()) }
+    }
+}
+
+/// A handle for writing to a [`Console`], from any thread.
+///
+/// Writing does nothing once the console is dropped.
+#[derive(Clone)]
+pub struct ConsoleWriter {
+    shared: Arc<Mutex<Shared>>,
+}
+
+impl ConsoleWriter {
+    /// Shows all text written so far, if the console is following.
+    pub fn flush(&mut self) {
+        self.shared.lock().refresh();
+    }
+}
+
+impl fmt::Write for ConsoleWriter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.write_fmt(format_args!("{s}"))
+    }
+
+    fn write_fmt(&mut self, args: fmt::Arguments<'_>) -> fmt::Result {
+        let mut shared = self.shared.lock();
+        if shared.text_box.is_some() {
+            shared.push_fmt(args)?;
+        }
+        Ok(())
+    }
+}
+
+impl ufmt::uWrite for ConsoleWriter {
+    type Error = Infallible;
+
+    fn write_str(&mut self, s: &str) -> Result<(), Self::Error> {
+        let mut shared = self.shared.lock();
+        if shared.text_box.is_some() {
+            shared.push_line_str(s);
+        }
+        Ok(())
+    }
+}
+
+#[flipperzero_test::tests]
+mod tests {
+    use core::fmt::Write;
+
+    use super::{Console, ConsoleFont};
+    use crate::furi::thread;
+
+    #[test]
+    fn write() {
+        let mut console = Console::new(16);
+        console.set_font(ConsoleFont::Hex);
+        let _ = writeln!(console, "first");
+        let _ = writeln!(console, "second");
+        assert_eq!(console.text(), "first\nsecond\n");
+
+        // The oldest line is removed to make room.
+        let _ = writeln!(console, "third");
+        assert_eq!(console.text(), "second\nthird\n");
+
+        let mut writer = console.writer();
+        thread::spawn(move || {
+            let _ = write!(writer, "4th");
+            0
+        })
+        .join();
+        assert_eq!(console.text(), "second\nthird\n4th");
+
+        console.set_follow(false);
+        let _ = write!(console, "0123456789abcdefXY");
+        assert_eq!(console.text(), "23456789abcdefXY");
+
+        console.set_follow(true);
+        let _ = ufmt::uwrite!(console, "{}", 5);
+        console.flush();
+        assert_eq!(console.text(), "3456789abcdefXY5");
+
+        let mut writer = console.writer();
+        console.clear();
+        assert_eq!(console.text(), "");
+        drop(console);
+        let _ = write!(writer, "ignored");
+    }
+}
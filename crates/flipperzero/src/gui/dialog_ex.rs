@@ -0,0 +1,296 @@
+//! Dialog, a message with up to three buttons.
+
+use alloc::boxed::Box;
+use core::ffi::{c_void, CStr};
+use core::ptr::{self, NonNull};
+
+use flipperzero_sys as sys;
+use flipperzero_sys::furi::FuriBox;
+
+use super::canvas::Align;
+use super::view::AsView;
+use crate::furi::string::FuriString;
+
+/// A button event of a [`DialogEx`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DialogExResult {
+    /// The left button was clicked.
+    Left,
+    /// The center button was clicked.
+    Center,
+    /// The right button was clicked.
+    Right,
+    /// The left button was pressed, with extended events enabled.
+    PressLeft,
+    /// The center button was pressed, with extended events enabled.
+    PressCenter,
+    /// The right button was pressed, with extended events enabled.
+    PressRight,
+    /// The left button was released, with extended events enabled.
+    ReleaseLeft,
+    /// The center button was released, with extended events enabled.
+    ReleaseCenter,
+    /// The right button was released, with extended events enabled.
+    ReleaseRight,
+}
+
+impl DialogExResult {
+    pub fn from_sys(sys: sys::DialogExResult) -> Option<Self> {
+        match sys {
+            sys::DialogExResultLeft => Some(Self::Left),
+            sys::DialogExResultCenter => Some(Self::Center),
+            sys::DialogExResultRight => Some(Self::Right),
+            sys::DialogExPressLeft => Some(Self::PressLeft),
+            sys::DialogExPressCenter => Some(Self::PressCenter),
+            sys::DialogExPressRight => Some(Self::PressRight),
+            sys::DialogExReleaseLeft => Some(Self::ReleaseLeft),
+            sys::DialogExReleaseCenter => Some(Self::ReleaseCenter),
+            sys::DialogExReleaseRight => Some(Self::ReleaseRight),
+            _ => None,
+        }
+    }
+}
+
+type Callback = Box<dyn FnMut(DialogExResult)>;
+
+/// A message with a header, an icon and up to three buttons, shown inside a
+/// [`ViewDispatcher`](super::ViewDispatcher).
+///
+/// Unlike [`DialogsApp::show_message`](crate::dialogs::DialogsApp::show_message), the
+/// dialog does not block: the closure set with [`DialogEx::set_result_callback`] is
+/// called with the buttons clicked, on the thread running the dispatcher.
+pub struct DialogEx {
+    raw: NonNull<sys::DialogEx>,
+    /// The SDK keeps pointers to the texts.
+    header: Option<FuriString>,
+    text: Option<FuriString>,
+    buttons: [Option<FuriString>; 3],
+    callback: Option<FuriBox<Callback>>,
+}
+
+impl DialogEx {
+    /// Creates an empty dialog.
+    pub fn new() -> Self {
+        Self {
+            // SAFETY: Alloc always returns a valid non-null pointer or `furi_panic`s.
+            raw: unsafe { NonNull::new_unchecked(sys::dialog_ex_alloc()) },
+            header: None,
+            text: None,
+            buttons: [None, None, None],
+            callback: None,
+        }
+    }
+
+    /// Obtain raw Furi dialog handle.
+    ///
+    /// This pointer must not be `free`d or otherwise invalidated. It must not be
+    /// referenced after [`DialogEx`] has been dropped.
+    pub fn as_ptr(&self) -> *mut sys::DialogEx {
+        self.raw.as_ptr()
+    }
+
+    /// Sets `callback` to be called with the button events.
+    pub fn set_result_callback<F: FnMut(DialogExResult) + 'static>(&mut self, callback: F) {
+        let mut callback: FuriBox<Callback> = FuriBox::new(Box::new(callback));
+        unsafe {
+            sys::dialog_ex_set_context(self.as_ptr(), FuriBox::as_mut_ptr(&mut callback).cast());
+            sys::dialog_ex_set_result_callback(self.as_ptr(), Some(result_callback));
+        }
+        // The previous callback is no longer used.
+        self.callback = Some(callback);
+    }
+
+    /// Sets the header text, which can be multiline, aligned to (`x`, `y`).
+    pub fn set_header(&mut self, header: &CStr, x: u8, y: u8, horizontal: Align, vertical: Align) {
+        let header = FuriString::from(header);
+        unsafe {
+            sys::dialog_ex_set_header(
+                self.as_ptr(),
+                header.as_c_ptr(),
+                x,
+                y,
+                horizontal.to_sys(),
+                vertical.to_sys(),
+            )
+        };
+        self.header = Some(header);
+    }
+
+    /// Sets the body text, which can be multiline, aligned to (`x`, `y`).
+    pub fn set_text(&mut self, text: &CStr, x: u8, y: u8, horizontal: Align, vertical: Align) {
+        let text = FuriString::from(text);
+        unsafe {
+            sys::dialog_ex_set_text(
+                self.as_ptr(),
+                text.as_c_ptr(),
+                x,
+                y,
+                horizontal.to_sys(),
+                vertical.to_sys(),
+            )
+        };
+        self.text = Some(text);
+    }
+
+    /// Clears the header text.
+    pub fn clear_header(&mut self) {
+        unsafe {
+            sys::dialog_ex_set_header(
+                self.as_ptr(),
+                ptr::null(),
+                0,
+                0,
+                sys::AlignLeft,
+                sys::AlignTop,
+            )
+        };
+        self.header = None;
+    }
+
+    /// Clears the body text.
+    pub fn clear_text(&mut self) {
+        unsafe {
+            sys::dialog_ex_set_text(
+                self.as_ptr(),
+                ptr::null(),
+                0,
+                0,
+                sys::AlignLeft,
+                sys::AlignTop,
+            )
+        };
+        self.text = None;
+    }
+
+    /// Sets the icon, with its top left corner at (`x`, `y`).
+    pub fn set_icon(&mut self, x: u8, y: u8, icon: &'static sys::Icon) {
+        unsafe { sys::dialog_ex_set_icon(self.as_ptr(), x, y, icon) }
+    }
+
+    /// Clears the icon.
+    pub fn clear_icon(&mut self) {
+        unsafe { sys::dialog_ex_set_icon(self.as_ptr(), 0, 0, ptr::null()) }
+    }
+
+    /// Sets the labels of the buttons. Buttons without a label are not shown.
+    pub fn set_buttons(
+        &mut self,
+        left: Option<&CStr>,
+        center: Option<&CStr>,
+        right: Option<&CStr>,
+    ) {
+        let setters = [
+            sys::dialog_ex_set_left_button_text,
+            sys::dialog_ex_set_center_button_text,
+            sys::dialog_ex_set_right_button_text,
+        ];
+        for ((label, setter), old) in [left, center, right]
+            .into_iter()
+            .zip(setters)
+            .zip(&mut self.buttons)
+        {
+            let label = label.map(FuriString::from);
+            let ptr = label.as_ref().map_or(ptr::null(), |l| l.as_c_ptr());
+            unsafe { setter(self.raw.as_ptr(), ptr) };
+            // The previous label is no longer used.
+            *old = label;
+        }
+    }
+
+    /// Enables or disables reporting presses and releases of the buttons, in addition
+    /// to clicks.
+    pub fn set_extended_events(&mut self, enabled: bool) {
+        if enabled {
+            unsafe { sys::dialog_ex_enable_extended_events(self.as_ptr()) }
+        } else {
+            unsafe { sys::dialog_ex_disable_extended_events(self.as_ptr()) }
+        }
+    }
+
+    /// Clears the texts, the icon and the buttons, and removes the result callback.
+    pub fn reset(&mut self) {
+        unsafe {
+            sys::dialog_ex_reset(self.as_ptr());
+            sys::dialog_ex_set_result_callback(self.as_ptr(), None);
+            sys::dialog_ex_set_context(self.as_ptr(), ptr::null_mut());
+        }
+        self.header = None;
+        self.text = None;
+        self.buttons = [None, None, None];
+        self.callback = None;
+    }
+}
+
+impl Default for DialogEx {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+unsafe impl AsView for DialogEx {
+    fn as_view_ptr(&self) -> NonNull<sys::View> {
+        // SAFETY: The view is allocated with the dialog.
+        unsafe { NonNull::new_unchecked(sys::dialog_ex_get_view(self.as_ptr())) }
+    }
+}
+
+impl Drop for DialogEx {
+    fn drop(&mut self) {
+        unsafe { sys::dialog_ex_free(self.as_ptr()) }
+    }
+}
+
+unsafe extern "C" fn result_callback(result: sys::DialogExResult, context: *mut c_void) {
+    let callback = unsafe { &mut *context.cast::<Callback>() };
+    if let Some(result) = DialogExResult::from_sys(result) {
+        callback(result);
+    }
+}
+
+#[flipperzero_test::tests]
+mod tests {
+    use alloc::rc::Rc;
+
+    use flipperzero_sys as sys;
+
+    use super::{DialogEx, DialogExResult};
+    use crate::gui::canvas::Align;
+
+    #[test]
+    fn result_from_sys() {
+        assert_eq!(
+            DialogExResult::from_sys(sys::DialogExResultCenter),
+            Some(DialogExResult::Center)
+        );
+        assert_eq!(
+            DialogExResult::from_sys(sys::DialogExReleaseRight),
+            Some(DialogExResult::ReleaseRight)
+        );
+    }
+
+    #[test]
+    fn result_callback() {
+        let called = Rc::new(());
+
+        let mut dialog = DialogEx::new();
+        dialog.set_header(c"Header", 64, 0, Align::Center, Align::Top);
+        dialog.set_text(c"Text", 64, 32, Align::Center, Align::Center);
+        dialog.set_buttons(Some(c"Back"), None, Some(c"OK"));
+        dialog.set_extended_events(true);
+        let dialog_called = called.clone();
+        dialog.set_result_callback(move |_| drop(dialog_called.clone()));
+        assert_eq!(Rc::strong_count(&called), 2);
+        dialog.clear_header();
+        dialog.reset();
+        assert_eq!(Rc::strong_count(&called), 1);
+
+        // Replacing the callback and dropping the dialog drop the closures.
+        for _ in 0..2 {
+            let dialog_called = called.clone();
+            dialog.set_result_callback(move |_| drop(dialog_called.clone()));
+        }
+        assert_eq!(Rc::strong_count(&called), 2);
+        drop(dialog);
+        assert_eq!(Rc::strong_count(&called), 1);
+    }
+}
@@ -0,0 +1,263 @@
+//! File browser, for picking a file on the SD card.
+
+use alloc::boxed::Box;
+use core::ffi::{c_void, CStr};
+use core::mem::ManuallyDrop;
+use core::ptr::NonNull;
+
+use flipperzero_sys as sys;
+use flipperzero_sys::furi::FuriBox;
+
+use super::view::AsView;
+use crate::dialogs::DialogFileBrowserOptions;
+use crate::furi::string::FuriString;
+
+/// The size of the buffer for the icon of a [`FileBrowserItem`].
+pub const ITEM_ICON_SIZE: usize = 32;
+
+/// The name and icon of a file shown by a [`FileBrowser`], written by its item loader.
+pub struct FileBrowserItem<'a> {
+    name: &'a mut FuriString,
+    icon: &'a mut [u8],
+}
+
+impl FileBrowserItem<'_> {
+    /// Returns the name shown for the file, initially empty.
+    pub fn name_mut(&mut self) -> &mut FuriString {
+        self.name
+    }
+
+    /// Returns the icon shown for the file, initially blank: the data of a 10x10 icon
+    /// in the format of application manifest icons, of at most [`ITEM_ICON_SIZE`]
+    /// bytes.
+    pub fn icon_mut(&mut self) -> &mut [u8] {
+        self.icon
+    }
+}
+
+type ResultCallback = Box<dyn FnMut(FuriString)>;
+type ItemLoader = Box<dyn FnMut(&CStr, &mut FileBrowserItem<'_>) -> bool + Send>;
+
+/// The context of the result callback.
+struct State {
+    /// The SDK writes the selected path to it.
+    result: FuriString,
+    callback: Option<ResultCallback>,
+}
+
+/// A list of the files of a folder on the SD card, for picking one, shown inside a
+/// [`ViewDispatcher`](super::ViewDispatcher).
+///
+/// Unlike [`DialogsApp::show_file_browser`](crate::dialogs::DialogsApp::show_file_browser),
+/// the browser does not block: it lists files from [`FileBrowser::start`], and the
+/// closure set with [`FileBrowser::set_result_callback`] is called with the path of the
+/// file picked, on the thread running the dispatcher.
+pub struct FileBrowser {
+    raw: NonNull<sys::FileBrowser>,
+    state: FuriBox<State>,
+    loader: Option<FuriBox<ItemLoader>>,
+    started: bool,
+}
+
+impl FileBrowser {
+    /// Creates a file browser, listing all files.
+    pub fn new() -> Self {
+        let mut state = FuriBox::new(State {
+            result: FuriString::new(),
+            callback: None,
+        });
+        Self {
+            // SAFETY: Alloc always returns a valid non-null pointer or `furi_panic`s.
+            raw: unsafe {
+                NonNull::new_unchecked(sys::file_browser_alloc(state.result.as_mut_ptr()))
+            },
+            state,
+            loader: None,
+            started: false,
+        }
+    }
+
+    /// Obtain raw Furi file browser handle.
+    ///
+    /// This pointer must not be `free`d or otherwise invalidated. It must not be
+    /// referenced after [`FileBrowser`] has been dropped.
+    pub fn as_ptr(&self) -> *mut sys::FileBrowser {
+        self.raw.as_ptr()
+    }
+
+    /// Sets the extension of the files listed, the folder the user can't go above, and
+    /// how files are shown.
+    ///
+    /// The item loader of `options` is not used: set one with
+    /// [`FileBrowser::set_item_loader`] instead. The options take effect the next time
+    /// the browser is started.
+    pub fn configure(&mut self, options: &DialogFileBrowserOptions<'static>) {
+        let options = options.as_sys();
+        unsafe {
+            sys::file_browser_configure(
+                self.as_ptr(),
+                options.extension,
+                options.base_path,
+                options.skip_assets,
+                options.hide_dot_files,
+                options.icon,
+                options.hide_ext,
+            )
+        };
+    }
+
+    /// Sets `callback` to be called with the path of the file picked.
+    pub fn set_result_callback<F: FnMut(FuriString) + 'static>(&mut self, callback: F) {
+        self.state.callback = Some(Box::new(callback));
+        unsafe {
+            sys::file_browser_set_callback(
+                self.as_ptr(),
+                Some(result_callback),
+                FuriBox::as_mut_ptr(&mut self.state).cast(),
+            )
+        };
+    }
+
+    /// Sets `loader` to be called with the path of each file listed, to give it a
+    /// custom name and icon. It returns `true` if it wrote them, or `false` to show the
+    /// file as usual.
+    ///
+    /// `loader` is called on the thread listing the files.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the browser is started.
+    pub fn set_item_loader<F>(&mut self, loader: F)
+    where
+        F: FnMut(&CStr, &mut FileBrowserItem<'_>) -> bool + Send + 'static,
+    {
+        assert!(!self.started, "File browser is started");
+        let mut loader: FuriBox<ItemLoader> = FuriBox::new(Box::new(loader));
+        unsafe {
+            sys::file_browser_set_item_callback(
+                self.as_ptr(),
+                Some(item_callback),
+                FuriBox::as_mut_ptr(&mut loader).cast(),
+            )
+        };
+        // The previous loader is no longer used.
+        self.loader = Some(loader);
+    }
+
+    /// Starts listing files, from the folder of `path` with it selected if it is a
+    /// file, or from the folder itself.
+    pub fn start(&mut self, path: &CStr) {
+        self.stop();
+        let mut path = FuriString::from(path);
+        unsafe { sys::file_browser_start(self.as_ptr(), path.as_mut_ptr()) };
+        self.started = true;
+    }
+
+    /// Stops listing files.
+    pub fn stop(&mut self) {
+        if self.started {
+            unsafe { sys::file_browser_stop(self.as_ptr()) };
+            self.started = false;
+        }
+    }
+
+    /// Returns `true` if the browser is started.
+    pub fn is_started(&self) -> bool {
+        self.started
+    }
+}
+
+impl Default for FileBrowser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+unsafe impl AsView for FileBrowser {
+    fn as_view_ptr(&self) -> NonNull<sys::View> {
+        // SAFETY: The view is allocated with the file browser.
+        unsafe { NonNull::new_unchecked(sys::file_browser_get_view(self.as_ptr())) }
+    }
+}
+
+impl Drop for FileBrowser {
+    fn drop(&mut self) {
+        // The item loader must not be called once dropped.
+        self.stop();
+        unsafe { sys::file_browser_free(self.as_ptr()) }
+    }
+}
+
+unsafe extern "C" fn result_callback(context: *mut c_void) {
+    let state = unsafe { &mut *context.cast::<State>() };
+    let path = FuriString::from(state.result.as_c_str());
+    if let Some(callback) = &mut state.callback {
+        callback(path);
+    }
+}
+
+unsafe extern "C" fn item_callback(
+    path: *mut sys::FuriString,
+    context: *mut c_void,
+    icon: *mut *mut u8,
+    item_name: *mut sys::FuriString,
+) -> bool {
+    let loader = unsafe { &mut *context.cast::<ItemLoader>() };
+    let path = unsafe { CStr::from_ptr(sys::furi_string_get_cstr(path)) };
+    // Both are owned by the browser.
+    let mut name =
+        ManuallyDrop::new(unsafe { FuriString::from_raw(NonNull::new_unchecked(item_name)) });
+    let icon: &mut [u8] = match unsafe { *icon } {
+        icon if icon.is_null() => &mut [],
+        icon => unsafe { core::slice::from_raw_parts_mut(icon, ITEM_ICON_SIZE) },
+    };
+    icon.fill(0);
+
+    loader(
+        path,
+        &mut FileBrowserItem {
+            name: &mut name,
+            icon,
+        },
+    )
+}
+
+#[flipperzero_test::tests]
+mod tests {
+    use alloc::sync::Arc;
+
+    use super::FileBrowser;
+    use crate::dialogs::DialogFileBrowserOptions;
+
+    #[test]
+    fn callbacks() {
+        let mut browser = FileBrowser::new();
+        browser.configure(&DialogFileBrowserOptions::new().set_hide_dot_files(true));
+        browser.set_result_callback(|_| {});
+        browser.set_item_loader(|_, item| {
+            let _ = item.name_mut();
+            false
+        });
+        assert!(!browser.is_started());
+    }
+
+    #[test]
+    fn closures_are_dropped() {
+        let called = Arc::new(());
+
+        let mut browser = FileBrowser::new();
+        let on_result = called.clone();
+        browser.set_result_callback(move |_| drop(on_result.clone()));
+        // Replacing the item loader drops the previous one.
+        for _ in 0..2 {
+            let loader = called.clone();
+            browser.set_item_loader(move |_, _| {
+                drop(loader.clone());
+                false
+            });
+        }
+        assert_eq!(Arc::strong_count(&called), 3);
+        drop(browser);
+        assert_eq!(Arc::strong_count(&called), 1);
+    }
+}
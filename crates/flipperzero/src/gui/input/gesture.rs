@@ -0,0 +1,293 @@
+use super::{InputEvent, InputType, Key};
+
+/// A set of [`Key`]s.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Keys(u8);
+
+impl Keys {
+    const ALL: [Key; 6] = [
+        Key::Up,
+        Key::Down,
+        Key::Right,
+        Key::Left,
+        Key::Ok,
+        Key::Back,
+    ];
+
+    /// The empty set.
+    pub const fn new() -> Self {
+        Self(0)
+    }
+
+    const fn bit(key: Key) -> u8 {
+        1 << key as u8
+    }
+
+    /// Returns a copy of the set with `key` added.
+    pub const fn with(self, key: Key) -> Self {
+        Self(self.0 | Self::bit(key))
+    }
+
+    /// Returns `true` if `key` is in the set.
+    pub const fn contains(&self, key: Key) -> bool {
+        self.0 & Self::bit(key) != 0
+    }
+
+    /// Returns the number of keys in the set.
+    pub const fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    /// Returns `true` if the set is empty.
+    pub const fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Iterates over the keys in the set.
+    pub fn iter(&self) -> impl Iterator<Item = Key> + '_ {
+        Self::ALL.into_iter().filter(|&key| self.contains(key))
+    }
+
+    fn remove(&mut self, key: Key) {
+        self.0 &= !Self::bit(key);
+    }
+}
+
+impl From<Key> for Keys {
+    fn from(key: Key) -> Self {
+        Self::new().with(key)
+    }
+}
+
+/// A gesture recognized by a [`GestureRecognizer`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Gesture {
+    /// A key was pressed and released once.
+    Tap(Key),
+    /// A key was tapped twice in quick succession.
+    DoubleTap(Key),
+    /// A key is being held, and has been for `percent` percent of the hold time.
+    HoldProgress { key: Key, percent: u8 },
+    /// A key that reported [`Gesture::HoldProgress`] was released, or became part of a
+    /// chord, before the hold time.
+    HoldCancelled(Key),
+    /// A key has been held for the hold time. Its release is not reported.
+    Hold(Key),
+    /// Several keys were held down at the same time, and have all been released.
+    Chord(Keys),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum State {
+    Idle,
+    /// A single key is down.
+    Pressed {
+        key: Key,
+        since: u32,
+        /// Whether this is the second press of a double tap.
+        second: bool,
+        /// The last [`Gesture::HoldProgress`] reported.
+        percent: u8,
+    },
+    /// A key was tapped, and may be tapped again for a double tap.
+    Tapped {
+        key: Key,
+        at: u32,
+    },
+    /// A key has been held for the hold time, and not yet released.
+    Held {
+        key: Key,
+    },
+    /// Several keys were down at the same time.
+    Chord {
+        down: Keys,
+        keys: Keys,
+    },
+}
+
+/// Turns the presses and releases of [`InputEvent`]s into taps, double taps, holds and
+/// chords.
+///
+/// Feed it every input event with [`GestureRecognizer::input`], and call
+/// [`GestureRecognizer::tick`] regularly, at least every few tens of milliseconds,
+/// so that it can report hold progress and taps once the double tap window has passed.
+/// Both take the current time in milliseconds, such as
+/// [`furi::kernel::get_tick`](crate::furi::kernel::get_tick), whose ticks are
+/// milliseconds on the Flipper Zero, and call the given closure with each gesture
+/// recognized.
+///
+/// Only [`InputType::Press`] and [`InputType::Release`] events are used; the input
+/// service's own short, long and repeat events are ignored.
+#[derive(Debug, Clone)]
+pub struct GestureRecognizer {
+    hold_ms: u32,
+    double_tap_ms: u32,
+    state: State,
+}
+
+impl Default for GestureRecognizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GestureRecognizer {
+    /// Creates a recognizer with a hold time of 1 second and a double tap window of
+    /// 250 milliseconds.
+    pub const fn new() -> Self {
+        Self {
+            hold_ms: 1000,
+            double_tap_ms: 250,
+            state: State::Idle,
+        }
+    }
+
+    /// Sets how long a key must be held for [`Gesture::Hold`].
+    pub const fn hold_time(mut self, ms: u32) -> Self {
+        self.hold_ms = ms;
+        self
+    }
+
+    /// Sets how soon after a tap the second tap of a double tap must start.
+    ///
+    /// Taps are only reported once this has passed without a second tap, so a shorter
+    /// window makes taps more responsive. Zero disables double taps, reporting taps as
+    /// soon as the key is released.
+    pub const fn double_tap_time(mut self, ms: u32) -> Self {
+        self.double_tap_ms = ms;
+        self
+    }
+
+    /// Discards any gesture in progress.
+    pub fn reset(&mut self) {
+        self.state = State::Idle;
+    }
+
+    /// Handles an input event that happened at `now`.
+    pub fn input(&mut self, event: &InputEvent, now: u32, mut f: impl FnMut(Gesture)) {
+        let key = event.key;
+        self.tick(now, &mut f);
+
+        self.state = match (event.kind, self.state) {
+            (InputType::Press, State::Idle) => Self::pressed(key, now, false),
+            (InputType::Press, State::Tapped { key: tapped, .. }) if tapped == key => {
+                Self::pressed(key, now, true)
+            }
+            (InputType::Press, State::Tapped { key: tapped, .. }) => {
+                f(Gesture::Tap(tapped));
+                Self::pressed(key, now, false)
+            }
+            (
+                InputType::Press,
+                State::Pressed {
+                    key: down,
+                    second,
+                    percent,
+                    ..
+                },
+            ) => {
+                // The first press of a double tap that became a chord was a tap.
+                if second {
+                    f(Gesture::Tap(down));
+                }
+                if percent > 0 {
+                    f(Gesture::HoldCancelled(down));
+                }
+                let keys = Keys::from(down).with(key);
+                State::Chord { down: keys, keys }
+            }
+            (InputType::Press, State::Held { key: down }) => {
+                let keys = Keys::from(down).with(key);
+                State::Chord { down: keys, keys }
+            }
+            (InputType::Press, State::Chord { down, keys }) => State::Chord {
+                down: down.with(key),
+                keys: keys.with(key),
+            },
+
+            (
+                InputType::Release,
+                State::Pressed {
+                    key: down,
+                    second,
+                    percent,
+                    ..
+                },
+            ) if down == key => {
+                if percent > 0 {
+                    f(Gesture::HoldCancelled(key));
+                }
+                if second {
+                    f(Gesture::DoubleTap(key));
+                    State::Idle
+                } else if self.double_tap_ms == 0 {
+                    f(Gesture::Tap(key));
+                    State::Idle
+                } else {
+                    State::Tapped { key, at: now }
+                }
+            }
+            (InputType::Release, State::Held { key: down }) if down == key => State::Idle,
+            (InputType::Release, State::Chord { mut down, keys }) => {
+                down.remove(key);
+                if down.is_empty() {
+                    f(Gesture::Chord(keys));
+                    State::Idle
+                } else {
+                    State::Chord { down, keys }
+                }
+            }
+
+            // Releases of keys pressed before the recognizer started or was reset, and
+            // the input service's derived events.
+            (_, state) => state,
+        };
+    }
+
+    /// Reports hold progress and pending taps at `now`.
+    pub fn tick(&mut self, now: u32, mut f: impl FnMut(Gesture)) {
+        match &mut self.state {
+            State::Pressed {
+                key,
+                since,
+                second,
+                percent,
+            } => {
+                let held = now.wrapping_sub(*since);
+                if held >= self.hold_ms {
+                    // The first press of a double tap that became a hold was a tap.
+                    if *second {
+                        f(Gesture::Tap(*key));
+                    }
+                    f(Gesture::Hold(*key));
+                    self.state = State::Held { key: *key };
+                } else {
+                    let progress = (u64::from(held) * 100 / u64::from(self.hold_ms)) as u8;
+                    if progress > *percent {
+                        *percent = progress;
+                        f(Gesture::HoldProgress {
+                            key: *key,
+                            percent: progress,
+                        });
+                    }
+                }
+            }
+            State::Tapped { key, at } => {
+                if now.wrapping_sub(*at) >= self.double_tap_ms {
+                    f(Gesture::Tap(*key));
+                    self.state = State::Idle;
+                }
+            }
+            State::Idle | State::Held { .. } | State::Chord { .. } => {}
+        }
+    }
+
+    fn pressed(key: Key, now: u32, second: bool) -> State {
+        State::Pressed {
+            key,
+            since: now,
+            second,
+            percent: 0,
+        }
+    }
+}
@@ -0,0 +1,257 @@
+//! Input events.
+//!
+//! View ports and views receive the input events meant for them. To see every event,
+//! regardless of what is on screen, subscribe to the input service with
+//! [`InputEvents::subscribe`]. [`GestureRecognizer`] turns either into higher-level
+//! gestures.
+
+mod gesture;
+
+use core::ffi::CStr;
+use core::ptr::NonNull;
+
+use flipperzero_sys as sys;
+use flipperzero_sys::furi::UnsafeRecord;
+
+use crate::furi::pubsub::{Message, Subscription};
+
+pub use gesture::{Gesture, GestureRecognizer, Keys};
+
+/// A hardware button.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Key {
+    Up,
+    Down,
+    Right,
+    Left,
+    Ok,
+    Back,
+}
+
+impl Key {
+    /// Converts an SDK key, returning `None` if it is not a known key.
+    pub fn from_sys(key: sys::InputKey) -> Option<Self> {
+        Some(match key {
+            sys::InputKeyUp => Self::Up,
+            sys::InputKeyDown => Self::Down,
+            sys::InputKeyRight => Self::Right,
+            sys::InputKeyLeft => Self::Left,
+            sys::InputKeyOk => Self::Ok,
+            sys::InputKeyBack => Self::Back,
+            _ => return None,
+        })
+    }
+
+    pub fn to_sys(&self) -> sys::InputKey {
+        match self {
+            Self::Up => sys::InputKeyUp,
+            Self::Down => sys::InputKeyDown,
+            Self::Right => sys::InputKeyRight,
+            Self::Left => sys::InputKeyLeft,
+            Self::Ok => sys::InputKeyOk,
+            Self::Back => sys::InputKeyBack,
+        }
+    }
+
+    /// Returns the name of the key, such as `Up`.
+    pub fn name(&self) -> &'static CStr {
+        unsafe { CStr::from_ptr(sys::input_get_key_name(self.to_sys())) }
+    }
+}
+
+impl TryFrom<sys::InputKey> for Key {
+    type Error = sys::InputKey;
+
+    fn try_from(key: sys::InputKey) -> Result<Self, Self::Error> {
+        Self::from_sys(key).ok_or(key)
+    }
+}
+
+impl From<Key> for sys::InputKey {
+    fn from(key: Key) -> Self {
+        key.to_sys()
+    }
+}
+
+/// The kind of an [`InputEvent`].
+///
+/// `Press` and `Release` are the physical events. The input service derives the others
+/// from them: `Short` on a release shortly after the press, `Long` once the key has
+/// been held for a while, and then `Repeat` periodically until it is released.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InputType {
+    Press,
+    Release,
+    Short,
+    Long,
+    Repeat,
+}
+
+impl InputType {
+    /// Converts an SDK input type, returning `None` if it is not a known type.
+    pub fn from_sys(input_type: sys::InputType) -> Option<Self> {
+        Some(match input_type {
+            sys::InputTypePress => Self::Press,
+            sys::InputTypeRelease => Self::Release,
+            sys::InputTypeShort => Self::Short,
+            sys::InputTypeLong => Self::Long,
+            sys::InputTypeRepeat => Self::Repeat,
+            _ => return None,
+        })
+    }
+
+    pub fn to_sys(&self) -> sys::InputType {
+        match self {
+            Self::Press => sys::InputTypePress,
+            Self::Release => sys::InputTypeRelease,
+            Self::Short => sys::InputTypeShort,
+            Self::Long => sys::InputTypeLong,
+            Self::Repeat => sys::InputTypeRepeat,
+        }
+    }
+
+    /// Returns the name of the input type, such as `Press`.
+    pub fn name(&self) -> &'static CStr {
+        unsafe { CStr::from_ptr(sys::input_get_type_name(self.to_sys())) }
+    }
+}
+
+impl TryFrom<sys::InputType> for InputType {
+    type Error = sys::InputType;
+
+    fn try_from(input_type: sys::InputType) -> Result<Self, Self::Error> {
+        Self::from_sys(input_type).ok_or(input_type)
+    }
+}
+
+impl From<InputType> for sys::InputType {
+    fn from(input_type: InputType) -> Self {
+        input_type.to_sys()
+    }
+}
+
+/// An input event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InputEvent {
+    pub key: Key,
+    pub kind: InputType,
+    /// Identifies the press that this event belongs to; all events from one press of a
+    /// key, up to and including its release, share a sequence number.
+    pub sequence: u32,
+}
+
+impl InputEvent {
+    /// Converts an SDK input event, returning `None` if its key or type is unknown.
+    pub fn from_sys(event: &sys::InputEvent) -> Option<Self> {
+        Some(Self {
+            key: Key::from_sys(event.key)?,
+            kind: InputType::from_sys(event.type_)?,
+            // SAFETY: Both fields of the union are plain `u32` data.
+            sequence: unsafe { event.__bindgen_anon_1.sequence },
+        })
+    }
+
+    pub fn to_sys(&self) -> sys::InputEvent {
+        sys::InputEvent {
+            __bindgen_anon_1: sys::InputEvent__bindgen_ty_1 {
+                sequence: self.sequence,
+            },
+            key: self.key.to_sys(),
+            type_: self.kind.to_sys(),
+        }
+    }
+}
+
+impl From<InputEvent> for sys::InputEvent {
+    fn from(event: InputEvent) -> Self {
+        event.to_sys()
+    }
+}
+
+impl Message for InputEvent {
+    type Raw = sys::InputEvent;
+
+    fn from_raw(raw: &sys::InputEvent) -> Option<Self> {
+        Self::from_sys(raw)
+    }
+}
+
+/// Input service record, which publishes every input event.
+pub struct InputEvents {
+    record: UnsafeRecord<sys::FuriPubSub>,
+}
+
+impl InputEvents {
+    const NAME: &CStr = c"input_events";
+
+    /// Open record to the input service's events.
+    pub fn open() -> Self {
+        Self {
+            record: unsafe { UnsafeRecord::open(Self::NAME) },
+        }
+    }
+
+    /// Obtain raw pointer to the input service's pubsub.
+    ///
+    /// This pointer must not be free'd or used after the `InputEvents` object has been
+    /// dropped.
+    #[inline]
+    pub fn as_ptr(&self) -> *mut sys::FuriPubSub {
+        self.record.as_ptr()
+    }
+
+    /// Calls `f` with every input event until the returned subscription is dropped,
+    /// whichever view port or view has focus.
+    ///
+    /// `f` is called on the input service's thread; see [`Subscription`] for what it
+    /// may do.
+    pub fn subscribe<F>(&self, f: F) -> Subscription<'_, InputEvent, F>
+    where
+        F: FnMut(InputEvent) + Send + 'static,
+    {
+        // SAFETY: The record is non-null, and kept open by `self`. It only carries
+        // `sys::InputEvent`s.
+        unsafe { Subscription::new(NonNull::new_unchecked(self.as_ptr()), f) }
+    }
+}
+
+#[flipperzero_test::tests]
+mod tests {
+    use super::{InputEvent, InputEvents, InputType, Key};
+
+    #[test]
+    fn conversions() {
+        for key in [
+            Key::Up,
+            Key::Down,
+            Key::Right,
+            Key::Left,
+            Key::Ok,
+            Key::Back,
+        ] {
+            assert_eq!(Key::try_from(key.to_sys()), Ok(key));
+        }
+        assert!(Key::try_from(flipperzero_sys::InputKeyMAX).is_err());
+        assert_eq!(
+            InputType::try_from(flipperzero_sys::InputTypeLong),
+            Ok(InputType::Long)
+        );
+        assert_eq!(Key::Ok.name().to_bytes(), b"Ok");
+        assert_eq!(InputType::Short.name().to_bytes(), b"Short");
+
+        let event = InputEvent {
+            key: Key::Ok,
+            kind: InputType::Long,
+            sequence: 42,
+        };
+        assert_eq!(InputEvent::from_sys(&event.into()), Some(event));
+    }
+
+    #[test]
+    fn subscribe() {
+        let input = InputEvents::open();
+        let subscription = input.subscribe(|_| {});
+        drop(subscription);
+    }
+}
@@ -0,0 +1,64 @@
+//! Loading, an animated hourglass.
+
+use core::ptr::NonNull;
+
+use flipperzero_sys as sys;
+
+use super::view::AsView;
+
+/// An animated hourglass, shown while the app is busy, such as during long SD card or
+/// radio operations.
+///
+/// Switch to another view once the work is done.
+pub struct Loading {
+    raw: NonNull<sys::Loading>,
+}
+
+impl Loading {
+    /// Creates a loading view.
+    pub fn new() -> Self {
+        Self {
+            // SAFETY: Alloc always returns a valid non-null pointer or `furi_panic`s.
+            raw: unsafe { NonNull::new_unchecked(sys::loading_alloc()) },
+        }
+    }
+
+    /// Obtain raw Furi loading view handle.
+    ///
+    /// This pointer must not be `free`d or otherwise invalidated. It must not be
+    /// referenced after [`Loading`] has been dropped.
+    pub fn as_ptr(&self) -> *mut sys::Loading {
+        self.raw.as_ptr()
+    }
+}
+
+impl Default for Loading {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+unsafe impl AsView for Loading {
+    fn as_view_ptr(&self) -> NonNull<sys::View> {
+        // SAFETY: The view is allocated with the loading view.
+        unsafe { NonNull::new_unchecked(sys::loading_get_view(self.as_ptr())) }
+    }
+}
+
+impl Drop for Loading {
+    fn drop(&mut self) {
+        unsafe { sys::loading_free(self.as_ptr()) }
+    }
+}
+
+#[flipperzero_test::tests]
+mod tests {
+    use super::Loading;
+    use crate::gui::view::AsView;
+
+    #[test]
+    fn view() {
+        let loading = Loading::new();
+        let _ = loading.as_view_ptr();
+    }
+}
@@ -1,6 +1,49 @@
 //! GUI service.
 
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub mod button_menu;
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub mod byte_input;
 pub mod canvas;
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub mod console;
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub mod dialog_ex;
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub mod file_browser;
+pub mod input;
+pub mod loading;
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub mod number_input;
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub mod popup;
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub mod scene_manager;
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub mod submenu;
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub mod text_input;
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub mod variable_item_list;
+pub mod view;
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub mod view_dispatcher;
+pub mod view_port;
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub mod widget;
 
 use core::ffi::CStr;
 use core::marker::PhantomData;
@@ -11,6 +54,49 @@ use canvas::Canvas;
 use flipperzero_sys as sys;
 use flipperzero_sys::furi::UnsafeRecord;
 
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub use button_menu::ButtonMenu;
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub use byte_input::ByteInput;
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub use console::Console;
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub use dialog_ex::DialogEx;
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub use file_browser::FileBrowser;
+pub use loading::Loading;
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub use number_input::NumberInput;
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub use popup::Popup;
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub use scene_manager::SceneManager;
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub use submenu::Submenu;
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub use text_input::TextInput;
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub use variable_item_list::VariableItemList;
+pub use view::{CustomView, View};
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub use view_dispatcher::ViewDispatcher;
+pub use view_port::ViewPort;
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub use widget::Widget;
+
 /// GUI service record.
 pub struct Gui {
     record: UnsafeRecord<sys::Gui>,
@@ -55,6 +141,36 @@ impl Gui {
     }
 }
 
+/// A layer of the GUI, which view ports are added to.
+///
+/// Layers are drawn from `Desktop` up; the topmost enabled view port of the highest
+/// layer that has one gets input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GuiLayer {
+    /// The desktop, which is below everything else.
+    Desktop,
+    /// Application windows, drawn below the status bar.
+    Window,
+    /// Icons on the left of the status bar.
+    StatusBarLeft,
+    /// Icons on the right of the status bar.
+    StatusBarRight,
+    /// The whole screen, covering the status bar.
+    Fullscreen,
+}
+
+impl GuiLayer {
+    pub fn to_sys(&self) -> sys::GuiLayer {
+        match self {
+            Self::Desktop => sys::GuiLayerDesktop,
+            Self::Window => sys::GuiLayerWindow,
+            Self::StatusBarLeft => sys::GuiLayerStatusBarLeft,
+            Self::StatusBarRight => sys::GuiLayerStatusBarRight,
+            Self::Fullscreen => sys::GuiLayerFullscreen,
+        }
+    }
+}
+
 /// A RAII implementation of a "scope lock" for the GUI Direct Draw Lock. When this
 /// structure is dropped, the Direct Draw Lock will be released.
 ///
@@ -0,0 +1,127 @@
+//! Number input, for entering an integer within a range.
+
+use alloc::boxed::Box;
+use core::ffi::{c_void, CStr};
+use core::ops::RangeInclusive;
+use core::ptr::NonNull;
+
+use flipperzero_sys as sys;
+use flipperzero_sys::furi::FuriBox;
+
+use super::view::AsView;
+use crate::furi::string::FuriString;
+
+type Callback = Box<dyn FnMut(i32)>;
+
+/// An editor for an integer within a range.
+///
+/// When the user saves the number, the closure set with
+/// [`NumberInput::set_result_callback`] is called with it, on the thread running the
+/// [`ViewDispatcher`](super::ViewDispatcher) the input is added to.
+pub struct NumberInput {
+    raw: NonNull<sys::NumberInput>,
+    /// The SDK keeps a pointer to the header.
+    header: Option<FuriString>,
+    callback: Option<FuriBox<Callback>>,
+}
+
+impl NumberInput {
+    /// Creates a number input.
+    pub fn new() -> Self {
+        Self {
+            // SAFETY: Alloc always returns a valid non-null pointer or `furi_panic`s.
+            raw: unsafe { NonNull::new_unchecked(sys::number_input_alloc()) },
+            header: None,
+            callback: None,
+        }
+    }
+
+    /// Obtain raw Furi number input handle.
+    ///
+    /// This pointer must not be `free`d or otherwise invalidated. It must not be
+    /// referenced after [`NumberInput`] has been dropped.
+    pub fn as_ptr(&self) -> *mut sys::NumberInput {
+        self.raw.as_ptr()
+    }
+
+    /// Starts editing `number`, which can be changed within `range`, calling `callback`
+    /// with the number once it is saved.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `number` is not within `range`.
+    pub fn set_result_callback<F>(&mut self, number: i32, range: RangeInclusive<i32>, callback: F)
+    where
+        F: FnMut(i32) + 'static,
+    {
+        assert!(range.contains(&number), "Number out of range");
+        let mut callback: FuriBox<Callback> = FuriBox::new(Box::new(callback));
+        unsafe {
+            sys::number_input_set_result_callback(
+                self.as_ptr(),
+                Some(result_callback),
+                FuriBox::as_mut_ptr(&mut callback).cast(),
+                number,
+                *range.start(),
+                *range.end(),
+            )
+        };
+        // The previous callback is no longer used.
+        self.callback = Some(callback);
+    }
+
+    /// Sets the header shown above the number.
+    pub fn set_header(&mut self, header: &CStr) {
+        let header = FuriString::from(header);
+        unsafe { sys::number_input_set_header_text(self.as_ptr(), header.as_c_ptr()) };
+        // The previous header is no longer used.
+        self.header = Some(header);
+    }
+}
+
+impl Default for NumberInput {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+unsafe impl AsView for NumberInput {
+    fn as_view_ptr(&self) -> NonNull<sys::View> {
+        // SAFETY: The view is allocated with the number input.
+        unsafe { NonNull::new_unchecked(sys::number_input_get_view(self.as_ptr())) }
+    }
+}
+
+impl Drop for NumberInput {
+    fn drop(&mut self) {
+        unsafe { sys::number_input_free(self.as_ptr()) }
+    }
+}
+
+unsafe extern "C" fn result_callback(context: *mut c_void, number: i32) {
+    let callback = unsafe { &mut *context.cast::<Callback>() };
+    callback(number);
+}
+
+#[flipperzero_test::tests]
+mod tests {
+    use alloc::rc::Rc;
+
+    use super::NumberInput;
+
+    #[test]
+    fn result_callback() {
+        let called = Rc::new(());
+
+        let mut number_input = NumberInput::new();
+        number_input.set_header(c"Header");
+        // Replacing the callback drops the previous one.
+        for _ in 0..2 {
+            let on_result = called.clone();
+            number_input.set_result_callback(5, -10..=10, move |_| drop(on_result.clone()));
+        }
+        assert_eq!(Rc::strong_count(&called), 2);
+        drop(number_input);
+        assert_eq!(Rc::strong_count(&called), 1);
+    }
+}
@@ -0,0 +1,222 @@
+//! Popup, a message that can close itself after a timeout.
+
+use alloc::boxed::Box;
+use core::ffi::{c_void, CStr};
+use core::ptr::{self, NonNull};
+
+use flipperzero_sys as sys;
+use flipperzero_sys::furi::FuriBox;
+
+use super::canvas::Align;
+use super::view::AsView;
+use crate::furi::string::FuriString;
+use crate::furi::sync::Mutex;
+use crate::furi::time::FuriDuration;
+
+type Callback = Box<dyn FnMut() + Send>;
+
+/// A message with a header and an icon, such as a confirmation that a file was saved,
+/// shown inside a [`ViewDispatcher`](super::ViewDispatcher).
+///
+/// The closure set with [`Popup::set_callback`] is called when a key is pressed, on the
+/// thread running the dispatcher, or when the timeout set with [`Popup::set_timeout`]
+/// expires, on the timer thread.
+pub struct Popup {
+    raw: NonNull<sys::Popup>,
+    /// The SDK keeps pointers to the texts.
+    header: Option<FuriString>,
+    text: Option<FuriString>,
+    /// Locked by the callback, which can run on the timer thread while the app replaces
+    /// it, or on both threads at once.
+    callback: FuriBox<Mutex<Option<Callback>>>,
+}
+
+impl Popup {
+    /// Creates an empty popup, without a timeout.
+    pub fn new() -> Self {
+        Self {
+            // SAFETY: Alloc always returns a valid non-null pointer or `furi_panic`s.
+            raw: unsafe { NonNull::new_unchecked(sys::popup_alloc()) },
+            header: None,
+            text: None,
+            callback: FuriBox::new(Mutex::new(None)),
+        }
+    }
+
+    /// Obtain raw Furi popup handle.
+    ///
+    /// This pointer must not be `free`d or otherwise invalidated. It must not be
+    /// referenced after [`Popup`] has been dropped.
+    pub fn as_ptr(&self) -> *mut sys::Popup {
+        self.raw.as_ptr()
+    }
+
+    /// Sets `callback` to be called when the timeout expires or a key is pressed.
+    ///
+    /// The callback can be called on the timer thread, so it must be `Send`.
+    pub fn set_callback<F: FnMut() + Send + 'static>(&mut self, callback: F) {
+        *self.callback.lock() = Some(Box::new(callback));
+        unsafe {
+            // `popup_reset` clears the context.
+            sys::popup_set_context(
+                self.as_ptr(),
+                FuriBox::as_mut_ptr(&mut self.callback).cast(),
+            );
+            sys::popup_set_callback(self.as_ptr(), Some(popup_callback));
+        }
+    }
+
+    /// Sets the time after which the popup calls its callback, counted from when it is
+    /// shown, or disables the timeout if `None`.
+    pub fn set_timeout(&mut self, timeout: Option<FuriDuration>) {
+        match timeout {
+            Some(timeout) => unsafe {
+                let millis = u32::try_from(timeout.as_millis()).unwrap_or(u32::MAX);
+                sys::popup_set_timeout(self.as_ptr(), millis);
+                sys::popup_enable_timeout(self.as_ptr());
+            },
+            None => unsafe { sys::popup_disable_timeout(self.as_ptr()) },
+        }
+    }
+
+    /// Sets the header text, which can be multiline, aligned to (`x`, `y`).
+    pub fn set_header(&mut self, header: &CStr, x: u8, y: u8, horizontal: Align, vertical: Align) {
+        let header = FuriString::from(header);
+        unsafe {
+            sys::popup_set_header(
+                self.as_ptr(),
+                header.as_c_ptr(),
+                x,
+                y,
+                horizontal.to_sys(),
+                vertical.to_sys(),
+            )
+        };
+        self.header = Some(header);
+    }
+
+    /// Sets the body text, which can be multiline, aligned to (`x`, `y`).
+    pub fn set_text(&mut self, text: &CStr, x: u8, y: u8, horizontal: Align, vertical: Align) {
+        let text = FuriString::from(text);
+        unsafe {
+            sys::popup_set_text(
+                self.as_ptr(),
+                text.as_c_ptr(),
+                x,
+                y,
+                horizontal.to_sys(),
+                vertical.to_sys(),
+            )
+        };
+        self.text = Some(text);
+    }
+
+    /// Clears the header text.
+    pub fn clear_header(&mut self) {
+        unsafe {
+            sys::popup_set_header(
+                self.as_ptr(),
+                ptr::null(),
+                0,
+                0,
+                sys::AlignLeft,
+                sys::AlignTop,
+            )
+        };
+        self.header = None;
+    }
+
+    /// Clears the body text.
+    pub fn clear_text(&mut self) {
+        unsafe {
+            sys::popup_set_text(
+                self.as_ptr(),
+                ptr::null(),
+                0,
+                0,
+                sys::AlignLeft,
+                sys::AlignTop,
+            )
+        };
+        self.text = None;
+    }
+
+    /// Sets the icon, with its top left corner at (`x`, `y`).
+    pub fn set_icon(&mut self, x: u8, y: u8, icon: &'static sys::Icon) {
+        unsafe { sys::popup_set_icon(self.as_ptr(), x, y, icon) }
+    }
+
+    /// Clears the icon.
+    pub fn clear_icon(&mut self) {
+        unsafe { sys::popup_set_icon(self.as_ptr(), 0, 0, ptr::null()) }
+    }
+
+    /// Clears the texts and the icon, disables the timeout and removes the callback.
+    pub fn reset(&mut self) {
+        unsafe {
+            sys::popup_reset(self.as_ptr());
+            sys::popup_set_callback(self.as_ptr(), None);
+            sys::popup_set_context(self.as_ptr(), ptr::null_mut());
+        }
+        self.header = None;
+        self.text = None;
+        *self.callback.lock() = None;
+    }
+}
+
+impl Default for Popup {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+unsafe impl AsView for Popup {
+    fn as_view_ptr(&self) -> NonNull<sys::View> {
+        // SAFETY: The view is allocated with the popup.
+        unsafe { NonNull::new_unchecked(sys::popup_get_view(self.as_ptr())) }
+    }
+}
+
+impl Drop for Popup {
+    fn drop(&mut self) {
+        unsafe { sys::popup_free(self.as_ptr()) }
+    }
+}
+
+unsafe extern "C" fn popup_callback(context: *mut c_void) {
+    let callback = unsafe { &*context.cast::<Mutex<Option<Callback>>>() };
+    if let Some(callback) = &mut *callback.lock() {
+        callback();
+    }
+}
+
+#[flipperzero_test::tests]
+mod tests {
+    use alloc::sync::Arc;
+
+    use super::Popup;
+    use crate::furi::time::FuriDuration;
+    use crate::gui::canvas::Align;
+
+    #[test]
+    fn callback() {
+        let called = Arc::new(());
+
+        let mut popup = Popup::new();
+        popup.set_header(c"Header", 64, 0, Align::Center, Align::Top);
+        popup.set_text(c"Text", 64, 32, Align::Center, Align::Center);
+        popup.set_timeout(Some(FuriDuration::from_millis(1500)));
+        let popup_called = called.clone();
+        popup.set_callback(move || drop(popup_called.clone()));
+        assert_eq!(Arc::strong_count(&called), 2);
+        popup.set_timeout(None);
+        popup.reset();
+        assert_eq!(Arc::strong_count(&called), 1);
+
+        // Dropping the popup drops its callback.
+        let popup_called = called.clone();
+        popup.set_callback(move || drop(popup_called.clone()));
+        drop(popup);
+        assert_eq!(Arc::strong_count(&called), 1);
+    }
+}
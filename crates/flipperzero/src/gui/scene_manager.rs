@@ -0,0 +1,627 @@
+//! Scene manager, which moves between the screens of a multi-screen app.
+//!
+//! Each screen of the app is a [`Scene`], which shows one of the views of a
+//! [`ViewDispatcher`] and handles its events. Scenes are kept on a stack: moving to
+//! the next scene pushes it, and going back pops it.
+
+use alloc::collections::VecDeque;
+use core::cell::Cell;
+use core::ffi::c_void;
+use core::ptr::{self, NonNull};
+
+use flipperzero_sys as sys;
+use flipperzero_sys::furi::FuriBox;
+
+use super::view_dispatcher::{
+    self, CustomEvent, DispatcherEvent, DispatcherHandle, ViewDispatcher, ViewId,
+};
+
+/// A scene of an app, shown by a [`SceneManager`].
+///
+/// Scenes are the variants of an enum declared with [`scenes!`](crate::scenes). The
+/// handlers get the app, and a [`SceneManagerHandle`] to move to other scenes, show
+/// views or keep state.
+///
+/// ```
+/// # use flipperzero::gui::scene_manager::{Scene, SceneManagerHandle};
+/// # use flipperzero::gui::view_dispatcher::DispatcherEvent;
+/// struct App {
+///     count: u32,
+/// }
+///
+/// flipperzero::scenes! {
+///     enum AppScene {
+///         Start,
+///         Count,
+///     }
+/// }
+///
+/// impl Scene for AppScene {
+///     type App = App;
+///     type Event = u32;
+///     type View = u32;
+///
+///     fn on_enter(self, _app: &mut App, scenes: &mut SceneManagerHandle<'_, Self>) {
+///         scenes.view_dispatcher().switch_to_view(self as u32);
+///     }
+///
+///     fn on_event(
+///         self,
+///         app: &mut App,
+///         scenes: &mut SceneManagerHandle<'_, Self>,
+///         event: DispatcherEvent<u32>,
+///     ) -> bool {
+///         match (self, event) {
+///             (AppScene::Start, DispatcherEvent::Custom(_)) => {
+///                 scenes.next_scene(AppScene::Count);
+///                 true
+///             }
+///             (AppScene::Count, DispatcherEvent::Custom(n)) => {
+///                 app.count += n;
+///                 true
+///             }
+///             // Unconsumed back events go to the previous scene.
+///             _ => false,
+///         }
+///     }
+/// }
+/// ```
+pub trait Scene: SceneId {
+    /// The app whose screens the scenes are.
+    type App;
+    /// The custom events of the app's [`ViewDispatcher`].
+    type Event: CustomEvent;
+    /// The IDs of the views of the app's [`ViewDispatcher`].
+    type View: ViewId;
+
+    /// Called when the scene becomes the current scene, either as the next scene or
+    /// when going back to it.
+    fn on_enter(self, app: &mut Self::App, scenes: &mut SceneManagerHandle<'_, Self>);
+
+    /// Handles an event not consumed by the current view, returning `true` if it was
+    /// consumed.
+    ///
+    /// Unconsumed [`DispatcherEvent::Back`] events go back to the previous scene; on
+    /// the first scene, they stop the app.
+    fn on_event(
+        self,
+        app: &mut Self::App,
+        scenes: &mut SceneManagerHandle<'_, Self>,
+        event: DispatcherEvent<Self::Event>,
+    ) -> bool {
+        let _ = (app, scenes, event);
+        false
+    }
+
+    /// Called when the scene stops being the current scene, either because another
+    /// scene is entered or because it is left.
+    fn on_exit(self, app: &mut Self::App, scenes: &mut SceneManagerHandle<'_, Self>) {
+        let _ = (app, scenes);
+    }
+}
+
+/// The scene IDs used by the SDK.
+///
+/// # Safety
+///
+/// This trait is implemented with [`scenes!`](crate::scenes), and must not be
+/// implemented by hand.
+pub unsafe trait SceneId: Copy + Eq + 'static {
+    /// Converts the scene to the number used by the SDK.
+    fn to_raw(self) -> u32;
+
+    /// Converts a scene number, returning `None` if it is not a scene of this type.
+    fn from_raw(raw: u32) -> Option<Self>;
+
+    #[doc(hidden)]
+    const HANDLERS: SceneHandlers;
+}
+
+/// The handler tables of a [`SceneId`], indexed by scene number.
+#[doc(hidden)]
+pub struct SceneHandlers {
+    pub on_enter: &'static [sys::AppSceneOnEnterCallback],
+    pub on_event: &'static [sys::AppSceneOnEventCallback],
+    pub on_exit: &'static [sys::AppSceneOnExitCallback],
+}
+
+/// Declares an enum of [`Scene`]s.
+///
+/// The variants must not have fields or explicit discriminants. Attributes, such as
+/// derives, are passed through; `Clone`, `Copy`, `PartialEq` and `Eq` are always
+/// derived.
+///
+/// ```
+/// flipperzero::scenes! {
+///     #[derive(Debug)]
+///     pub enum AppScene {
+///         Menu,
+///         Settings,
+///     }
+/// }
+/// # use flipperzero::gui::scene_manager::{Scene, SceneManagerHandle};
+/// # impl Scene for AppScene {
+/// #     type App = ();
+/// #     type Event = u32;
+/// #     type View = u32;
+/// #     fn on_enter(self, _: &mut (), _: &mut SceneManagerHandle<'_, Self>) {}
+/// # }
+/// ```
+#[macro_export]
+macro_rules! scenes {
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident {
+            $($(#[$variant_meta:meta])* $variant:ident),+ $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, PartialEq, Eq)]
+        #[repr(u32)]
+        $vis enum $name {
+            $($(#[$variant_meta])* $variant),+
+        }
+
+        unsafe impl $crate::gui::scene_manager::SceneId for $name {
+            fn to_raw(self) -> u32 {
+                self as u32
+            }
+
+            fn from_raw(raw: u32) -> ::core::option::Option<Self> {
+                $(
+                    if raw == Self::$variant as u32 {
+                        return ::core::option::Option::Some(Self::$variant);
+                    }
+                )+
+                ::core::option::Option::None
+            }
+
+            const HANDLERS: $crate::gui::scene_manager::SceneHandlers =
+                $crate::gui::scene_manager::SceneHandlers {
+                    on_enter: &[$(::core::option::Option::Some(
+                        $crate::gui::scene_manager::on_enter_callback::<
+                            $name,
+                            { $name::$variant as u32 },
+                        >,
+                    )),+],
+                    on_event: &[$(::core::option::Option::Some(
+                        $crate::gui::scene_manager::on_event_callback::<
+                            $name,
+                            { $name::$variant as u32 },
+                        >,
+                    )),+],
+                    on_exit: &[$(::core::option::Option::Some(
+                        $crate::gui::scene_manager::on_exit_callback::<
+                            $name,
+                            { $name::$variant as u32 },
+                        >,
+                    )),+],
+                };
+        }
+    };
+}
+
+/// A scene change requested by a scene handler.
+#[derive(Clone, Copy)]
+enum Transition<S> {
+    Next(S),
+    Previous,
+    SearchPrevious(S),
+}
+
+/// The app and scene manager passed to scene handlers, while the scene manager calls
+/// into the SDK.
+struct Context<S: Scene> {
+    app: *mut S::App,
+    scenes: *mut SceneManager<S>,
+}
+
+impl<S: Scene> Clone for Context<S> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<S: Scene> Copy for Context<S> {}
+
+/// Moves between the [`Scene`]s of an app, whose views are shown by a
+/// [`ViewDispatcher`].
+///
+/// Scene changes requested by scene handlers, such as
+/// [`SceneManagerHandle::next_scene`], take effect once the handler returns, in the
+/// order they were requested.
+pub struct SceneManager<S: Scene> {
+    raw: NonNull<sys::SceneManager>,
+    _handlers: FuriBox<sys::SceneManagerHandlers>,
+    context: FuriBox<Cell<Option<Context<S>>>>,
+    pending: VecDeque<Transition<S>>,
+    /// Whether the scene stack is empty, in which case the SDK can't handle events.
+    empty: bool,
+    view_dispatcher: ViewDispatcher<S::Event, S::View>,
+}
+
+impl<S: Scene> SceneManager<S> {
+    /// Creates a scene manager for the scenes of an app whose views are added to
+    /// `view_dispatcher`.
+    pub fn new(view_dispatcher: ViewDispatcher<S::Event, S::View>) -> Self {
+        let handlers = S::HANDLERS;
+        let mut handlers = FuriBox::new(sys::SceneManagerHandlers {
+            on_enter_handlers: handlers.on_enter.as_ptr(),
+            on_event_handlers: handlers.on_event.as_ptr(),
+            on_exit_handlers: handlers.on_exit.as_ptr(),
+            scene_num: handlers.on_enter.len() as u32,
+        });
+        let mut context = FuriBox::new(Cell::new(None));
+
+        // SAFETY: Alloc always returns a valid non-null pointer or `furi_panic`s.
+        let raw = unsafe {
+            NonNull::new_unchecked(sys::scene_manager_alloc(
+                FuriBox::as_mut_ptr(&mut handlers),
+                FuriBox::as_mut_ptr(&mut context).cast(),
+            ))
+        };
+
+        Self {
+            raw,
+            _handlers: handlers,
+            context,
+            pending: VecDeque::new(),
+            empty: true,
+            view_dispatcher,
+        }
+    }
+
+    /// Obtain raw Furi scene manager handle.
+    ///
+    /// This pointer must not be `free`d or otherwise invalidated. It must not be
+    /// referenced after [`SceneManager`] has been dropped.
+    pub fn as_ptr(&self) -> *mut sys::SceneManager {
+        self.raw.as_ptr()
+    }
+
+    /// Returns the view dispatcher showing the scenes' views.
+    pub fn view_dispatcher(&self) -> &ViewDispatcher<S::Event, S::View> {
+        &self.view_dispatcher
+    }
+
+    /// Returns the view dispatcher showing the scenes' views.
+    pub fn view_dispatcher_mut(&mut self) -> &mut ViewDispatcher<S::Event, S::View> {
+        &mut self.view_dispatcher
+    }
+
+    /// Returns `true` if `scene` is a previous scene, that can be gone back to.
+    pub fn has_previous_scene(&self, scene: S) -> bool {
+        unsafe { sys::scene_manager_has_previous_scene(self.as_ptr(), scene.to_raw()) }
+    }
+
+    /// Sets the state of `scene`, such as the item selected in its menu, which is kept
+    /// while the scene is not shown.
+    pub fn set_scene_state(&self, scene: S, state: u32) {
+        unsafe { sys::scene_manager_set_scene_state(self.as_ptr(), scene.to_raw(), state) }
+    }
+
+    /// Returns the state of `scene`, which is initially zero.
+    pub fn scene_state(&self, scene: S) -> u32 {
+        unsafe { sys::scene_manager_get_scene_state(self.as_ptr(), scene.to_raw()) }
+    }
+
+    /// Enters `start`, and runs the view dispatcher until it is stopped, passing the
+    /// events not consumed by the current view to the current scene.
+    ///
+    /// When the view dispatcher stops, the current scene is left.
+    pub fn run(&mut self, app: &mut S::App, start: S) {
+        let raw = unsafe { NonNull::new_unchecked(self.view_dispatcher.as_ptr()) };
+        let this = ptr::from_mut(self);
+        let app = ptr::from_mut(app);
+        // SAFETY: `self` and `app` are only used through `this` and `app` until the
+        // scene manager stops, and scene handlers are only called through `call`.
+        unsafe {
+            (*this).pending.push_front(Transition::Next(start));
+            Self::apply(this, app);
+
+            view_dispatcher::run_event_loop(raw, &mut |event| Self::handle(this, app, event));
+
+            if !(*this).empty {
+                Self::call(this, app, |raw| sys::scene_manager_stop(raw));
+            }
+            (*this).pending.clear();
+        }
+    }
+
+    /// Calls `f` with the raw scene manager, which may call scene handlers.
+    ///
+    /// # Safety
+    ///
+    /// `this` and `app` must be valid, and not otherwise used until `f` returns.
+    unsafe fn call<R>(
+        this: *mut Self,
+        app: *mut S::App,
+        f: impl FnOnce(*mut sys::SceneManager) -> R,
+    ) -> R {
+        unsafe {
+            let raw = (*this).as_ptr();
+            (*this).context.set(Some(Context { app, scenes: this }));
+            let result = f(raw);
+            (*this).context.set(None);
+            result
+        }
+    }
+
+    /// Applies the pending scene changes.
+    ///
+    /// # Safety
+    ///
+    /// As for [`SceneManager::call`].
+    unsafe fn apply(this: *mut Self, app: *mut S::App) {
+        unsafe {
+            while let Some(transition) = (*this).pending.pop_front() {
+                match transition {
+                    Transition::Next(scene) => {
+                        Self::call(this, app, |raw| {
+                            sys::scene_manager_next_scene(raw, scene.to_raw())
+                        });
+                        (*this).empty = false;
+                    }
+                    Transition::Previous if !(*this).empty => {
+                        if !Self::call(this, app, |raw| sys::scene_manager_previous_scene(raw)) {
+                            (*this).empty = true;
+                            (*this).view_dispatcher.stop();
+                        }
+                    }
+                    Transition::SearchPrevious(scene) if !(*this).empty => {
+                        Self::call(this, app, |raw| {
+                            sys::scene_manager_search_and_switch_to_previous_scene(
+                                raw,
+                                scene.to_raw(),
+                            )
+                        });
+                    }
+                    Transition::Previous | Transition::SearchPrevious(_) => {}
+                }
+            }
+        }
+    }
+
+    /// Passes a view dispatcher event to the current scene.
+    ///
+    /// # Safety
+    ///
+    /// As for [`SceneManager::call`].
+    unsafe fn handle(this: *mut Self, app: *mut S::App, event: DispatcherEvent<S::Event>) -> bool {
+        unsafe {
+            if (*this).empty {
+                return false;
+            }
+            let consumed = Self::call(this, app, |raw| match event {
+                DispatcherEvent::Custom(event) => {
+                    sys::scene_manager_handle_custom_event(raw, event.to_raw())
+                }
+                DispatcherEvent::Back => sys::scene_manager_handle_back_event(raw),
+                DispatcherEvent::Tick => {
+                    sys::scene_manager_handle_tick_event(raw);
+                    true
+                }
+            });
+            // An unconsumed back event left the first scene, which stops the view
+            // dispatcher.
+            if matches!(event, DispatcherEvent::Back) && !consumed {
+                (*this).empty = true;
+            }
+            Self::apply(this, app);
+            consumed
+        }
+    }
+}
+
+impl<S: Scene> Drop for SceneManager<S> {
+    fn drop(&mut self) {
+        unsafe { sys::scene_manager_free(self.as_ptr()) };
+    }
+}
+
+/// A running [`SceneManager`], passed to scene handlers.
+///
+/// It can request scene changes, keep scene state and use the view dispatcher, but
+/// can't replace or drop the scene manager.
+pub struct SceneManagerHandle<'a, S: Scene> {
+    raw: NonNull<sys::SceneManager>,
+    pending: &'a mut VecDeque<Transition<S>>,
+    view_dispatcher: DispatcherHandle<'a, S::Event, S::View>,
+}
+
+impl<'a, S: Scene> SceneManagerHandle<'a, S> {
+    /// Obtain raw Furi scene manager handle.
+    ///
+    /// This pointer must not be `free`d or otherwise invalidated. It must not be
+    /// referenced after [`SceneManager`] has been dropped.
+    pub fn as_ptr(&self) -> *mut sys::SceneManager {
+        self.raw.as_ptr()
+    }
+
+    /// Returns the view dispatcher showing the scenes' views.
+    pub fn view_dispatcher(&mut self) -> &mut DispatcherHandle<'a, S::Event, S::View> {
+        &mut self.view_dispatcher
+    }
+
+    /// Moves to `scene`, keeping the current scene to go back to.
+    pub fn next_scene(&mut self, scene: S) {
+        self.pending.push_back(Transition::Next(scene));
+    }
+
+    /// Goes back to the previous scene.
+    ///
+    /// On the first scene, this leaves it and stops the view dispatcher.
+    pub fn previous_scene(&mut self) {
+        self.pending.push_back(Transition::Previous);
+    }
+
+    /// Goes back to the most recent `scene` before the current one, leaving the scenes
+    /// after it. Does nothing if `scene` is not a previous scene.
+    pub fn search_and_switch_to_previous(&mut self, scene: S) {
+        self.pending.push_back(Transition::SearchPrevious(scene));
+    }
+
+    /// Returns `true` if `scene` is a previous scene, that can be gone back to.
+    pub fn has_previous_scene(&self, scene: S) -> bool {
+        unsafe { sys::scene_manager_has_previous_scene(self.as_ptr(), scene.to_raw()) }
+    }
+
+    /// Sets the state of `scene`, like [`SceneManager::set_scene_state`].
+    pub fn set_scene_state(&self, scene: S, state: u32) {
+        unsafe { sys::scene_manager_set_scene_state(self.as_ptr(), scene.to_raw(), state) }
+    }
+
+    /// Returns the state of `scene`, which is initially zero.
+    pub fn scene_state(&self, scene: S) -> u32 {
+        unsafe { sys::scene_manager_get_scene_state(self.as_ptr(), scene.to_raw()) }
+    }
+}
+
+/// Calls `f` with scene `id` and the context of a scene handler.
+///
+/// # Safety
+///
+/// `context` must be the context of a [`SceneManager<S>`].
+unsafe fn with_scene<S: Scene, R>(
+    context: *mut c_void,
+    id: u32,
+    f: impl FnOnce(S, &mut S::App, &mut SceneManagerHandle<'_, S>) -> R,
+) -> Option<R> {
+    let context = unsafe { &*context.cast::<Cell<Option<Context<S>>>>() };
+    let Context { app, scenes } = context.get()?;
+    let scene = S::from_raw(id)?;
+    // SAFETY: `SceneManager::call` set the context, and does not use them until the
+    // SDK returns.
+    let scenes = unsafe { &mut *scenes };
+    let mut handle = SceneManagerHandle {
+        raw: scenes.raw,
+        pending: &mut scenes.pending,
+        view_dispatcher: DispatcherHandle::new(&mut scenes.view_dispatcher),
+    };
+    Some(f(scene, unsafe { &mut *app }, &mut handle))
+}
+
+#[doc(hidden)]
+pub unsafe extern "C" fn on_enter_callback<S: Scene, const ID: u32>(context: *mut c_void) {
+    unsafe {
+        with_scene::<S, _>(context, ID, |scene, app, scenes| {
+            scene.on_enter(app, scenes)
+        })
+    };
+}
+
+#[doc(hidden)]
+pub unsafe extern "C" fn on_event_callback<S: Scene, const ID: u32>(
+    context: *mut c_void,
+    event: sys::SceneManagerEvent,
+) -> bool {
+    let event = match event.type_ {
+        sys::SceneManagerEventTypeCustom => match S::Event::from_raw(event.event) {
+            Some(event) => DispatcherEvent::Custom(event),
+            None => return false,
+        },
+        sys::SceneManagerEventTypeBack => DispatcherEvent::Back,
+        sys::SceneManagerEventTypeTick => DispatcherEvent::Tick,
+        _ => return false,
+    };
+    unsafe {
+        with_scene::<S, _>(context, ID, |scene, app, scenes| {
+            scene.on_event(app, scenes, event)
+        })
+    }
+    .unwrap_or(false)
+}
+
+#[doc(hidden)]
+pub unsafe extern "C" fn on_exit_callback<S: Scene, const ID: u32>(context: *mut c_void) {
+    unsafe { with_scene::<S, _>(context, ID, |scene, app, scenes| scene.on_exit(app, scenes)) };
+}
+
+#[flipperzero_test::tests]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::{Scene, SceneManager, SceneManagerHandle};
+    use crate::gui::view_dispatcher::{DispatcherEvent, ViewDispatcher, ViewDispatcherType};
+
+    crate::scenes! {
+        #[derive(Debug)]
+        enum TestScene {
+            First,
+            Second,
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum Step {
+        Enter(TestScene),
+        Event(TestScene, u32),
+        Exit(TestScene),
+    }
+
+    impl Scene for TestScene {
+        type App = Vec<Step>;
+        type Event = u32;
+        type View = u32;
+
+        fn on_enter(self, app: &mut Vec<Step>, scenes: &mut SceneManagerHandle<'_, Self>) {
+            app.push(Step::Enter(self));
+            match self {
+                TestScene::First if scenes.scene_state(self) == 0 => {
+                    scenes.view_dispatcher().send_custom_event(1);
+                }
+                // Returned to from the second scene.
+                TestScene::First => scenes.previous_scene(),
+                TestScene::Second => {
+                    assert!(scenes.has_previous_scene(TestScene::First));
+                    scenes.view_dispatcher().send_custom_event(2);
+                }
+            }
+        }
+
+        fn on_event(
+            self,
+            app: &mut Vec<Step>,
+            scenes: &mut SceneManagerHandle<'_, Self>,
+            event: DispatcherEvent<u32>,
+        ) -> bool {
+            let DispatcherEvent::Custom(event) = event else {
+                return false;
+            };
+            app.push(Step::Event(self, event));
+            match self {
+                TestScene::First => {
+                    scenes.set_scene_state(self, event);
+                    scenes.next_scene(TestScene::Second);
+                }
+                TestScene::Second => scenes.previous_scene(),
+            }
+            true
+        }
+
+        fn on_exit(self, app: &mut Vec<Step>, _: &mut SceneManagerHandle<'_, Self>) {
+            app.push(Step::Exit(self));
+        }
+    }
+
+    #[test]
+    fn transitions() {
+        let mut scenes = SceneManager::new(ViewDispatcher::new(ViewDispatcherType::Window));
+        let mut steps = Vec::new();
+        scenes.run(&mut steps, TestScene::First);
+        assert_eq!(
+            steps,
+            [
+                Step::Enter(TestScene::First),
+                Step::Event(TestScene::First, 1),
+                Step::Exit(TestScene::First),
+                Step::Enter(TestScene::Second),
+                Step::Event(TestScene::Second, 2),
+                Step::Exit(TestScene::Second),
+                Step::Enter(TestScene::First),
+                Step::Exit(TestScene::First),
+            ]
+        );
+        assert_eq!(scenes.scene_state(TestScene::First), 1);
+    }
+}
@@ -0,0 +1,151 @@
+//! Submenu, a scrollable list of items that can be selected.
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::ffi::{c_void, CStr};
+use core::ptr::NonNull;
+
+use flipperzero_sys as sys;
+use flipperzero_sys::furi::FuriBox;
+
+use super::view::AsView;
+
+type Callback = Box<dyn FnMut()>;
+
+/// A scrollable list of items, each calling a closure when selected with OK.
+///
+/// Items are numbered in the order they are added, from zero.
+///
+/// The closures are called on the thread running the
+/// [`ViewDispatcher`](super::ViewDispatcher) the submenu is added to, and usually send
+/// it a custom event with an [`EventSender`](super::view_dispatcher::EventSender).
+pub struct Submenu {
+    raw: NonNull<sys::Submenu>,
+    callbacks: Vec<FuriBox<Callback>>,
+}
+
+impl Submenu {
+    /// Creates an empty submenu.
+    pub fn new() -> Self {
+        Self {
+            // SAFETY: Alloc always returns a valid non-null pointer or `furi_panic`s.
+            raw: unsafe { NonNull::new_unchecked(sys::submenu_alloc()) },
+            callbacks: Vec::new(),
+        }
+    }
+
+    /// Obtain raw Furi submenu handle.
+    ///
+    /// This pointer must not be `free`d or otherwise invalidated. It must not be
+    /// referenced after [`Submenu`] has been dropped.
+    pub fn as_ptr(&self) -> *mut sys::Submenu {
+        self.raw.as_ptr()
+    }
+
+    /// Adds an item, returning its index. `callback` is called when it is selected.
+    pub fn add_item<F: FnMut() + 'static>(&mut self, label: &CStr, callback: F) -> u32 {
+        let index = self.callbacks.len() as u32;
+        let mut callback: FuriBox<Callback> = FuriBox::new(Box::new(callback));
+        unsafe {
+            sys::submenu_add_item(
+                self.as_ptr(),
+                label.as_ptr(),
+                index,
+                Some(item_callback),
+                FuriBox::as_mut_ptr(&mut callback).cast(),
+            )
+        };
+        self.callbacks.push(callback);
+        index
+    }
+
+    /// Changes the label of the item at `index`.
+    pub fn set_item_label(&mut self, index: u32, label: &CStr) {
+        unsafe { sys::submenu_change_item_label(self.as_ptr(), index, label.as_ptr()) }
+    }
+
+    /// Returns the number of items.
+    pub fn len(&self) -> usize {
+        self.callbacks.len()
+    }
+
+    /// Returns `true` if the submenu has no items.
+    pub fn is_empty(&self) -> bool {
+        self.callbacks.is_empty()
+    }
+
+    /// Removes all items and the header.
+    pub fn reset(&mut self) {
+        unsafe { sys::submenu_reset(self.as_ptr()) };
+        self.callbacks.clear();
+    }
+
+    /// Returns the index of the highlighted item.
+    pub fn selected_item(&self) -> u32 {
+        unsafe { sys::submenu_get_selected_item(self.as_ptr()) }
+    }
+
+    /// Highlights the item at `index`, scrolling to it.
+    pub fn set_selected_item(&mut self, index: u32) {
+        unsafe { sys::submenu_set_selected_item(self.as_ptr(), index) }
+    }
+
+    /// Sets the header shown above the items.
+    pub fn set_header(&mut self, header: &CStr) {
+        unsafe { sys::submenu_set_header(self.as_ptr(), header.as_ptr()) }
+    }
+}
+
+impl Default for Submenu {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+unsafe impl AsView for Submenu {
+    fn as_view_ptr(&self) -> NonNull<sys::View> {
+        // SAFETY: The view is allocated with the submenu.
+        unsafe { NonNull::new_unchecked(sys::submenu_get_view(self.as_ptr())) }
+    }
+}
+
+impl Drop for Submenu {
+    fn drop(&mut self) {
+        unsafe { sys::submenu_free(self.as_ptr()) }
+    }
+}
+
+unsafe extern "C" fn item_callback(context: *mut c_void, _index: u32) {
+    let callback = unsafe { &mut *context.cast::<Callback>() };
+    callback();
+}
+
+#[flipperzero_test::tests]
+mod tests {
+    use alloc::rc::Rc;
+
+    use super::Submenu;
+
+    #[test]
+    fn items() {
+        let selected = Rc::new(());
+
+        let mut submenu = Submenu::new();
+        for i in 0..3 {
+            let selected = selected.clone();
+            assert_eq!(submenu.add_item(c"Item", move || drop(selected.clone())), i);
+        }
+        submenu.set_selected_item(2);
+        assert_eq!(submenu.selected_item(), 2);
+        assert_eq!(Rc::strong_count(&selected), 4);
+        submenu.reset();
+        assert!(submenu.is_empty());
+        assert_eq!(Rc::strong_count(&selected), 1);
+
+        // Dropping the submenu drops the item closures.
+        let item_selected = selected.clone();
+        submenu.add_item(c"Item", move || drop(item_selected.clone()));
+        drop(submenu);
+        assert_eq!(Rc::strong_count(&selected), 1);
+    }
+}
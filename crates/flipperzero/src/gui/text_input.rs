@@ -0,0 +1,279 @@
+//! Text input, an on-screen keyboard.
+
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ffi::{c_char, c_void, CStr};
+use core::fmt::Write;
+use core::ptr::NonNull;
+
+use flipperzero_sys as sys;
+use flipperzero_sys::furi::FuriBox;
+
+use super::view::AsView;
+use crate::furi::string::FuriString;
+
+/// Characters that are not accepted by a [`TextInput`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IllegalChars {
+    /// Any character typed is accepted.
+    #[default]
+    None,
+    /// Characters that are not allowed in file names, such as `/` and `:`.
+    Filename,
+    /// The characters of the string.
+    Custom(&'static CStr),
+}
+
+impl IllegalChars {
+    const FILENAME: &'static [u8] = b"<>:\"/\\|?*";
+
+    /// Returns the first illegal character of `text`.
+    pub fn find(&self, text: &CStr) -> Option<char> {
+        let illegal = match self {
+            Self::None => return None,
+            Self::Filename => Self::FILENAME,
+            Self::Custom(chars) => chars.to_bytes(),
+        };
+        text.to_bytes()
+            .iter()
+            .find(|c| illegal.contains(c))
+            .map(|&c| char::from(c))
+    }
+}
+
+type ResultCallback = Box<dyn FnMut(FuriString)>;
+type Validator = Box<dyn FnMut(&CStr) -> Result<(), FuriString>>;
+
+/// The context of the callbacks.
+struct State {
+    /// The text being edited, followed by a nul.
+    buffer: Vec<u8>,
+    on_result: Option<ResultCallback>,
+    validator: Option<Validator>,
+    illegal: IllegalChars,
+}
+
+impl State {
+    fn text(&self) -> &CStr {
+        CStr::from_bytes_until_nul(&self.buffer).expect("Text is nul-terminated")
+    }
+}
+
+/// An on-screen keyboard, for entering text of up to a maximum length.
+///
+/// The text is kept in a buffer owned by the input. When the user saves it, after it
+/// has been validated, the closure set with [`TextInput::set_result_callback`] is
+/// called with it, on the thread running the [`ViewDispatcher`](super::ViewDispatcher)
+/// the input is added to.
+pub struct TextInput {
+    raw: NonNull<sys::TextInput>,
+    /// The SDK keeps a pointer to the header.
+    header: Option<FuriString>,
+    state: FuriBox<State>,
+}
+
+impl TextInput {
+    /// Creates a text input for text of at most `max_len` bytes.
+    pub fn new(max_len: usize) -> Self {
+        Self {
+            // SAFETY: Alloc always returns a valid non-null pointer or `furi_panic`s.
+            raw: unsafe { NonNull::new_unchecked(sys::text_input_alloc()) },
+            header: None,
+            state: FuriBox::new(State {
+                buffer: vec![0; max_len + 1],
+                on_result: None,
+                validator: None,
+                illegal: IllegalChars::None,
+            }),
+        }
+    }
+
+    /// Obtain raw Furi text input handle.
+    ///
+    /// This pointer must not be `free`d or otherwise invalidated. It must not be
+    /// referenced after [`TextInput`] has been dropped.
+    pub fn as_ptr(&self) -> *mut sys::TextInput {
+        self.raw.as_ptr()
+    }
+
+    /// Starts editing `text`, calling `callback` with the text once it is saved.
+    ///
+    /// If `clear_default_text` is `true`, `text` is selected, so that typing replaces
+    /// it. `text` is truncated to the maximum length.
+    pub fn set_result_callback<F>(&mut self, text: &CStr, clear_default_text: bool, callback: F)
+    where
+        F: FnMut(FuriString) + 'static,
+    {
+        let state = &mut *self.state;
+        let text = text.to_bytes();
+        let len = text.len().min(state.buffer.len() - 1);
+        state.buffer.fill(0);
+        state.buffer[..len].copy_from_slice(&text[..len]);
+        state.on_result = Some(Box::new(callback));
+
+        unsafe {
+            sys::text_input_set_result_callback(
+                self.as_ptr(),
+                Some(result_callback),
+                FuriBox::as_mut_ptr(&mut self.state).cast(),
+                self.state.buffer.as_mut_ptr().cast::<c_char>(),
+                self.state.buffer.len(),
+                clear_default_text,
+            )
+        };
+    }
+
+    /// Returns the text, as last saved or being edited.
+    pub fn text(&self) -> &CStr {
+        self.state.text()
+    }
+
+    /// Sets the minimum length of the text that can be saved.
+    pub fn set_minimum_length(&mut self, len: usize) {
+        unsafe { sys::text_input_set_minimum_length(self.as_ptr(), len) }
+    }
+
+    /// Sets a closure that validates the text when the user tries to save it,
+    /// returning an error message to show if it is not valid.
+    pub fn set_validator<F>(&mut self, validator: F)
+    where
+        F: FnMut(&CStr) -> Result<(), FuriString> + 'static,
+    {
+        self.state.validator = Some(Box::new(validator));
+        self.install_validator();
+    }
+
+    /// Sets the characters that are not accepted. Text containing any of them can't be
+    /// saved.
+    pub fn set_illegal_chars(&mut self, illegal: IllegalChars) {
+        self.state.illegal = illegal;
+        self.install_validator();
+    }
+
+    fn install_validator(&mut self) {
+        unsafe {
+            sys::text_input_set_validator(
+                self.as_ptr(),
+                Some(validator_callback),
+                FuriBox::as_mut_ptr(&mut self.state).cast(),
+            )
+        };
+    }
+
+    /// Sets the header shown above the keyboard.
+    pub fn set_header(&mut self, header: &CStr) {
+        let header = FuriString::from(header);
+        unsafe { sys::text_input_set_header_text(self.as_ptr(), header.as_c_ptr()) };
+        // The previous header is no longer used.
+        self.header = Some(header);
+    }
+
+    /// Removes the header, the result callback, the validator and the illegal
+    /// characters, and clears the text.
+    pub fn reset(&mut self) {
+        unsafe { sys::text_input_reset(self.as_ptr()) };
+        self.header = None;
+        let state = &mut *self.state;
+        state.buffer.fill(0);
+        state.on_result = None;
+        state.validator = None;
+        state.illegal = IllegalChars::None;
+    }
+}
+
+unsafe impl AsView for TextInput {
+    fn as_view_ptr(&self) -> NonNull<sys::View> {
+        // SAFETY: The view is allocated with the text input.
+        unsafe { NonNull::new_unchecked(sys::text_input_get_view(self.as_ptr())) }
+    }
+}
+
+impl Drop for TextInput {
+    fn drop(&mut self) {
+        unsafe { sys::text_input_free(self.as_ptr()) }
+    }
+}
+
+unsafe extern "C" fn result_callback(context: *mut c_void) {
+    let state = unsafe { &mut *context.cast::<State>() };
+    let text = FuriString::from(state.text());
+    if let Some(callback) = &mut state.on_result {
+        callback(text);
+    }
+}
+
+unsafe extern "C" fn validator_callback(
+    text: *const c_char,
+    error: *mut sys::FuriString,
+    context: *mut c_void,
+) -> bool {
+    let state = unsafe { &mut *context.cast::<State>() };
+    let text = unsafe { CStr::from_ptr(text) };
+
+    let result = match state.illegal.find(text) {
+        Some(c) => {
+            let mut message = FuriString::new();
+            let _ = write!(message, "Invalid character: {c}");
+            Err(message)
+        }
+        None => match &mut state.validator {
+            Some(validator) => validator(text),
+            None => Ok(()),
+        },
+    };
+    match result {
+        Ok(()) => true,
+        Err(message) => {
+            unsafe { sys::furi_string_set_str(error, message.as_c_ptr()) };
+            false
+        }
+    }
+}
+
+#[flipperzero_test::tests]
+mod tests {
+    use alloc::rc::Rc;
+
+    use super::{IllegalChars, TextInput};
+
+    #[test]
+    fn text() {
+        let mut text_input = TextInput::new(4);
+        text_input.set_header(c"Header");
+        text_input.set_result_callback(c"Flipper", true, |_| {});
+        assert_eq!(text_input.text().to_bytes(), b"Flip");
+        text_input.set_illegal_chars(IllegalChars::Filename);
+        text_input.set_validator(|_| Ok(()));
+        text_input.reset();
+        assert!(text_input.text().is_empty());
+    }
+
+    #[test]
+    fn closures_are_dropped() {
+        let called = Rc::new(());
+
+        let mut text_input = TextInput::new(8);
+        let on_result = called.clone();
+        text_input.set_result_callback(c"", false, move |_| drop(on_result.clone()));
+        let validator = called.clone();
+        text_input.set_validator(move |_| {
+            drop(validator.clone());
+            Ok(())
+        });
+        assert_eq!(Rc::strong_count(&called), 3);
+        text_input.reset();
+        assert_eq!(Rc::strong_count(&called), 1);
+
+        let on_result = called.clone();
+        text_input.set_result_callback(c"", false, move |_| drop(on_result.clone()));
+        drop(text_input);
+        assert_eq!(Rc::strong_count(&called), 1);
+    }
+
+    #[test]
+    fn illegal_chars() {
+        assert!(IllegalChars::None.find(c"a/b").is_none());
+        assert_eq!(IllegalChars::Filename.find(c"a/b"), Some('/'));
+    }
+}
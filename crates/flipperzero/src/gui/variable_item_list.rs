@@ -0,0 +1,407 @@
+//! Variable item list, a list of settings whose values are changed with Left and Right.
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::ffi::{c_void, CStr};
+use core::fmt::Write;
+use core::ops::RangeInclusive;
+use core::ptr::NonNull;
+
+use flipperzero_sys as sys;
+use flipperzero_sys::furi::FuriBox;
+
+use super::view::AsView;
+use crate::furi::string::FuriString;
+
+/// The values of an item of a [`VariableItemList`].
+pub trait ItemValues: 'static {
+    /// The type of the values.
+    type Value;
+
+    /// Returns the number of values, which must not be zero.
+    fn count(&self) -> u8;
+
+    /// Returns the value at `index`, which is less than [`ItemValues::count`].
+    fn value(&self, index: u8) -> Self::Value;
+
+    /// Writes the text shown for the value at `index`.
+    fn write_label(&self, index: u8, label: &mut FuriString);
+}
+
+/// A list of options, such as the variants of an enum, with labels.
+///
+/// ```
+/// # use flipperzero::gui::variable_item_list::Options;
+/// #[derive(Clone, Copy)]
+/// enum Speed {
+///     Slow,
+///     Fast,
+/// }
+///
+/// let speeds = Options::new(&[(Speed::Slow, c"Slow"), (Speed::Fast, c"Fast")]);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Options<T: 'static> {
+    options: &'static [(T, &'static CStr)],
+}
+
+impl<T: Copy + 'static> Options<T> {
+    /// Creates a list of options.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are no options, or more than 255.
+    pub const fn new(options: &'static [(T, &'static CStr)]) -> Self {
+        assert!(
+            !options.is_empty() && options.len() <= u8::MAX as usize,
+            "Invalid number of options"
+        );
+        Self { options }
+    }
+}
+
+impl<T: Copy + 'static> ItemValues for Options<T> {
+    type Value = T;
+
+    fn count(&self) -> u8 {
+        self.options.len() as u8
+    }
+
+    fn value(&self, index: u8) -> T {
+        self.options[usize::from(index)].0
+    }
+
+    fn write_label(&self, index: u8, label: &mut FuriString) {
+        label.push_c_str(self.options[usize::from(index)].1);
+    }
+}
+
+/// Evenly spaced numbers, shown with a formatting function.
+///
+/// ```
+/// # use core::fmt::Write;
+/// # use flipperzero::gui::variable_item_list::Range;
+/// let volume = Range::new(0..=100, 10).format(|value, label| {
+///     let _ = write!(label, "{value}%");
+/// });
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Range<F> {
+    start: i32,
+    step: i32,
+    len: u8,
+    format: F,
+}
+
+impl Range<fn(i32, &mut FuriString)> {
+    /// Creates the numbers of `range` that are a multiple of `step` from its start,
+    /// shown as plain numbers.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is empty, `step` is not positive, or there are more than 255
+    /// numbers.
+    pub fn new(range: RangeInclusive<i32>, step: i32) -> Self {
+        assert!(step > 0, "Step must be positive");
+        let (start, end) = range.into_inner();
+        assert!(start <= end, "Empty range");
+        let len = (i64::from(end) - i64::from(start)) / i64::from(step) + 1;
+        assert!(len <= i64::from(u8::MAX), "Too many values");
+
+        Self {
+            start,
+            step,
+            len: len as u8,
+            format: |value, label| {
+                let _ = write!(label, "{value}");
+            },
+        }
+    }
+}
+
+impl<F> Range<F> {
+    /// Shows the numbers with `format`, which writes the text for a number.
+    pub fn format<G: Fn(i32, &mut FuriString) + 'static>(self, format: G) -> Range<G> {
+        Range {
+            start: self.start,
+            step: self.step,
+            len: self.len,
+            format,
+        }
+    }
+}
+
+impl<F: Fn(i32, &mut FuriString) + 'static> ItemValues for Range<F> {
+    type Value = i32;
+
+    fn count(&self) -> u8 {
+        self.len
+    }
+
+    fn value(&self, index: u8) -> i32 {
+        self.start + i32::from(index) * self.step
+    }
+
+    fn write_label(&self, index: u8, label: &mut FuriString) {
+        (self.format)(self.value(index), label);
+    }
+}
+
+type EnterCallback = Box<dyn FnMut(u8)>;
+
+/// The context of an item's change callback.
+struct Item<V: ItemValues> {
+    values: V,
+    on_change: Box<dyn FnMut(V::Value)>,
+}
+
+impl<V: ItemValues> Item<V> {
+    /// Shows the value at `index` of the item.
+    fn show(&self, raw: *mut sys::VariableItem, index: u8) {
+        let mut label = FuriString::new();
+        self.values.write_label(index, &mut label);
+        unsafe {
+            sys::variable_item_set_current_value_index(raw, index);
+            // Copied by the SDK.
+            sys::variable_item_set_current_value_text(raw, label.as_c_ptr());
+        }
+    }
+}
+
+/// An item of a [`VariableItemList`], whatever its values.
+trait AnyItem {
+    fn count(&self) -> u8;
+    fn show(&self, raw: *mut sys::VariableItem, index: u8);
+}
+
+impl<V: ItemValues> AnyItem for FuriBox<Item<V>> {
+    fn count(&self) -> u8 {
+        self.values.count()
+    }
+
+    fn show(&self, raw: *mut sys::VariableItem, index: u8) {
+        Item::show(self, raw, index)
+    }
+}
+
+/// A list of settings, whose values are changed with Left and Right.
+///
+/// Items are numbered in the order they are added, from zero. Each has a list of
+/// values, such as [`Options`] or a [`Range`] of numbers, and a closure called with
+/// the new value when it is changed.
+///
+/// The closures are called on the thread running the
+/// [`ViewDispatcher`](super::ViewDispatcher) the list is added to.
+pub struct VariableItemList {
+    raw: NonNull<sys::VariableItemList>,
+    items: Vec<(NonNull<sys::VariableItem>, Box<dyn AnyItem>)>,
+    on_enter: Option<FuriBox<EnterCallback>>,
+}
+
+impl VariableItemList {
+    /// Creates an empty list.
+    pub fn new() -> Self {
+        Self {
+            // SAFETY: Alloc always returns a valid non-null pointer or `furi_panic`s.
+            raw: unsafe { NonNull::new_unchecked(sys::variable_item_list_alloc()) },
+            items: Vec::new(),
+            on_enter: None,
+        }
+    }
+
+    /// Obtain raw Furi variable item list handle.
+    ///
+    /// This pointer must not be `free`d or otherwise invalidated. It must not be
+    /// referenced after [`VariableItemList`] has been dropped.
+    pub fn as_ptr(&self) -> *mut sys::VariableItemList {
+        self.raw.as_ptr()
+    }
+
+    /// Adds an item showing the value at `index` of `values`, and returns the item's
+    /// index. `on_change` is called with the new value whenever it is changed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is not the index of a value.
+    pub fn add_item<V, F>(&mut self, label: &CStr, values: V, index: u8, on_change: F) -> u8
+    where
+        V: ItemValues,
+        F: FnMut(V::Value) + 'static,
+    {
+        let count = values.count();
+        assert!(index < count, "Value index out of range");
+        let item_index = self.items.len() as u8;
+
+        let mut item = FuriBox::new(Item {
+            values,
+            on_change: Box::new(on_change),
+        });
+        // SAFETY: Add always returns a valid non-null pointer or `furi_panic`s.
+        let raw = unsafe {
+            NonNull::new_unchecked(sys::variable_item_list_add(
+                self.as_ptr(),
+                label.as_ptr(),
+                count,
+                Some(change_callback::<V>),
+                FuriBox::as_mut_ptr(&mut item).cast(),
+            ))
+        };
+        item.show(raw.as_ptr(), index);
+        self.items.push((raw, Box::new(item)));
+        item_index
+    }
+
+    /// Sets `callback` to be called with the index of the item OK is pressed on.
+    pub fn set_enter_callback<F: FnMut(u8) + 'static>(&mut self, callback: F) {
+        let mut callback: FuriBox<EnterCallback> = FuriBox::new(Box::new(callback));
+        unsafe {
+            sys::variable_item_list_set_enter_callback(
+                self.as_ptr(),
+                Some(enter_callback),
+                FuriBox::as_mut_ptr(&mut callback).cast(),
+            )
+        };
+        // The previous callback is no longer used.
+        self.on_enter = Some(callback);
+    }
+
+    /// Returns the number of items.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns `true` if the list has no items.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Removes all items.
+    pub fn reset(&mut self) {
+        unsafe { sys::variable_item_list_reset(self.as_ptr()) };
+        self.items.clear();
+    }
+
+    /// Returns the index of the highlighted item.
+    pub fn selected_item(&self) -> u8 {
+        unsafe { sys::variable_item_list_get_selected_item_index(self.as_ptr()) }
+    }
+
+    /// Highlights the item at `index`, scrolling to it.
+    pub fn set_selected_item(&mut self, index: u8) {
+        unsafe { sys::variable_item_list_set_selected_item(self.as_ptr(), index) }
+    }
+
+    /// Returns the index of the value shown by the item at `item`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no item at `item`.
+    pub fn value_index(&self, item: u8) -> u8 {
+        let (raw, _) = self.items[usize::from(item)];
+        unsafe { sys::variable_item_get_current_value_index(raw.as_ptr()) }
+    }
+
+    /// Shows the value at `index` in the item at `item`, without calling its closure.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no item at `item`, or `index` is not the index of one of its
+    /// values.
+    pub fn set_value_index(&mut self, item: u8, index: u8) {
+        let (raw, item) = &self.items[usize::from(item)];
+        assert!(index < item.count(), "Value index out of range");
+        item.show(raw.as_ptr(), index);
+    }
+}
+
+impl Default for VariableItemList {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+unsafe impl AsView for VariableItemList {
+    fn as_view_ptr(&self) -> NonNull<sys::View> {
+        // SAFETY: The view is allocated with the list.
+        unsafe { NonNull::new_unchecked(sys::variable_item_list_get_view(self.as_ptr())) }
+    }
+}
+
+impl Drop for VariableItemList {
+    fn drop(&mut self) {
+        unsafe { sys::variable_item_list_free(self.as_ptr()) }
+    }
+}
+
+unsafe extern "C" fn change_callback<V: ItemValues>(raw: *mut sys::VariableItem) {
+    let item = unsafe { &mut *sys::variable_item_get_context(raw).cast::<Item<V>>() };
+    let index = unsafe { sys::variable_item_get_current_value_index(raw) };
+    item.show(raw, index);
+    (item.on_change)(item.values.value(index));
+}
+
+unsafe extern "C" fn enter_callback(context: *mut c_void, index: u32) {
+    let callback = unsafe { &mut *context.cast::<EnterCallback>() };
+    callback(index as u8);
+}
+
+#[flipperzero_test::tests]
+mod tests {
+    use alloc::rc::Rc;
+    use core::ffi::CStr;
+    use core::fmt::Write;
+
+    use super::{ItemValues, Options, Range, VariableItemList};
+    use crate::furi::string::FuriString;
+
+    static ON_OFF: [(bool, &CStr); 2] = [(false, c"Off"), (true, c"On")];
+
+    #[test]
+    fn range() {
+        let volume = Range::new(0..=100, 10).format(|value, label| {
+            let _ = write!(label, "{value}%");
+        });
+        assert_eq!(volume.count(), 11);
+        assert_eq!(volume.value(3), 30);
+        let mut text = FuriString::new();
+        volume.write_label(3, &mut text);
+        assert_eq!(text, "30%");
+    }
+
+    #[test]
+    fn items() {
+        let volume = Range::new(0..=100, 10);
+
+        let mut list = VariableItemList::new();
+        assert_eq!(list.add_item(c"Power", Options::new(&ON_OFF), 1, |_| {}), 0);
+        assert_eq!(list.add_item(c"Volume", volume, 5, |_| {}), 1);
+        assert_eq!(list.value_index(0), 1);
+        assert_eq!(list.value_index(1), 5);
+        list.set_value_index(1, 10);
+        assert_eq!(list.value_index(1), 10);
+        list.set_selected_item(1);
+        assert_eq!(list.selected_item(), 1);
+    }
+
+    #[test]
+    fn closures_are_dropped() {
+        let called = Rc::new(());
+
+        let mut list = VariableItemList::new();
+        let changed = called.clone();
+        list.add_item(c"Power", Options::new(&ON_OFF), 0, move |_| {
+            drop(changed.clone())
+        });
+        // Replacing the enter callback drops the previous one.
+        for _ in 0..2 {
+            let entered = called.clone();
+            list.set_enter_callback(move |_| drop(entered.clone()));
+        }
+        assert_eq!(Rc::strong_count(&called), 3);
+
+        list.reset();
+        assert_eq!(Rc::strong_count(&called), 2);
+        drop(list);
+        assert_eq!(Rc::strong_count(&called), 1);
+    }
+}
@@ -0,0 +1,321 @@
+//! Views, which are shown by a `ViewDispatcher`.
+//!
+//! The SDK's GUI modules, such as menus and text inputs, are views. Apps can also
+//! implement their own with the [`View`] trait and [`CustomView`].
+
+use core::ffi::c_void;
+use core::marker::PhantomData;
+use core::mem;
+use core::ops::{Deref, DerefMut};
+use core::ptr::{self, NonNull};
+
+use flipperzero_sys as sys;
+use flipperzero_sys::furi::FuriBox;
+
+use super::canvas::Canvas;
+use super::input::InputEvent;
+use super::view_port::Orientation;
+
+/// A type that owns a `sys::View`.
+///
+/// # Safety
+///
+/// [`AsView::as_view_ptr`] must return a valid view, which stays at the same address
+/// until `self` is dropped, even if `self` is moved.
+pub unsafe trait AsView {
+    /// Obtain raw view handle.
+    ///
+    /// This pointer must not be `free`d or otherwise invalidated. It must not be
+    /// referenced after `self` has been dropped.
+    fn as_view_ptr(&self) -> NonNull<sys::View>;
+}
+
+/// A view implemented in Rust, shown with [`CustomView`].
+///
+/// What the view shows is its [`View::Model`], which is kept behind a lock as the GUI
+/// thread draws it while the app changes it. The view itself is only used by the app
+/// thread, so it can hold state that the drawing code does not need, such as an
+/// event sender.
+pub trait View: 'static {
+    /// The data the view draws.
+    type Model: Send + 'static;
+
+    /// Draws `model`. Called on the GUI thread, with the model locked.
+    fn draw(model: &Self::Model, canvas: &mut Canvas);
+
+    /// Handles an input event, returning `true` if it was consumed.
+    ///
+    /// Unconsumed short presses of [`Key::Back`](super::input::Key::Back) go to the
+    /// view dispatcher as a back event.
+    fn input(&mut self, model: Model<'_, Self::Model>, event: InputEvent) -> bool {
+        let _ = (model, event);
+        false
+    }
+
+    /// Called when the view is shown.
+    fn enter(&mut self, model: Model<'_, Self::Model>) {
+        let _ = model;
+    }
+
+    /// Called when the view is hidden, or removed while shown.
+    fn exit(&mut self, model: Model<'_, Self::Model>) {
+        let _ = model;
+    }
+}
+
+/// A `sys::View` backed by a [`View`] implementation.
+pub struct CustomView<T: View> {
+    inner: FuriBox<Inner<T>>,
+}
+
+/// The context of the view callbacks.
+struct Inner<T> {
+    raw: NonNull<sys::View>,
+    view: T,
+}
+
+impl<T: View> CustomView<T> {
+    /// Creates a view showing `model`.
+    pub fn new(view: T, model: T::Model) -> Self {
+        // The SDK allocates the model with `malloc`, which is 8-byte aligned.
+        assert!(
+            mem::align_of::<T::Model>() <= 8,
+            "Model alignment too large"
+        );
+
+        // SAFETY: Alloc always returns a valid non-null pointer or `furi_panic`s.
+        let raw = unsafe { NonNull::new_unchecked(sys::view_alloc()) };
+        let mut inner = FuriBox::new(Inner { raw, view });
+        unsafe {
+            sys::view_allocate_model(
+                raw.as_ptr(),
+                sys::ViewModelTypeLocking,
+                mem::size_of::<T::Model>().max(1),
+            );
+            let data = sys::view_get_model(raw.as_ptr()).cast::<T::Model>();
+            data.write(model);
+            sys::view_commit_model(raw.as_ptr(), false);
+
+            sys::view_set_context(raw.as_ptr(), FuriBox::as_mut_ptr(&mut inner).cast());
+            sys::view_set_draw_callback(raw.as_ptr(), Some(draw_callback::<T>));
+            sys::view_set_input_callback(raw.as_ptr(), Some(input_callback::<T>));
+            sys::view_set_enter_callback(raw.as_ptr(), Some(enter_callback::<T>));
+            sys::view_set_exit_callback(raw.as_ptr(), Some(exit_callback::<T>));
+        }
+
+        Self { inner }
+    }
+
+    /// Obtain raw Furi view handle.
+    ///
+    /// This pointer must not be `free`d or otherwise invalidated. It must not be
+    /// referenced after [`CustomView`] has been dropped.
+    pub fn as_ptr(&self) -> *mut sys::View {
+        self.inner.raw.as_ptr()
+    }
+
+    /// Returns the view's model.
+    pub fn model(&mut self) -> Model<'_, T::Model> {
+        Model::new(self.inner.raw)
+    }
+
+    /// Gets a reference to the [`View`] implementation.
+    pub fn get_ref(&self) -> &T {
+        &self.inner.view
+    }
+
+    /// Gets a mutable reference to the [`View`] implementation.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner.view
+    }
+
+    /// Sets the orientation of the view. Input events are remapped to match.
+    pub fn set_orientation(&self, orientation: Orientation) {
+        let orientation = match orientation {
+            Orientation::Horizontal => sys::ViewOrientationHorizontal,
+            Orientation::HorizontalFlip => sys::ViewOrientationHorizontalFlip,
+            Orientation::Vertical => sys::ViewOrientationVertical,
+            Orientation::VerticalFlip => sys::ViewOrientationVerticalFlip,
+        };
+        unsafe { sys::view_set_orientation(self.as_ptr(), orientation) }
+    }
+}
+
+unsafe impl<T: View> AsView for CustomView<T> {
+    fn as_view_ptr(&self) -> NonNull<sys::View> {
+        self.inner.raw
+    }
+}
+
+impl<T: View> Drop for CustomView<T> {
+    fn drop(&mut self) {
+        unsafe {
+            let data = sys::view_get_model(self.as_ptr()).cast::<T::Model>();
+            ptr::drop_in_place(data);
+            sys::view_commit_model(self.as_ptr(), false);
+            // Frees the model.
+            sys::view_free(self.as_ptr());
+        }
+    }
+}
+
+/// The model of a [`CustomView`].
+///
+/// Lock it with [`Model::lock`] to read or change it. Only one guard can be held at a
+/// time, as the lock is recursive.
+pub struct Model<'a, M> {
+    raw: NonNull<sys::View>,
+    _marker: PhantomData<&'a mut M>,
+}
+
+impl<'a, M> Model<'a, M> {
+    fn new(raw: NonNull<sys::View>) -> Self {
+        Self {
+            raw,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Locks the model until the returned guard is dropped.
+    ///
+    /// If the model was changed through the guard, the view is redrawn when it is
+    /// dropped. The GUI can't draw the view while the model is locked, so keep the
+    /// guard short-lived.
+    pub fn lock(&mut self) -> ModelGuard<'_, M> {
+        let model = unsafe { sys::view_get_model(self.raw.as_ptr()) }.cast::<M>();
+        ModelGuard {
+            raw: self.raw,
+            // SAFETY: The model was allocated and initialized by `CustomView::new`.
+            model: unsafe { NonNull::new_unchecked(model) },
+            changed: false,
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// A RAII implementation of a "scope lock" for the model of a [`CustomView`]. When this
+/// structure is dropped, the model is unlocked, and the view redrawn if it was changed.
+pub struct ModelGuard<'a, M> {
+    raw: NonNull<sys::View>,
+    model: NonNull<M>,
+    changed: bool,
+    _marker: PhantomData<&'a mut M>,
+}
+
+impl<M> Deref for ModelGuard<'_, M> {
+    type Target = M;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { self.model.as_ref() }
+    }
+}
+
+impl<M> DerefMut for ModelGuard<'_, M> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.changed = true;
+        unsafe { self.model.as_mut() }
+    }
+}
+
+impl<M> Drop for ModelGuard<'_, M> {
+    fn drop(&mut self) {
+        unsafe { sys::view_commit_model(self.raw.as_ptr(), self.changed) }
+    }
+}
+
+/// Calls `f` with the view and model of a view callback's `context`.
+///
+/// # Safety
+///
+/// `context` must be the context set by `CustomView::new`.
+unsafe fn with_view<T: View, R>(
+    context: *mut c_void,
+    f: impl FnOnce(&mut T, Model<'_, T::Model>) -> R,
+) -> R {
+    // The callbacks run on the app thread, which also owns the `CustomView`, so the
+    // view isn't borrowed anywhere else while they run.
+    let inner = unsafe { &mut *context.cast::<Inner<T>>() };
+    f(&mut inner.view, Model::new(inner.raw))
+}
+
+unsafe extern "C" fn draw_callback<T: View>(canvas: *mut sys::Canvas, model: *mut c_void) {
+    let model = unsafe { &*model.cast::<T::Model>() };
+    T::draw(model, unsafe { Canvas::from_raw_mut(canvas) });
+}
+
+unsafe extern "C" fn input_callback<T: View>(
+    event: *mut sys::InputEvent,
+    context: *mut c_void,
+) -> bool {
+    match InputEvent::from_sys(unsafe { &*event }) {
+        Some(event) => unsafe {
+            with_view::<T, _>(context, |view, model| view.input(model, event))
+        },
+        None => false,
+    }
+}
+
+unsafe extern "C" fn enter_callback<T: View>(context: *mut c_void) {
+    unsafe { with_view::<T, _>(context, |view, model| view.enter(model)) }
+}
+
+unsafe extern "C" fn exit_callback<T: View>(context: *mut c_void) {
+    unsafe { with_view::<T, _>(context, |view, model| view.exit(model)) }
+}
+
+#[flipperzero_test::tests]
+mod tests {
+    use core::sync::atomic::{AtomicU32, Ordering};
+
+    use super::{CustomView, View};
+    use crate::gui::canvas::Canvas;
+
+    struct Counter {
+        step: u32,
+    }
+
+    impl View for Counter {
+        type Model = u32;
+
+        fn draw(_: &u32, canvas: &mut Canvas) {
+            canvas.clear();
+        }
+    }
+
+    #[test]
+    fn model_lock() {
+        let mut view = CustomView::new(Counter { step: 2 }, 40);
+        let step = view.get_ref().step;
+        *view.model().lock() += step;
+        assert_eq!(*view.model().lock(), 42);
+
+        view.get_mut().step = 3;
+        assert_eq!(view.get_ref().step, 3);
+    }
+
+    static DROPS: AtomicU32 = AtomicU32::new(0);
+
+    struct Counted;
+
+    impl Drop for Counted {
+        fn drop(&mut self) {
+            DROPS.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    struct Empty;
+
+    impl View for Empty {
+        type Model = Counted;
+
+        fn draw(_: &Counted, _: &mut Canvas) {}
+    }
+
+    #[test]
+    fn model_dropped() {
+        let view = CustomView::new(Empty, Counted);
+        assert_eq!(DROPS.load(Ordering::Relaxed), 0);
+        drop(view);
+        assert_eq!(DROPS.load(Ordering::Relaxed), 1);
+    }
+}
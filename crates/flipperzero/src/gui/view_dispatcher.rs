@@ -0,0 +1,510 @@
+//! View dispatcher, which shows one of several views and runs the app's event loop.
+
+use alloc::boxed::Box;
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use core::any::Any;
+use core::cell::Cell;
+use core::ffi::c_void;
+use core::marker::PhantomData;
+use core::ptr::{self, NonNull};
+
+use flipperzero_sys as sys;
+
+use super::view::AsView;
+use super::Gui;
+use crate::furi::time::FuriDuration;
+
+/// Identifies a view added to a [`ViewDispatcher`].
+///
+/// Usually implemented by a fieldless enum:
+///
+/// ```
+/// # use flipperzero::gui::view_dispatcher::ViewId;
+/// #[derive(Clone, Copy, PartialEq, Eq)]
+/// enum AppView {
+///     Menu,
+///     Settings,
+/// }
+///
+/// impl ViewId for AppView {
+///     fn to_raw(self) -> u32 {
+///         self as u32
+///     }
+/// }
+/// ```
+pub trait ViewId: Copy + Eq + 'static {
+    /// Converts the ID to the number used by the SDK.
+    ///
+    /// Distinct IDs must convert to distinct numbers.
+    fn to_raw(self) -> u32;
+}
+
+impl ViewId for u32 {
+    fn to_raw(self) -> u32 {
+        self
+    }
+}
+
+/// An event sent to a [`ViewDispatcher`] with [`ViewDispatcher::send_custom_event`].
+///
+/// The SDK's views send their own custom events, which are passed on to the app's
+/// handler if [`CustomEvent::from_raw`] converts them.
+pub trait CustomEvent: Copy + 'static {
+    /// Converts the event to the number used by the SDK.
+    fn to_raw(self) -> u32;
+
+    /// Converts a number sent to the view dispatcher, returning `None` if it is not an
+    /// event of this type.
+    fn from_raw(raw: u32) -> Option<Self>;
+}
+
+impl CustomEvent for u32 {
+    fn to_raw(self) -> u32 {
+        self
+    }
+
+    fn from_raw(raw: u32) -> Option<Self> {
+        Some(raw)
+    }
+}
+
+/// An event handled by the closure passed to [`ViewDispatcher::run`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DispatcherEvent<E> {
+    /// A custom event, that the current view did not consume.
+    Custom(E),
+    /// A short press of [`Key::Back`](super::input::Key::Back), that the current view
+    /// did not consume.
+    Back,
+    /// The tick period set with [`ViewDispatcher::set_tick_period`] has passed.
+    Tick,
+}
+
+/// Where a [`ViewDispatcher`] shows its views.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViewDispatcherType {
+    /// Full screen, under the status bar. Meant for the desktop.
+    Desktop,
+    /// Under the status bar.
+    Window,
+    /// Full screen, over the status bar.
+    Fullscreen,
+}
+
+impl ViewDispatcherType {
+    pub fn to_sys(&self) -> sys::ViewDispatcherType {
+        match self {
+            Self::Desktop => sys::ViewDispatcherTypeDesktop,
+            Self::Window => sys::ViewDispatcherTypeWindow,
+            Self::Fullscreen => sys::ViewDispatcherTypeFullscreen,
+        }
+    }
+}
+
+/// Shows one of several views, and routes events to the app.
+///
+/// The dispatcher owns the views added to it, and frees them when it is dropped. While
+/// [`ViewDispatcher::run`] is running, input goes to the current view and the events it
+/// does not consume go to the closure passed to `run`, all on the calling thread.
+///
+/// ```no_run
+/// # use flipperzero::gui::view::{CustomView, View};
+/// # use flipperzero::gui::view_dispatcher::{DispatcherEvent, ViewDispatcher, ViewDispatcherType};
+/// # use flipperzero::gui::canvas::Canvas;
+/// struct Hello;
+///
+/// impl View for Hello {
+///     type Model = ();
+///
+///     fn draw(_: &(), canvas: &mut Canvas) {
+///         canvas.clear();
+///     }
+/// }
+///
+/// let mut view_dispatcher = ViewDispatcher::<u32>::new(ViewDispatcherType::Fullscreen);
+/// view_dispatcher.add_view(0, CustomView::new(Hello, ()));
+/// view_dispatcher.switch_to_view(0);
+/// view_dispatcher.run(|_, event| match event {
+///     // Stop the dispatcher.
+///     DispatcherEvent::Back => false,
+///     _ => true,
+/// });
+/// ```
+pub struct ViewDispatcher<E: CustomEvent = u32, V: ViewId = u32> {
+    raw: NonNull<sys::ViewDispatcher>,
+    _gui: Gui,
+    views: Vec<(V, Box<dyn Any>)>,
+    sender: Rc<Cell<Option<NonNull<sys::ViewDispatcher>>>>,
+    _event: PhantomData<fn(E)>,
+}
+
+impl<E: CustomEvent, V: ViewId> ViewDispatcher<E, V> {
+    /// Creates a view dispatcher, and attaches it to the GUI.
+    pub fn new(kind: ViewDispatcherType) -> Self {
+        // SAFETY: Alloc always returns a valid non-null pointer or `furi_panic`s.
+        let raw = unsafe { NonNull::new_unchecked(sys::view_dispatcher_alloc()) };
+        let gui = Gui::open();
+        unsafe { sys::view_dispatcher_attach_to_gui(raw.as_ptr(), gui.as_ptr(), kind.to_sys()) };
+
+        Self {
+            raw,
+            _gui: gui,
+            views: Vec::new(),
+            sender: Rc::new(Cell::new(Some(raw))),
+            _event: PhantomData,
+        }
+    }
+
+    /// Obtain raw Furi view dispatcher handle.
+    ///
+    /// This pointer must not be `free`d or otherwise invalidated. It must not be
+    /// referenced after [`ViewDispatcher`] has been dropped.
+    pub fn as_ptr(&self) -> *mut sys::ViewDispatcher {
+        self.raw.as_ptr()
+    }
+
+    /// Adds `view` under `id`, replacing and dropping any view already added under it.
+    pub fn add_view<T: AsView + 'static>(&mut self, id: V, view: T) {
+        self.remove_view(id);
+        unsafe {
+            sys::view_dispatcher_add_view(self.as_ptr(), id.to_raw(), view.as_view_ptr().as_ptr())
+        };
+        self.views.push((id, Box::new(view)));
+    }
+
+    /// Removes the view added under `id`, returning it.
+    ///
+    /// If it is the current view, no view is shown until the next call to
+    /// [`ViewDispatcher::switch_to_view`].
+    pub fn remove_view(&mut self, id: V) -> Option<Box<dyn Any>> {
+        let index = self.views.iter().position(|(v, _)| *v == id)?;
+        unsafe { sys::view_dispatcher_remove_view(self.as_ptr(), id.to_raw()) };
+        Some(self.views.swap_remove(index).1)
+    }
+
+    /// Returns the view added under `id`, if it is a `T`.
+    pub fn view<T: 'static>(&self, id: V) -> Option<&T> {
+        self.views
+            .iter()
+            .find(|(v, _)| *v == id)
+            .and_then(|(_, view)| view.downcast_ref())
+    }
+
+    /// Returns the view added under `id`, if it is a `T`.
+    pub fn view_mut<T: 'static>(&mut self, id: V) -> Option<&mut T> {
+        self.views
+            .iter_mut()
+            .find(|(v, _)| *v == id)
+            .and_then(|(_, view)| view.downcast_mut())
+    }
+
+    /// Shows the view added under `id`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no view was added under `id`.
+    pub fn switch_to_view(&mut self, id: V) {
+        assert!(
+            self.views.iter().any(|(v, _)| *v == id),
+            "No view with this ID"
+        );
+        unsafe { sys::view_dispatcher_switch_to_view(self.as_ptr(), id.to_raw()) }
+    }
+
+    /// Moves the view dispatcher to the top of its GUI layer.
+    pub fn send_to_front(&self) {
+        unsafe { sys::view_dispatcher_send_to_front(self.as_ptr()) }
+    }
+
+    /// Moves the view dispatcher to the bottom of its GUI layer.
+    pub fn send_to_back(&self) {
+        unsafe { sys::view_dispatcher_send_to_back(self.as_ptr()) }
+    }
+
+    /// Sets how often [`DispatcherEvent::Tick`] is sent, or stops sending it.
+    ///
+    /// Ticks are only sent while nothing else is happening, so the period is the
+    /// shortest time between two of them.
+    pub fn set_tick_period(&mut self, period: Option<FuriDuration>) {
+        let (callback, period) = match period {
+            Some(period) => (
+                Some(tick_callback::<E> as unsafe extern "C" fn(*mut c_void)),
+                period,
+            ),
+            None => (None, FuriDuration::WAIT_FOREVER),
+        };
+        unsafe {
+            sys::view_dispatcher_set_tick_event_callback(self.as_ptr(), callback, period.as_ticks())
+        };
+    }
+
+    /// Sends a custom event, which is handled by the current view or the closure
+    /// passed to [`ViewDispatcher::run`].
+    ///
+    /// The event is queued; it is handled once control returns to the dispatcher.
+    pub fn send_custom_event(&self, event: E) {
+        unsafe { sys::view_dispatcher_send_custom_event(self.as_ptr(), event.to_raw()) }
+    }
+
+    /// Returns a handle for sending custom events from views' closures, which don't
+    /// have access to the dispatcher.
+    pub fn sender(&self) -> EventSender<E> {
+        EventSender {
+            raw: self.sender.clone(),
+            _event: PhantomData,
+        }
+    }
+
+    /// Stops [`ViewDispatcher::run`], once the event being handled has been.
+    pub fn stop(&self) {
+        unsafe { sys::view_dispatcher_stop(self.as_ptr()) }
+    }
+
+    /// Runs the event loop until the dispatcher is stopped, calling `handler` with every
+    /// event not consumed by the current view.
+    ///
+    /// `handler` returns whether it handled the event. Returning `false` for
+    /// [`DispatcherEvent::Back`] stops the dispatcher.
+    pub fn run<F>(&mut self, mut handler: F)
+    where
+        F: FnMut(&mut DispatcherHandle<'_, E, V>, DispatcherEvent<E>) -> bool,
+    {
+        let raw = self.raw;
+        let mut handle = DispatcherHandle::new(self);
+        // SAFETY: The dispatcher is only used through `handle` until the event loop
+        // returns, which can't replace or drop it.
+        unsafe { run_event_loop(raw, &mut |event| handler(&mut handle, event)) }
+    }
+}
+
+impl<E: CustomEvent, V: ViewId> Drop for ViewDispatcher<E, V> {
+    fn drop(&mut self) {
+        self.sender.set(None);
+        unsafe {
+            for (id, _) in &self.views {
+                sys::view_dispatcher_remove_view(self.as_ptr(), id.to_raw());
+            }
+            // Also removes the view port from the GUI, which is still open.
+            sys::view_dispatcher_free(self.as_ptr());
+        }
+    }
+}
+
+/// A [`ViewDispatcher`] whose event loop is running, passed to the closure given to
+/// [`ViewDispatcher::run`].
+///
+/// It has the methods of the dispatcher that can be used while it runs, but can't
+/// replace or drop the dispatcher.
+pub struct DispatcherHandle<'a, E: CustomEvent = u32, V: ViewId = u32> {
+    inner: &'a mut ViewDispatcher<E, V>,
+}
+
+impl<'a, E: CustomEvent, V: ViewId> DispatcherHandle<'a, E, V> {
+    pub(crate) fn new(inner: &'a mut ViewDispatcher<E, V>) -> Self {
+        Self { inner }
+    }
+
+    /// Obtain raw Furi view dispatcher handle.
+    ///
+    /// This pointer must not be `free`d or otherwise invalidated. It must not be
+    /// referenced after [`ViewDispatcher`] has been dropped.
+    pub fn as_ptr(&self) -> *mut sys::ViewDispatcher {
+        self.inner.as_ptr()
+    }
+
+    /// Adds `view` under `id`, like [`ViewDispatcher::add_view`].
+    pub fn add_view<T: AsView + 'static>(&mut self, id: V, view: T) {
+        self.inner.add_view(id, view)
+    }
+
+    /// Removes the view added under `id`, like [`ViewDispatcher::remove_view`].
+    pub fn remove_view(&mut self, id: V) -> Option<Box<dyn Any>> {
+        self.inner.remove_view(id)
+    }
+
+    /// Returns the view added under `id`, if it is a `T`.
+    pub fn view<T: 'static>(&self, id: V) -> Option<&T> {
+        self.inner.view(id)
+    }
+
+    /// Returns the view added under `id`, if it is a `T`.
+    pub fn view_mut<T: 'static>(&mut self, id: V) -> Option<&mut T> {
+        self.inner.view_mut(id)
+    }
+
+    /// Shows the view added under `id`, like [`ViewDispatcher::switch_to_view`].
+    pub fn switch_to_view(&mut self, id: V) {
+        self.inner.switch_to_view(id)
+    }
+
+    /// Moves the view dispatcher to the top of its GUI layer.
+    pub fn send_to_front(&self) {
+        self.inner.send_to_front()
+    }
+
+    /// Moves the view dispatcher to the bottom of its GUI layer.
+    pub fn send_to_back(&self) {
+        self.inner.send_to_back()
+    }
+
+    /// Sets how often [`DispatcherEvent::Tick`] is sent, like
+    /// [`ViewDispatcher::set_tick_period`].
+    pub fn set_tick_period(&mut self, period: Option<FuriDuration>) {
+        self.inner.set_tick_period(period)
+    }
+
+    /// Sends a custom event, like [`ViewDispatcher::send_custom_event`].
+    pub fn send_custom_event(&self, event: E) {
+        self.inner.send_custom_event(event)
+    }
+
+    /// Returns a handle for sending custom events, like [`ViewDispatcher::sender`].
+    pub fn sender(&self) -> EventSender<E> {
+        self.inner.sender()
+    }
+
+    /// Stops the event loop, once the event being handled has been.
+    pub fn stop(&self) {
+        self.inner.stop()
+    }
+}
+
+/// Sends custom events to a [`ViewDispatcher`], for example from the closures of its
+/// views.
+///
+/// Events sent after the dispatcher has been dropped are discarded.
+pub struct EventSender<E: CustomEvent> {
+    raw: Rc<Cell<Option<NonNull<sys::ViewDispatcher>>>>,
+    _event: PhantomData<fn(E)>,
+}
+
+impl<E: CustomEvent> Clone for EventSender<E> {
+    fn clone(&self) -> Self {
+        Self {
+            raw: self.raw.clone(),
+            _event: PhantomData,
+        }
+    }
+}
+
+impl<E: CustomEvent> EventSender<E> {
+    /// Sends a custom event, like [`ViewDispatcher::send_custom_event`].
+    pub fn send(&self, event: E) {
+        if let Some(raw) = self.raw.get() {
+            unsafe { sys::view_dispatcher_send_custom_event(raw.as_ptr(), event.to_raw()) }
+        }
+    }
+}
+
+type Handler<'a, E> = dyn FnMut(DispatcherEvent<E>) -> bool + 'a;
+
+/// Runs the event loop of `raw` until it is stopped, calling `handler` with every event
+/// not consumed by the current view.
+///
+/// # Safety
+///
+/// `raw` must be a valid view dispatcher, whose event callbacks are only set by this
+/// module.
+pub(crate) unsafe fn run_event_loop<E: CustomEvent>(
+    raw: NonNull<sys::ViewDispatcher>,
+    mut handler: &mut Handler<'_, E>,
+) {
+    let raw = raw.as_ptr();
+    unsafe {
+        sys::view_dispatcher_set_event_callback_context(raw, ptr::from_mut(&mut handler).cast());
+        sys::view_dispatcher_set_custom_event_callback(raw, Some(custom_callback::<E>));
+        sys::view_dispatcher_set_navigation_event_callback(raw, Some(navigation_callback::<E>));
+
+        sys::view_dispatcher_run(raw);
+
+        sys::view_dispatcher_set_custom_event_callback(raw, None);
+        sys::view_dispatcher_set_navigation_event_callback(raw, None);
+        sys::view_dispatcher_set_event_callback_context(raw, ptr::null_mut());
+    }
+}
+
+/// Calls the handler passed to `run_event_loop` with `event`.
+///
+/// # Safety
+///
+/// `context` must be null, or the context set by a running `run_event_loop`.
+unsafe fn handle<E: CustomEvent>(context: *mut c_void, event: DispatcherEvent<E>) -> bool {
+    match NonNull::new(context.cast::<&mut Handler<'_, E>>()) {
+        Some(mut handler) => (unsafe { handler.as_mut() })(event),
+        None => false,
+    }
+}
+
+unsafe extern "C" fn custom_callback<E: CustomEvent>(context: *mut c_void, event: u32) -> bool {
+    match E::from_raw(event) {
+        Some(event) => unsafe { handle(context, DispatcherEvent::Custom(event)) },
+        None => false,
+    }
+}
+
+unsafe extern "C" fn navigation_callback<E: CustomEvent>(context: *mut c_void) -> bool {
+    unsafe { handle::<E>(context, DispatcherEvent::Back) }
+}
+
+unsafe extern "C" fn tick_callback<E: CustomEvent>(context: *mut c_void) {
+    unsafe { handle::<E>(context, DispatcherEvent::Tick) };
+}
+
+#[flipperzero_test::tests]
+mod tests {
+    use core::sync::atomic::{AtomicU32, Ordering};
+
+    use super::{DispatcherEvent, ViewDispatcher, ViewDispatcherType};
+    use crate::gui::canvas::Canvas;
+    use crate::gui::view::{CustomView, Model, View};
+
+    static ENTERS: AtomicU32 = AtomicU32::new(0);
+
+    struct Entered;
+
+    impl View for Entered {
+        type Model = u32;
+
+        fn draw(_: &u32, _: &mut Canvas) {}
+
+        fn enter(&mut self, mut model: Model<'_, u32>) {
+            *model.lock() += 1;
+            ENTERS.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn events() {
+        let mut view_dispatcher = ViewDispatcher::<u32>::new(ViewDispatcherType::Window);
+        view_dispatcher.add_view(1, CustomView::new(Entered, 0));
+        view_dispatcher.switch_to_view(1);
+        assert_eq!(ENTERS.load(Ordering::Relaxed), 1);
+
+        let sender = view_dispatcher.sender();
+        view_dispatcher.send_custom_event(7);
+        let mut events = 0;
+        view_dispatcher.run(|view_dispatcher, event| {
+            match event {
+                DispatcherEvent::Custom(7) => sender.send(8),
+                DispatcherEvent::Custom(8) => {
+                    let view = view_dispatcher
+                        .view_mut::<CustomView<Entered>>(1)
+                        .expect("view was added");
+                    assert_eq!(*view.model().lock(), 1);
+                    view_dispatcher.stop();
+                }
+                event => panic!("unexpected event {event:?}"),
+            }
+            events += 1;
+            true
+        });
+        assert_eq!(events, 2);
+
+        assert!(view_dispatcher.view::<u32>(1).is_none());
+        assert!(view_dispatcher.remove_view(1).is_some());
+        assert!(view_dispatcher.remove_view(1).is_none());
+        drop(view_dispatcher);
+        sender.send(9);
+    }
+}
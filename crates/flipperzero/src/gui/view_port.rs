@@ -0,0 +1,239 @@
+//! View ports.
+
+use core::ffi::c_void;
+use core::ptr::NonNull;
+
+use flipperzero_sys as sys;
+use flipperzero_sys::furi::FuriBox;
+
+use super::canvas::Canvas;
+use super::input::InputEvent;
+use super::{Gui, GuiLayer};
+
+/// Orientation of a [`ViewPort`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    Horizontal,
+    HorizontalFlip,
+    Vertical,
+    VerticalFlip,
+}
+
+impl Orientation {
+    /// Converts an SDK orientation, returning `None` if it is not a known orientation.
+    pub fn from_sys(orientation: sys::ViewPortOrientation) -> Option<Self> {
+        Some(match orientation {
+            sys::ViewPortOrientationHorizontal => Self::Horizontal,
+            sys::ViewPortOrientationHorizontalFlip => Self::HorizontalFlip,
+            sys::ViewPortOrientationVertical => Self::Vertical,
+            sys::ViewPortOrientationVerticalFlip => Self::VerticalFlip,
+            _ => return None,
+        })
+    }
+
+    pub fn to_sys(&self) -> sys::ViewPortOrientation {
+        match self {
+            Self::Horizontal => sys::ViewPortOrientationHorizontal,
+            Self::HorizontalFlip => sys::ViewPortOrientationHorizontalFlip,
+            Self::Vertical => sys::ViewPortOrientationVertical,
+            Self::VerticalFlip => sys::ViewPortOrientationVerticalFlip,
+        }
+    }
+}
+
+/// A view port on a [`GuiLayer`], which draws and handles input with closures.
+///
+/// The view port is added to the GUI when it is created, and removed when it is
+/// dropped. Both closures are called on the GUI thread, so they must be `Send`, and
+/// state shared with the rest of the app needs to be synchronized, for example with a
+/// [`Mutex`](crate::furi::sync::Mutex). They must also be `'static`, because a view
+/// port that is leaked with [`mem::forget`](core::mem::forget) is never removed.
+///
+/// The GUI only redraws the view port when asked to with [`ViewPort::update`].
+///
+/// ```no_run
+/// # use core::sync::atomic::{AtomicBool, Ordering};
+/// # use flipperzero::gui::{GuiLayer, ViewPort};
+/// # use flipperzero::gui::input::{InputType, Key};
+/// static EXIT: AtomicBool = AtomicBool::new(false);
+///
+/// let view_port = ViewPort::new(
+///     GuiLayer::Fullscreen,
+///     |canvas| canvas.clear(),
+///     |event| {
+///         if event.key == Key::Back && event.kind == InputType::Short {
+///             EXIT.store(true, Ordering::Relaxed);
+///         }
+///     },
+/// );
+/// while !EXIT.load(Ordering::Relaxed) {
+///     view_port.update();
+/// #   break;
+/// }
+/// ```
+pub struct ViewPort<D, I>
+where
+    D: FnMut(&mut Canvas) + Send + 'static,
+    I: FnMut(InputEvent) + Send + 'static,
+{
+    raw: NonNull<sys::ViewPort>,
+    gui: Gui,
+    _draw: FuriBox<D>,
+    _input: FuriBox<I>,
+}
+
+impl<D, I> ViewPort<D, I>
+where
+    D: FnMut(&mut Canvas) + Send + 'static,
+    I: FnMut(InputEvent) + Send + 'static,
+{
+    /// Creates a view port and adds it to `layer`.
+    ///
+    /// `draw` is called whenever the GUI redraws the view port, and `input` with every
+    /// input event while the view port is the topmost enabled one that takes input.
+    pub fn new(layer: GuiLayer, draw: D, input: I) -> Self {
+        let mut draw = FuriBox::new(draw);
+        let mut input = FuriBox::new(input);
+
+        // SAFETY: Alloc always returns a valid non-null pointer or `furi_panic`s.
+        let raw = unsafe { NonNull::new_unchecked(sys::view_port_alloc()) };
+        let gui = Gui::open();
+        unsafe {
+            sys::view_port_draw_callback_set(
+                raw.as_ptr(),
+                Some(draw_callback::<D>),
+                FuriBox::as_mut_ptr(&mut draw).cast(),
+            );
+            sys::view_port_input_callback_set(
+                raw.as_ptr(),
+                Some(input_callback::<I>),
+                FuriBox::as_mut_ptr(&mut input).cast(),
+            );
+            sys::gui_add_view_port(gui.as_ptr(), raw.as_ptr(), layer.to_sys());
+        }
+
+        Self {
+            raw,
+            gui,
+            _draw: draw,
+            _input: input,
+        }
+    }
+
+    /// Obtain raw Furi view port handle.
+    ///
+    /// This pointer must not be `free`d or otherwise invalidated. It must not be
+    /// referenced after [`ViewPort`] has been dropped.
+    pub fn as_ptr(&self) -> *mut sys::ViewPort {
+        self.raw.as_ptr()
+    }
+
+    /// Asks the GUI to redraw the view port.
+    ///
+    /// The draw closure is called later, on the GUI thread.
+    pub fn update(&self) {
+        unsafe { sys::view_port_update(self.as_ptr()) }
+    }
+
+    /// Enables or disables the view port. A disabled view port is neither drawn nor
+    /// sent input.
+    pub fn set_enabled(&self, enabled: bool) {
+        unsafe { sys::view_port_enabled_set(self.as_ptr(), enabled) }
+    }
+
+    /// Returns `true` if the view port is enabled.
+    pub fn is_enabled(&self) -> bool {
+        unsafe { sys::view_port_is_enabled(self.as_ptr()) }
+    }
+
+    /// Sets the orientation of the canvas passed to the draw closure. Input events
+    /// are remapped to match, so that `Up` is always towards the top of the canvas.
+    pub fn set_orientation(&self, orientation: Orientation) {
+        unsafe { sys::view_port_set_orientation(self.as_ptr(), orientation.to_sys()) }
+    }
+
+    /// Returns the orientation of the view port.
+    pub fn orientation(&self) -> Orientation {
+        Orientation::from_sys(unsafe { sys::view_port_get_orientation(self.as_ptr()) })
+            .expect("Invalid orientation")
+    }
+
+    /// Sets the width of the view port, which is only used by status bar layers.
+    pub fn set_width(&self, width: u8) {
+        unsafe { sys::view_port_set_width(self.as_ptr(), width) }
+    }
+
+    /// Sets the height of the view port.
+    pub fn set_height(&self, height: u8) {
+        unsafe { sys::view_port_set_height(self.as_ptr(), height) }
+    }
+
+    /// Moves the view port to `layer`, on top of the view ports already there.
+    pub fn set_layer(&self, layer: GuiLayer) {
+        unsafe {
+            sys::gui_remove_view_port(self.gui.as_ptr(), self.as_ptr());
+            sys::gui_add_view_port(self.gui.as_ptr(), self.as_ptr(), layer.to_sys());
+        }
+    }
+
+    /// Moves the view port to the top of its layer.
+    pub fn send_to_front(&self) {
+        unsafe { sys::gui_view_port_send_to_front(self.gui.as_ptr(), self.as_ptr()) }
+    }
+}
+
+impl<D, I> Drop for ViewPort<D, I>
+where
+    D: FnMut(&mut Canvas) + Send + 'static,
+    I: FnMut(InputEvent) + Send + 'static,
+{
+    fn drop(&mut self) {
+        unsafe {
+            sys::view_port_enabled_set(self.as_ptr(), false);
+            // The GUI holds its lock while calling the closures, so once this returns
+            // they are no longer running and can be freed.
+            sys::gui_remove_view_port(self.gui.as_ptr(), self.as_ptr());
+            sys::view_port_free(self.as_ptr());
+        }
+    }
+}
+
+unsafe extern "C" fn draw_callback<D>(canvas: *mut sys::Canvas, context: *mut c_void)
+where
+    D: FnMut(&mut Canvas),
+{
+    let draw = unsafe { &mut *context.cast::<D>() };
+    draw(unsafe { Canvas::from_raw_mut(canvas) });
+}
+
+unsafe extern "C" fn input_callback<I>(event: *mut sys::InputEvent, context: *mut c_void)
+where
+    I: FnMut(InputEvent),
+{
+    let input = unsafe { &mut *context.cast::<I>() };
+    if let Some(event) = InputEvent::from_sys(unsafe { &*event }) {
+        input(event);
+    }
+}
+
+#[flipperzero_test::tests]
+mod tests {
+    use super::{Orientation, ViewPort};
+    use crate::gui::GuiLayer;
+
+    #[test]
+    fn settings() {
+        let view_port = ViewPort::new(GuiLayer::Window, |_| {}, |_| {});
+        assert!(view_port.is_enabled());
+        assert_eq!(view_port.orientation(), Orientation::Horizontal);
+
+        view_port.set_orientation(Orientation::VerticalFlip);
+        assert_eq!(view_port.orientation(), Orientation::VerticalFlip);
+        view_port.set_enabled(false);
+        assert!(!view_port.is_enabled());
+
+        view_port.set_layer(GuiLayer::Desktop);
+        view_port.set_enabled(true);
+        view_port.update();
+    }
+}
@@ -0,0 +1,311 @@
+//! Widget, a view composed of text, shapes, icons and buttons.
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::ffi::{c_void, CStr};
+use core::ptr::NonNull;
+
+use flipperzero_sys as sys;
+use flipperzero_sys::furi::FuriBox;
+
+use super::canvas::{Align, Font};
+use super::input::InputType;
+use super::view::AsView;
+
+/// The position of a [`Widget`] button, at the bottom of the screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GuiButtonType {
+    Left,
+    Center,
+    Right,
+}
+
+impl GuiButtonType {
+    pub fn from_sys(sys: sys::GuiButtonType) -> Option<Self> {
+        match sys {
+            sys::GuiButtonTypeLeft => Some(Self::Left),
+            sys::GuiButtonTypeCenter => Some(Self::Center),
+            sys::GuiButtonTypeRight => Some(Self::Right),
+            _ => None,
+        }
+    }
+
+    pub fn to_sys(&self) -> sys::GuiButtonType {
+        match self {
+            Self::Left => sys::GuiButtonTypeLeft,
+            Self::Center => sys::GuiButtonTypeCenter,
+            Self::Right => sys::GuiButtonTypeRight,
+        }
+    }
+}
+
+type Callback = Box<dyn FnMut(InputType)>;
+
+/// A view composed of elements, such as text, shapes, icons and buttons, drawn in the
+/// order they are added.
+///
+/// Elements are added with methods that can be chained:
+///
+/// ```
+/// # use flipperzero::gui::canvas::{Align, Font};
+/// # use flipperzero::gui::widget::{GuiButtonType, Widget};
+/// let mut widget = Widget::new();
+/// widget
+///     .add_string(64, 4, Align::Center, Align::Top, Font::Primary, c"Saved")
+///     .add_frame(0, 0, 128, 64, 3)
+///     .add_button(GuiButtonType::Center, c"OK", |_| {});
+/// ```
+///
+/// The SDK copies the text of elements, so it need not outlive the call that adds
+/// them. Button closures are called on the thread running the
+/// [`ViewDispatcher`](super::ViewDispatcher) the widget is added to, and are kept until
+/// [`Widget::reset`] is called or the widget is dropped.
+pub struct Widget {
+    raw: NonNull<sys::Widget>,
+    callbacks: Vec<FuriBox<Callback>>,
+}
+
+impl Widget {
+    /// Creates an empty widget.
+    pub fn new() -> Self {
+        Self {
+            // SAFETY: Alloc always returns a valid non-null pointer or `furi_panic`s.
+            raw: unsafe { NonNull::new_unchecked(sys::widget_alloc()) },
+            callbacks: Vec::new(),
+        }
+    }
+
+    /// Obtain raw Furi widget handle.
+    ///
+    /// This pointer must not be `free`d or otherwise invalidated. It must not be
+    /// referenced after [`Widget`] has been dropped.
+    pub fn as_ptr(&self) -> *mut sys::Widget {
+        self.raw.as_ptr()
+    }
+
+    /// Adds a line of text, aligned to (`x`, `y`).
+    pub fn add_string(
+        &mut self,
+        x: u8,
+        y: u8,
+        horizontal: Align,
+        vertical: Align,
+        font: Font,
+        text: &CStr,
+    ) -> &mut Self {
+        unsafe {
+            sys::widget_add_string_element(
+                self.as_ptr(),
+                x,
+                y,
+                horizontal.to_sys(),
+                vertical.to_sys(),
+                font.to_sys(),
+                text.as_ptr(),
+            )
+        };
+        self
+    }
+
+    /// Adds text split into lines at each `\n`, aligned to (`x`, `y`).
+    pub fn add_string_multiline(
+        &mut self,
+        x: u8,
+        y: u8,
+        horizontal: Align,
+        vertical: Align,
+        font: Font,
+        text: &CStr,
+    ) -> &mut Self {
+        unsafe {
+            sys::widget_add_string_multiline_element(
+                self.as_ptr(),
+                x,
+                y,
+                horizontal.to_sys(),
+                vertical.to_sys(),
+                font.to_sys(),
+                text.as_ptr(),
+            )
+        };
+        self
+    }
+
+    /// Adds text wrapped to fit a box, aligned within it.
+    ///
+    /// The text may be formatted: a line starting with `\e#` is bold, `\e*` is
+    /// monospaced, and `\e!` is inverted. If `strip_to_dots` is `true`, text that
+    /// doesn't fit ends with `...`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_text_box(
+        &mut self,
+        x: u8,
+        y: u8,
+        width: u8,
+        height: u8,
+        horizontal: Align,
+        vertical: Align,
+        text: &CStr,
+        strip_to_dots: bool,
+    ) -> &mut Self {
+        unsafe {
+            sys::widget_add_text_box_element(
+                self.as_ptr(),
+                x,
+                y,
+                width,
+                height,
+                horizontal.to_sys(),
+                vertical.to_sys(),
+                text.as_ptr(),
+                strip_to_dots,
+            )
+        };
+        self
+    }
+
+    /// Adds text wrapped to fit a box, scrolled with Up and Down.
+    ///
+    /// The text may be formatted: a line starting with `\e#` is bold, `\e*` is
+    /// monospaced, `\e^` is centered and `\e$` is aligned right.
+    pub fn add_text_scroll(
+        &mut self,
+        x: u8,
+        y: u8,
+        width: u8,
+        height: u8,
+        text: &CStr,
+    ) -> &mut Self {
+        unsafe {
+            sys::widget_add_text_scroll_element(self.as_ptr(), x, y, width, height, text.as_ptr())
+        };
+        self
+    }
+
+    /// Adds an icon, with its top left corner at (`x`, `y`).
+    pub fn add_icon(&mut self, x: u8, y: u8, icon: &'static sys::Icon) -> &mut Self {
+        unsafe { sys::widget_add_icon_element(self.as_ptr(), x, y, icon) };
+        self
+    }
+
+    /// Adds the outline of a rectangle with corners of `radius`.
+    pub fn add_frame(&mut self, x: u8, y: u8, width: u8, height: u8, radius: u8) -> &mut Self {
+        unsafe { sys::widget_add_rect_element(self.as_ptr(), x, y, width, height, radius, false) };
+        self
+    }
+
+    /// Adds a filled rectangle with corners of `radius`.
+    pub fn add_box(&mut self, x: u8, y: u8, width: u8, height: u8, radius: u8) -> &mut Self {
+        unsafe { sys::widget_add_rect_element(self.as_ptr(), x, y, width, height, radius, true) };
+        self
+    }
+
+    /// Adds a circle centered at (`x`, `y`), filled if `fill` is `true`.
+    pub fn add_circle(&mut self, x: u8, y: u8, radius: u8, fill: bool) -> &mut Self {
+        unsafe { sys::widget_add_circle_element(self.as_ptr(), x, y, radius, fill) };
+        self
+    }
+
+    /// Adds a line from (`x1`, `y1`) to (`x2`, `y2`).
+    pub fn add_line(&mut self, x1: u8, y1: u8, x2: u8, y2: u8) -> &mut Self {
+        unsafe { sys::widget_add_line_element(self.as_ptr(), x1, y1, x2, y2) };
+        self
+    }
+
+    /// Adds a button at the bottom of the screen, pressed with the key of its
+    /// position. `callback` is called with the presses and releases of the key.
+    pub fn add_button<F>(&mut self, button: GuiButtonType, label: &CStr, callback: F) -> &mut Self
+    where
+        F: FnMut(InputType) + 'static,
+    {
+        let mut callback: FuriBox<Callback> = FuriBox::new(Box::new(callback));
+        unsafe {
+            sys::widget_add_button_element(
+                self.as_ptr(),
+                button.to_sys(),
+                label.as_ptr(),
+                Some(button_callback),
+                FuriBox::as_mut_ptr(&mut callback).cast(),
+            )
+        };
+        self.callbacks.push(callback);
+        self
+    }
+
+    /// Removes all elements, dropping the closures of the buttons.
+    pub fn reset(&mut self) {
+        unsafe { sys::widget_reset(self.as_ptr()) };
+        self.callbacks.clear();
+    }
+}
+
+impl Default for Widget {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+unsafe impl AsView for Widget {
+    fn as_view_ptr(&self) -> NonNull<sys::View> {
+        // SAFETY: The view is allocated with the widget.
+        unsafe { NonNull::new_unchecked(sys::widget_get_view(self.as_ptr())) }
+    }
+}
+
+impl Drop for Widget {
+    fn drop(&mut self) {
+        unsafe { sys::widget_free(self.as_ptr()) }
+    }
+}
+
+unsafe extern "C" fn button_callback(
+    _button: sys::GuiButtonType,
+    kind: sys::InputType,
+    context: *mut c_void,
+) {
+    let callback = unsafe { &mut *context.cast::<Callback>() };
+    if let Some(kind) = InputType::from_sys(kind) {
+        callback(kind);
+    }
+}
+
+#[flipperzero_test::tests]
+mod tests {
+    use alloc::rc::Rc;
+
+    use super::{GuiButtonType, Widget};
+    use crate::gui::canvas::{Align, Font};
+
+    #[test]
+    fn elements() {
+        let text = c"Text";
+        let pressed = Rc::new(());
+
+        let mut widget = Widget::new();
+        widget
+            .add_string(64, 0, Align::Center, Align::Top, Font::Primary, text)
+            .add_string_multiline(0, 16, Align::Left, Align::Top, Font::Secondary, text)
+            .add_text_box(0, 32, 128, 16, Align::Left, Align::Top, text, true)
+            .add_text_scroll(0, 48, 128, 16, text)
+            .add_frame(0, 0, 128, 64, 3)
+            .add_box(0, 0, 8, 8, 0)
+            .add_circle(64, 32, 4, false)
+            .add_line(0, 0, 127, 63);
+        for button in [GuiButtonType::Left, GuiButtonType::Right] {
+            let pressed = pressed.clone();
+            widget.add_button(button, text, move |_| drop(pressed.clone()));
+        }
+        assert_eq!(Rc::strong_count(&pressed), 3);
+
+        widget.reset();
+        assert_eq!(Rc::strong_count(&pressed), 1);
+
+        // Dropping the widget drops its button closures.
+        let button_pressed = pressed.clone();
+        widget.add_button(GuiButtonType::Center, text, move |_| {
+            drop(button_pressed.clone())
+        });
+        drop(widget);
+        assert_eq!(Rc::strong_count(&pressed), 1);
+    }
+}
@@ -0,0 +1,720 @@
+//! iButton (1-Wire and similar contact keys).
+//!
+//! Supports reading, writing and emulating Dallas (DS1990, DS1992, DS1996, ...), Cyfral
+//! and Metakom keys, as well as loading and saving `.ibtn` key files.
+//!
+//! Completion events are delivered to a closure running on the worker thread. To handle
+//! them on another thread, forward them through a [`MessageQueue`]:
+//!
+//! ```no_run
+//! # extern crate alloc;
+//! # use alloc::sync::Arc;
+//! # use flipperzero::furi::message_queue::MessageQueue;
+//! # use flipperzero::furi::time::FuriDuration;
+//! # use flipperzero::ibutton::{IButtonKey, IButtonWorker};
+//! let mut worker = IButtonWorker::new();
+//! let key = IButtonKey::new(worker.protocols());
+//! let queue = Arc::new(MessageQueue::new(1));
+//!
+//! let sender = queue.clone();
+//! let reader = worker.read(key, move || {
+//!     let _ = sender.put((), FuriDuration::ZERO);
+//! });
+//! queue.get(FuriDuration::MAX).unwrap();
+//! let key = reader.stop();
+//!
+//! let data = key.data(worker.protocols());
+//! ```
+//!
+//! [`MessageQueue`]: crate::furi::message_queue::MessageQueue
+
+use core::ffi::{c_void, CStr};
+use core::fmt;
+use core::ptr::NonNull;
+
+use bitflags::bitflags;
+use flipperzero_sys as sys;
+use sys::furi::FuriBox;
+
+use crate::furi::string::FuriString;
+
+/// Identifier of an iButton protocol.
+///
+/// Protocol ids are assigned by the firmware; use [`IButtonProtocols::id_by_name`] or
+/// [`IButtonProtocols::ids`] to obtain them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ProtocolId(sys::iButtonProtocolId);
+
+impl ProtocolId {
+    /// Converts a raw protocol id, returning `None` if it does not name a known protocol.
+    pub fn from_sys(id: sys::iButtonProtocolId) -> Option<Self> {
+        (0..IButtonProtocols::count() as sys::iButtonProtocolId)
+            .contains(&id)
+            .then_some(Self(id))
+    }
+
+    pub fn to_sys(&self) -> sys::iButtonProtocolId {
+        self.0
+    }
+}
+
+bitflags! {
+    /// Capabilities of an iButton protocol.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct Features: u32 {
+        /// Keys carry additional data (e.g. memory contents) besides their ID.
+        const EXT_DATA = sys::iButtonProtocolFeatureExtData.0 as u32;
+        /// The key's ID can be written to a blank.
+        const WRITE_ID = sys::iButtonProtocolFeatureWriteId.0 as u32;
+        /// The key's full data can be copied to a key of the same type.
+        const WRITE_COPY = sys::iButtonProtocolFeatureWriteCopy.0 as u32;
+    }
+}
+
+/// Collection of all iButton protocols known to the firmware.
+pub struct IButtonProtocols {
+    raw: NonNull<sys::iButtonProtocols>,
+}
+
+impl IButtonProtocols {
+    /// Allocates the protocol collection.
+    pub fn new() -> Self {
+        Self {
+            // SAFETY: Alloc always returns a valid non-null pointer or `furi_panic`s.
+            raw: unsafe { NonNull::new_unchecked(sys::ibutton_protocols_alloc()) },
+        }
+    }
+
+    /// Obtain raw iButton protocols.
+    ///
+    /// This pointer must not be `free`d or otherwise invalidated.
+    /// It must not be referenced after [`IButtonProtocols`] has been dropped.
+    #[inline]
+    pub fn as_ptr(&self) -> *mut sys::iButtonProtocols {
+        self.raw.as_ptr()
+    }
+
+    /// Total number of protocols.
+    pub fn count() -> usize {
+        unsafe { sys::ibutton_protocols_get_protocol_count() as usize }
+    }
+
+    /// Iterates over the ids of all protocols.
+    pub fn ids() -> impl Iterator<Item = ProtocolId> {
+        (0..Self::count() as sys::iButtonProtocolId).map(ProtocolId)
+    }
+
+    /// Largest data size of any protocol, in bytes.
+    pub fn max_data_size(&self) -> usize {
+        unsafe { sys::ibutton_protocols_get_max_data_size(self.as_ptr()) }
+    }
+
+    /// Finds a protocol by its name (e.g. `c"DS1990"`).
+    pub fn id_by_name(&self, name: &CStr) -> Option<ProtocolId> {
+        ProtocolId::from_sys(unsafe {
+            sys::ibutton_protocols_get_id_by_name(self.as_ptr(), name.as_ptr())
+        })
+    }
+
+    /// Name of the protocol, as used in `.ibtn` key files.
+    pub fn name(&self, id: ProtocolId) -> &'static CStr {
+        // SAFETY: Protocol names are static nul-terminated strings.
+        unsafe { CStr::from_ptr(sys::ibutton_protocols_get_name(self.as_ptr(), id.0)) }
+    }
+
+    /// Manufacturer of keys using the protocol.
+    pub fn manufacturer(&self, id: ProtocolId) -> &'static CStr {
+        // SAFETY: Manufacturer names are static nul-terminated strings.
+        unsafe { CStr::from_ptr(sys::ibutton_protocols_get_manufacturer(self.as_ptr(), id.0)) }
+    }
+
+    /// Capabilities of the protocol.
+    pub fn features(&self, id: ProtocolId) -> Features {
+        Features::from_bits_truncate(unsafe {
+            sys::ibutton_protocols_get_features(self.as_ptr(), id.0)
+        })
+    }
+
+    /// Checks whether the key's data is valid for its protocol.
+    ///
+    /// Keys without a protocol are never valid.
+    pub fn is_valid(&self, key: &IButtonKey) -> bool {
+        key.protocol_id().is_some()
+            && unsafe { sys::ibutton_protocols_is_valid(self.as_ptr(), key.as_ptr()) }
+    }
+
+    /// Renders the key's UID, as shown by the firmware's iButton app.
+    pub fn render_uid(&self, key: &IButtonKey) -> FuriString {
+        self.render(key, sys::ibutton_protocols_render_uid)
+    }
+
+    /// Renders a full human-readable description of the key's data.
+    pub fn render_data(&self, key: &IButtonKey) -> FuriString {
+        self.render(key, sys::ibutton_protocols_render_data)
+    }
+
+    /// Renders a short human-readable description of the key's data.
+    pub fn render_brief_data(&self, key: &IButtonKey) -> FuriString {
+        self.render(key, sys::ibutton_protocols_render_brief_data)
+    }
+
+    /// Renders a description of what is wrong with an invalid key.
+    pub fn render_error(&self, key: &IButtonKey) -> FuriString {
+        self.render(key, sys::ibutton_protocols_render_error)
+    }
+
+    fn render(
+        &self,
+        key: &IButtonKey,
+        render: unsafe extern "C" fn(
+            *mut sys::iButtonProtocols,
+            *const sys::iButtonKey,
+            *mut sys::FuriString,
+        ),
+    ) -> FuriString {
+        let mut result = FuriString::new();
+        if key.protocol_id().is_some() {
+            unsafe { render(self.as_ptr(), key.as_ptr(), result.as_mut_ptr()) };
+        }
+
+        result
+    }
+}
+
+impl Drop for IButtonProtocols {
+    fn drop(&mut self) {
+        unsafe { sys::ibutton_protocols_free(self.as_ptr()) }
+    }
+}
+
+impl Default for IButtonProtocols {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An iButton key: a protocol id and the key's data.
+pub struct IButtonKey {
+    raw: NonNull<sys::iButtonKey>,
+}
+
+impl IButtonKey {
+    /// Allocates an empty key, large enough to hold the data of any protocol.
+    pub fn new(protocols: &IButtonProtocols) -> Self {
+        let data_size = protocols.max_data_size();
+
+        Self {
+            // SAFETY: Alloc always returns a valid non-null pointer or `furi_panic`s.
+            raw: unsafe { NonNull::new_unchecked(sys::ibutton_key_alloc(data_size)) },
+        }
+    }
+
+    /// Creates a key from its protocol and data (e.g. the 8-byte ROM of a DS1990).
+    ///
+    /// Returns `None` if the length of `data` does not match the protocol's editable data.
+    pub fn with_data(protocols: &IButtonProtocols, id: ProtocolId, data: &[u8]) -> Option<Self> {
+        let mut key = Self::new(protocols);
+        key.set_protocol_id(id);
+
+        let editable = key.editable_data(protocols);
+        if editable.size != data.len() {
+            return None;
+        }
+
+        // SAFETY: `editable` points to `editable.size` bytes inside the key's data.
+        unsafe {
+            editable
+                .ptr
+                .copy_from_nonoverlapping(data.as_ptr(), data.len());
+            sys::ibutton_protocols_apply_edits(protocols.as_ptr(), key.as_ptr());
+        }
+
+        Some(key)
+    }
+
+    /// Loads a key from a `.ibtn` key file.
+    pub fn load(protocols: &IButtonProtocols, path: &CStr) -> Result<Self, Error> {
+        let key = Self::new(protocols);
+
+        if unsafe { sys::ibutton_protocols_load(protocols.as_ptr(), key.as_ptr(), path.as_ptr()) } {
+            Ok(key)
+        } else {
+            Err(Error::Load)
+        }
+    }
+
+    /// Saves the key to a `.ibtn` key file.
+    pub fn save(&self, protocols: &IButtonProtocols, path: &CStr) -> Result<(), Error> {
+        if self.protocol_id().is_some()
+            && unsafe {
+                sys::ibutton_protocols_save(protocols.as_ptr(), self.as_ptr(), path.as_ptr())
+            }
+        {
+            Ok(())
+        } else {
+            Err(Error::Save)
+        }
+    }
+
+    /// Obtain raw iButton key.
+    ///
+    /// This pointer must not be `free`d or otherwise invalidated.
+    /// It must not be referenced after [`IButtonKey`] has been dropped.
+    #[inline]
+    pub fn as_ptr(&self) -> *mut sys::iButtonKey {
+        self.raw.as_ptr()
+    }
+
+    /// Protocol of the key, or `None` if the key is empty.
+    pub fn protocol_id(&self) -> Option<ProtocolId> {
+        ProtocolId::from_sys(unsafe { sys::ibutton_key_get_protocol_id(self.as_ptr()) })
+    }
+
+    /// Sets the protocol of the key.
+    pub fn set_protocol_id(&mut self, id: ProtocolId) {
+        unsafe { sys::ibutton_key_set_protocol_id(self.as_ptr(), id.0) }
+    }
+
+    /// Clears the key's protocol and data.
+    pub fn reset(&mut self) {
+        unsafe { sys::ibutton_key_reset(self.as_ptr()) }
+    }
+
+    /// The key's data (e.g. the 8-byte ROM of a DS1990).
+    ///
+    /// Returns an empty slice if the key has no protocol.
+    pub fn data(&self, protocols: &IButtonProtocols) -> &[u8] {
+        if self.protocol_id().is_none() {
+            return &[];
+        }
+
+        let editable = self.editable_data(protocols);
+        // SAFETY: The editable data lives inside the key, which is borrowed for the
+        // lifetime of the returned slice.
+        unsafe { core::slice::from_raw_parts(editable.ptr, editable.size) }
+    }
+
+    fn editable_data(&self, protocols: &IButtonProtocols) -> sys::iButtonEditableData {
+        let mut editable = sys::iButtonEditableData {
+            ptr: core::ptr::null_mut(),
+            size: 0,
+        };
+        unsafe {
+            sys::ibutton_protocols_get_editable_data(
+                protocols.as_ptr(),
+                self.as_ptr(),
+                &mut editable,
+            )
+        };
+
+        editable
+    }
+}
+
+impl fmt::Debug for IButtonKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IButtonKey")
+            .field("protocol_id", &self.protocol_id())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for IButtonKey {
+    fn drop(&mut self) {
+        unsafe { sys::ibutton_key_free(self.as_ptr()) }
+    }
+}
+
+/// How [`IButtonWorker::write`] writes a key.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum WriteMode {
+    /// Write the key's ID to a blank (e.g. RW1990).
+    #[default]
+    Id,
+    /// Copy the key's full data to a key of the same type.
+    Copy,
+}
+
+/// Outcome of an [`IButtonWorker::write`] attempt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WriteResult {
+    /// The key was written.
+    Ok,
+    /// The key already holds the same data.
+    SameKey,
+    /// No key was detected.
+    NoDetect,
+    /// The key could not be written.
+    CannotWrite,
+}
+
+impl WriteResult {
+    pub fn from_sys(result: sys::iButtonWorkerWriteResult) -> Option<Self> {
+        match result {
+            sys::iButtonWorkerWriteOK => Some(Self::Ok),
+            sys::iButtonWorkerWriteSameKey => Some(Self::SameKey),
+            sys::iButtonWorkerWriteNoDetect => Some(Self::NoDetect),
+            sys::iButtonWorkerWriteCannotWrite => Some(Self::CannotWrite),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the key now holds the written data.
+    pub fn is_success(&self) -> bool {
+        matches!(self, Self::Ok | Self::SameKey)
+    }
+}
+
+/// Errors from loading and saving `.ibtn` key files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// The file is missing, malformed or uses an unknown protocol.
+    Load,
+    /// The file could not be written, or the key is empty.
+    Save,
+}
+
+impl Error {
+    /// Description associated with [`Error`].
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Load => "failed to load iButton key file",
+            Self::Save => "failed to save iButton key file",
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.description())
+    }
+}
+
+impl ufmt::uDisplay for Error {
+    fn fmt<W>(&self, f: &mut ufmt::Formatter<'_, W>) -> Result<(), W::Error>
+    where
+        W: ufmt::uWrite + ?Sized,
+    {
+        f.write_str(self.description())
+    }
+}
+
+/// iButton worker.
+///
+/// The worker runs one operation at a time on a dedicated thread. Each operation is
+/// represented by a handle, which stops the operation when dropped.
+pub struct IButtonWorker {
+    raw: NonNull<sys::iButtonWorker>,
+    protocols: IButtonProtocols,
+    /// Whether the worker thread is running.
+    running: bool,
+}
+
+impl IButtonWorker {
+    /// Allocates a new worker.
+    pub fn new() -> Self {
+        let protocols = IButtonProtocols::new();
+
+        Self {
+            // SAFETY: Alloc always returns a valid non-null pointer or `furi_panic`s.
+            raw: unsafe { NonNull::new_unchecked(sys::ibutton_worker_alloc(protocols.as_ptr())) },
+            protocols,
+            running: false,
+        }
+    }
+
+    /// Obtain raw iButton worker.
+    ///
+    /// This pointer must not be `free`d or otherwise invalidated.
+    /// It must not be referenced after [`IButtonWorker`] has been dropped.
+    #[inline]
+    pub fn as_ptr(&self) -> *mut sys::iButtonWorker {
+        self.raw.as_ptr()
+    }
+
+    /// Protocols used by the worker.
+    pub fn protocols(&self) -> &IButtonProtocols {
+        &self.protocols
+    }
+
+    /// Starts reading keys into `key`.
+    ///
+    /// `on_read` is called from the worker thread each time a key has been read. `key`
+    /// is given back by [`Reader::stop`].
+    pub fn read<F>(&mut self, key: IButtonKey, on_read: F) -> Reader<'_, F>
+    where
+        F: FnMut() + Send + 'static,
+    {
+        self.stop();
+        let mut context = FuriBox::new(on_read);
+
+        self.running = true;
+        unsafe {
+            sys::ibutton_worker_start_thread(self.as_ptr());
+            sys::ibutton_worker_read_set_callback(
+                self.as_ptr(),
+                Some(read_callback::<F>),
+                FuriBox::as_mut_ptr(&mut context).cast(),
+            );
+            sys::ibutton_worker_read_start(self.as_ptr(), key.as_ptr());
+        }
+
+        Reader {
+            worker: self,
+            key: Some(key),
+            _context: context,
+        }
+    }
+
+    /// Starts writing `key` to a key touching the Flipper.
+    ///
+    /// `on_result` is called from the worker thread with the outcome of write attempts.
+    /// Writing stops when the returned [`Writer`] is dropped, or `key` is given back by
+    /// [`Writer::stop`].
+    pub fn write<F>(&mut self, key: IButtonKey, mode: WriteMode, on_result: F) -> Writer<'_, F>
+    where
+        F: FnMut(WriteResult) + Send + 'static,
+    {
+        self.stop();
+        let mut context = FuriBox::new(on_result);
+
+        self.running = true;
+        unsafe {
+            sys::ibutton_worker_start_thread(self.as_ptr());
+            sys::ibutton_worker_write_set_callback(
+                self.as_ptr(),
+                Some(write_callback::<F>),
+                FuriBox::as_mut_ptr(&mut context).cast(),
+            );
+            match mode {
+                WriteMode::Id => sys::ibutton_worker_write_id_start(self.as_ptr(), key.as_ptr()),
+                WriteMode::Copy => {
+                    sys::ibutton_worker_write_copy_start(self.as_ptr(), key.as_ptr())
+                }
+            }
+        }
+
+        Writer {
+            worker: self,
+            key: Some(key),
+            _context: context,
+        }
+    }
+
+    /// Starts emulating `key`, until the returned [`Emulator`] is dropped, or `key` is
+    /// given back by [`Emulator::stop`].
+    ///
+    /// `on_emulated` is called from the worker thread each time a reader has read the key.
+    /// Keys with writable memory may be modified by the reader while being emulated.
+    pub fn emulate<F>(&mut self, key: IButtonKey, on_emulated: F) -> Emulator<'_, F>
+    where
+        F: FnMut() + Send + 'static,
+    {
+        self.stop();
+        let mut context = FuriBox::new(on_emulated);
+
+        self.running = true;
+        unsafe {
+            sys::ibutton_worker_start_thread(self.as_ptr());
+            sys::ibutton_worker_emulate_set_callback(
+                self.as_ptr(),
+                Some(emulate_callback::<F>),
+                FuriBox::as_mut_ptr(&mut context).cast(),
+            );
+            sys::ibutton_worker_emulate_start(self.as_ptr(), key.as_ptr());
+        }
+
+        Emulator {
+            worker: self,
+            key: Some(key),
+            _context: context,
+        }
+    }
+
+    /// Stops the current operation, if any, and waits for the worker thread to exit.
+    ///
+    /// Once this returns, no more callbacks will be invoked.
+    fn stop(&mut self) {
+        if !self.running {
+            return;
+        }
+        unsafe {
+            sys::ibutton_worker_stop(self.as_ptr());
+            sys::ibutton_worker_stop_thread(self.as_ptr());
+        }
+        self.running = false;
+    }
+}
+
+impl Drop for IButtonWorker {
+    fn drop(&mut self) {
+        // An operation whose handle was leaked is still running.
+        self.stop();
+        // The worker must be freed before the protocols it references.
+        unsafe { sys::ibutton_worker_free(self.as_ptr()) }
+    }
+}
+
+impl Default for IButtonWorker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+unsafe extern "C" fn read_callback<F: FnMut()>(context: *mut c_void) {
+    let on_read = context.cast::<F>();
+
+    unsafe { (*on_read)() }
+}
+
+unsafe extern "C" fn write_callback<F: FnMut(WriteResult)>(
+    context: *mut c_void,
+    result: sys::iButtonWorkerWriteResult,
+) {
+    let on_result = context.cast::<F>();
+
+    if let Some(result) = WriteResult::from_sys(result) {
+        unsafe { (*on_result)(result) }
+    }
+}
+
+unsafe extern "C" fn emulate_callback<F: FnMut()>(context: *mut c_void, emulated: bool) {
+    let on_emulated = context.cast::<F>();
+
+    if emulated {
+        unsafe { (*on_emulated)() }
+    }
+}
+
+/// Handle to a running [`IButtonWorker::read`] operation.
+///
+/// Reading stops when this handle is dropped.
+pub struct Reader<'a, F: FnMut()> {
+    worker: &'a mut IButtonWorker,
+    /// `None` once given back by `stop`.
+    key: Option<IButtonKey>,
+    _context: FuriBox<F>,
+}
+
+impl<F: FnMut()> Reader<'_, F> {
+    /// Stops reading, and gives back the key, holding the last key read.
+    pub fn stop(mut self) -> IButtonKey {
+        self.worker.stop();
+        self.key.take().unwrap()
+    }
+}
+
+impl<F: FnMut()> Drop for Reader<'_, F> {
+    fn drop(&mut self) {
+        // Ensure the callback is no longer running before the context is freed.
+        self.worker.stop();
+    }
+}
+
+/// Handle to a running [`IButtonWorker::write`] operation.
+///
+/// Writing stops when this handle is dropped.
+pub struct Writer<'a, F: FnMut(WriteResult)> {
+    worker: &'a mut IButtonWorker,
+    /// `None` once given back by `stop`.
+    key: Option<IButtonKey>,
+    _context: FuriBox<F>,
+}
+
+impl<F: FnMut(WriteResult)> Writer<'_, F> {
+    /// Stops writing, and gives back the key.
+    pub fn stop(mut self) -> IButtonKey {
+        self.worker.stop();
+        self.key.take().unwrap()
+    }
+}
+
+impl<F: FnMut(WriteResult)> Drop for Writer<'_, F> {
+    fn drop(&mut self) {
+        // Ensure the callback is no longer running before the context is freed.
+        self.worker.stop();
+    }
+}
+
+/// Handle to a running [`IButtonWorker::emulate`] operation.
+///
+/// Emulation stops when this handle is dropped.
+pub struct Emulator<'a, F: FnMut()> {
+    worker: &'a mut IButtonWorker,
+    /// `None` once given back by `stop`.
+    key: Option<IButtonKey>,
+    _context: FuriBox<F>,
+}
+
+impl<F: FnMut()> Emulator<'_, F> {
+    /// Stops emulating, and gives back the key, which the reader may have modified.
+    pub fn stop(mut self) -> IButtonKey {
+        self.worker.stop();
+        self.key.take().unwrap()
+    }
+}
+
+impl<F: FnMut()> Drop for Emulator<'_, F> {
+    fn drop(&mut self) {
+        // Ensure the callback is no longer running before the context is freed.
+        self.worker.stop();
+    }
+}
+
+#[flipperzero_test::tests]
+mod tests {
+    use super::{IButtonKey, IButtonProtocols};
+
+    fn ds1990(protocols: &IButtonProtocols) -> super::ProtocolId {
+        protocols.id_by_name(c"DS1990").unwrap()
+    }
+
+    #[test]
+    fn protocol_names() {
+        let protocols = IButtonProtocols::new();
+
+        for id in IButtonProtocols::ids() {
+            assert_eq!(protocols.id_by_name(protocols.name(id)), Some(id));
+        }
+
+        assert!(protocols.id_by_name(c"NotAProtocol").is_none());
+    }
+
+    #[test]
+    fn empty_key() {
+        let protocols = IButtonProtocols::new();
+        let key = IButtonKey::new(&protocols);
+
+        assert!(key.protocol_id().is_none());
+        assert!(key.data(&protocols).is_empty());
+        assert!(!protocols.is_valid(&key));
+    }
+
+    #[test]
+    fn key_data_size_is_checked() {
+        let protocols = IButtonProtocols::new();
+        let id = ds1990(&protocols);
+
+        assert!(IButtonKey::with_data(&protocols, id, &[0x01; 4]).is_none());
+
+        let rom = [0x01, 0x23, 0x45, 0x67, 0x89, 0xAB, 0xCD, 0xEF];
+        let key = IButtonKey::with_data(&protocols, id, &rom).unwrap();
+        assert_eq!(key.protocol_id(), Some(id));
+        assert_eq!(key.data(&protocols), rom);
+    }
+
+    #[test]
+    fn save_and_load() {
+        let protocols = IButtonProtocols::new();
+        let id = ds1990(&protocols);
+        let rom = [0x01, 0x23, 0x45, 0x67, 0x89, 0xAB, 0xCD, 0xEF];
+        let key = IButtonKey::with_data(&protocols, id, &rom).unwrap();
+        let path = c"/ext/flipperzero-rs-test.ibtn";
+
+        key.save(&protocols, path).unwrap();
+
+        let loaded = IButtonKey::load(&protocols, path).unwrap();
+        assert_eq!(loaded.protocol_id(), Some(id));
+        assert_eq!(loaded.data(&protocols), rom);
+    }
+}
@@ -0,0 +1,316 @@
+use core::mem::ManuallyDrop;
+use core::ptr;
+
+#[cfg(feature = "alloc")]
+use alloc::{boxed::Box, vec};
+
+#[cfg(feature = "alloc")]
+use super::DEFAULT_BUF_SIZE;
+use super::{BufRead, Error, Read, Seek, SeekFrom, Write};
+
+/// Adds buffering to a reader.
+///
+/// Reading from a [`File`](crate::storage::File) a few bytes at a time is slow, since
+/// each read goes through the storage service. `BufReader` reads large chunks into a
+/// buffer and serves small reads from there, and implements [`BufRead`] for reading
+/// lines.
+///
+/// The buffer is any `B: AsRef<[u8]> + AsMut<[u8]>`, either allocated on the heap with
+/// `BufReader::new` and `BufReader::with_capacity`, or provided by the caller with
+/// [`BufReader::with_buffer`] (e.g. an array on the stack).
+///
+/// ```no_run
+/// # use flipperzero::io::{BufRead, BufReader};
+/// # use flipperzero::storage::OpenOptions;
+/// let file = OpenOptions::new().read(true).open(c"/ext/test.txt").unwrap();
+/// let reader = BufReader::with_buffer([0; 256], file);
+/// for line in reader.lines() {
+///     let line = line.unwrap();
+/// }
+/// ```
+pub struct BufReader<R, B> {
+    inner: R,
+    buf: B,
+    pos: usize,
+    filled: usize,
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+impl<R: Read> BufReader<R, Box<[u8]>> {
+    /// Creates a reader with a heap buffer of 512 bytes, the size of an SD card sector.
+    pub fn new(inner: R) -> Self {
+        Self::with_capacity(DEFAULT_BUF_SIZE, inner)
+    }
+
+    /// Creates a reader with a heap buffer of `capacity` bytes.
+    pub fn with_capacity(capacity: usize, inner: R) -> Self {
+        Self::with_buffer(vec![0; capacity].into_boxed_slice(), inner)
+    }
+}
+
+impl<R: Read, B: AsRef<[u8]> + AsMut<[u8]>> BufReader<R, B> {
+    /// Creates a reader that uses `buf` as its buffer.
+    ///
+    /// The initial contents of `buf` are ignored.
+    pub fn with_buffer(buf: B, inner: R) -> Self {
+        Self {
+            inner,
+            buf,
+            pos: 0,
+            filled: 0,
+        }
+    }
+}
+
+impl<R, B: AsRef<[u8]> + AsMut<[u8]>> BufReader<R, B> {
+    /// Gets a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying reader.
+    ///
+    /// Reading from it directly skips over any data left in the buffer.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Returns the buffered data that has not been read yet.
+    pub fn buffer(&self) -> &[u8] {
+        &self.buf.as_ref()[self.pos..self.filled]
+    }
+
+    /// Size of the buffer, in bytes.
+    pub fn capacity(&self) -> usize {
+        self.buf.as_ref().len()
+    }
+
+    /// Unwraps this reader, returning the underlying reader and the buffer.
+    ///
+    /// Any data left in the buffer is lost.
+    pub fn into_parts(self) -> (R, B) {
+        (self.inner, self.buf)
+    }
+
+    /// Unwraps this reader, returning the underlying reader.
+    ///
+    /// Any data left in the buffer is lost.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    fn discard_buffer(&mut self) {
+        self.pos = 0;
+        self.filled = 0;
+    }
+}
+
+impl<R: Read, B: AsRef<[u8]> + AsMut<[u8]>> Read for BufReader<R, B> {
+    fn read(&mut self, out: &mut [u8]) -> Result<usize, Error> {
+        // Skip the buffer for large reads when it is empty, to avoid a useless copy.
+        if self.pos == self.filled && out.len() >= self.capacity() {
+            self.discard_buffer();
+            return self.inner.read(out);
+        }
+
+        let n = Read::read(&mut self.fill_buf()?, out)?;
+        self.consume(n);
+        Ok(n)
+    }
+}
+
+impl<R: Read, B: AsRef<[u8]> + AsMut<[u8]>> BufRead for BufReader<R, B> {
+    fn fill_buf(&mut self) -> Result<&[u8], Error> {
+        if self.pos >= self.filled {
+            self.filled = self.inner.read(self.buf.as_mut())?;
+            self.pos = 0;
+        }
+        Ok(&self.buf.as_mut()[self.pos..self.filled])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.pos = (self.pos + amt).min(self.filled);
+    }
+}
+
+impl<R: Seek, B: AsRef<[u8]> + AsMut<[u8]>> Seek for BufReader<R, B> {
+    /// Seeks in the underlying reader, discarding the buffer.
+    ///
+    /// With [`SeekFrom::Current`], the offset is relative to the position of the next
+    /// byte returned by this reader, not the position of the underlying reader.
+    fn seek(&mut self, pos: SeekFrom) -> Result<usize, Error> {
+        let pos = match pos {
+            SeekFrom::Current(n) => {
+                let remaining = (self.filled - self.pos) as i64;
+                SeekFrom::Current(n.checked_sub(remaining).ok_or(Error::InvalidParameter)?)
+            }
+            pos => pos,
+        };
+        let result = self.inner.seek(pos)?;
+        self.discard_buffer();
+        Ok(result)
+    }
+}
+
+/// Adds buffering to a writer.
+///
+/// Small writes are collected in a buffer, which is written to the underlying writer
+/// when it is full, on [`Write::flush`], and when the `BufWriter` is dropped. Errors
+/// while writing on drop are ignored, so call [`Write::flush`] (or
+/// [`BufWriter::into_inner`]) to handle them.
+///
+/// Like [`BufReader`], the buffer is allocated on the heap with `BufWriter::new` and
+/// `BufWriter::with_capacity`, or provided by the caller with
+/// [`BufWriter::with_buffer`].
+///
+/// ```no_run
+/// # use flipperzero::io::{BufWriter, Write};
+/// # use flipperzero::storage::OpenOptions;
+/// let file = OpenOptions::new()
+///     .write(true)
+///     .create_always(true)
+///     .open(c"/ext/test.txt")
+///     .unwrap();
+/// let mut writer = BufWriter::with_buffer([0; 256], file);
+/// for i in 0..100 {
+///     writeln!(writer, "line {i}").unwrap();
+/// }
+/// writer.flush().unwrap();
+/// ```
+pub struct BufWriter<W: Write, B: AsRef<[u8]> + AsMut<[u8]>> {
+    inner: W,
+    buf: B,
+    len: usize,
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+impl<W: Write> BufWriter<W, Box<[u8]>> {
+    /// Creates a writer with a heap buffer of 512 bytes, the size of an SD card sector.
+    pub fn new(inner: W) -> Self {
+        Self::with_capacity(DEFAULT_BUF_SIZE, inner)
+    }
+
+    /// Creates a writer with a heap buffer of `capacity` bytes.
+    pub fn with_capacity(capacity: usize, inner: W) -> Self {
+        Self::with_buffer(vec![0; capacity].into_boxed_slice(), inner)
+    }
+}
+
+impl<W: Write, B: AsRef<[u8]> + AsMut<[u8]>> BufWriter<W, B> {
+    /// Creates a writer that uses `buf` as its buffer.
+    ///
+    /// The initial contents of `buf` are ignored.
+    pub fn with_buffer(buf: B, inner: W) -> Self {
+        Self { inner, buf, len: 0 }
+    }
+
+    /// Gets a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying writer.
+    ///
+    /// Writing to it directly skips ahead of any data left in the buffer.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Returns the data that has been buffered but not written yet.
+    pub fn buffer(&self) -> &[u8] {
+        &self.buf.as_ref()[..self.len]
+    }
+
+    /// Size of the buffer, in bytes.
+    pub fn capacity(&self) -> usize {
+        self.buf.as_ref().len()
+    }
+
+    /// Writes out the buffer and unwraps this writer, returning the underlying writer.
+    ///
+    /// If writing out the buffer fails, the error is returned and the buffered data is
+    /// lost.
+    pub fn into_inner(self) -> Result<W, Error> {
+        self.into_parts().map(|(inner, _)| inner)
+    }
+
+    /// Writes out the buffer and unwraps this writer, returning the underlying writer
+    /// and the buffer.
+    ///
+    /// If writing out the buffer fails, the error is returned and the buffered data is
+    /// lost.
+    pub fn into_parts(mut self) -> Result<(W, B), Error> {
+        self.flush_buf()?;
+
+        let this = ManuallyDrop::new(self);
+        // SAFETY: `this` is never used or dropped again, so each field is moved out
+        // exactly once.
+        Ok(unsafe { (ptr::read(&this.inner), ptr::read(&this.buf)) })
+    }
+
+    /// Writes the buffered data to the underlying writer.
+    ///
+    /// Whatever was not written is kept at the start of the buffer, even on error.
+    fn flush_buf(&mut self) -> Result<(), Error> {
+        let buf = self.buf.as_mut();
+        let mut written = 0;
+        let mut result = Ok(());
+        while written < self.len {
+            match self.inner.write(&buf[written..self.len]) {
+                Ok(0) => {
+                    result = Err(Error::WriteZero);
+                    break;
+                }
+                Ok(n) => written += n,
+                Err(err) => {
+                    result = Err(err);
+                    break;
+                }
+            }
+        }
+
+        buf.copy_within(written..self.len, 0);
+        self.len -= written;
+        result
+    }
+}
+
+impl<W: Write, B: AsRef<[u8]> + AsMut<[u8]>> Write for BufWriter<W, B> {
+    fn write(&mut self, data: &[u8]) -> Result<usize, Error> {
+        if self.len + data.len() > self.capacity() {
+            self.flush_buf()?;
+        }
+
+        if data.len() >= self.capacity() {
+            // Too large to be worth buffering.
+            self.inner.write(data)
+        } else {
+            let len = self.len;
+            self.buf.as_mut()[len..len + data.len()].copy_from_slice(data);
+            self.len += data.len();
+            Ok(data.len())
+        }
+    }
+
+    fn flush(&mut self) -> Result<(), Error> {
+        self.flush_buf()?;
+        self.inner.flush()
+    }
+}
+
+impl<W: Write + Seek, B: AsRef<[u8]> + AsMut<[u8]>> Seek for BufWriter<W, B> {
+    /// Writes out the buffer, then seeks in the underlying writer.
+    fn seek(&mut self, pos: SeekFrom) -> Result<usize, Error> {
+        self.flush_buf()?;
+        self.inner.seek(pos)
+    }
+}
+
+impl<W: Write, B: AsRef<[u8]> + AsMut<[u8]>> Drop for BufWriter<W, B> {
+    fn drop(&mut self) {
+        // Errors can't be reported from `drop`; see the type documentation.
+        let _ = self.flush_buf();
+    }
+}
@@ -0,0 +1,173 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use super::{BufRead, Error, Read, Seek, SeekFrom, Write};
+
+/// An in-memory buffer with a position, implementing [`Read`], [`BufRead`], [`Seek`]
+/// and (for mutable buffers) [`Write`].
+///
+/// Comparable to `std::io::Cursor`. Useful for parsing data that is already in memory
+/// with the same code that reads from a [`File`](crate::storage::File), and for tests.
+///
+/// ```no_run
+/// # use flipperzero::io::{Cursor, Read};
+/// let mut cursor = Cursor::new(&b"Filetype: Flipper"[..]);
+/// let mut magic = [0; 8];
+/// cursor.read_exact(&mut magic).unwrap();
+/// assert_eq!(&magic, b"Filetype");
+/// assert_eq!(cursor.position(), 8);
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Cursor<T> {
+    inner: T,
+    pos: usize,
+}
+
+impl<T> Cursor<T> {
+    /// Creates a cursor at the start of `inner`.
+    pub const fn new(inner: T) -> Self {
+        Self { inner, pos: 0 }
+    }
+
+    /// Current position, in bytes from the start of the buffer.
+    ///
+    /// This may be past the end of the buffer.
+    pub const fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Moves the cursor to `pos` bytes from the start of the buffer.
+    pub fn set_position(&mut self, pos: usize) {
+        self.pos = pos;
+    }
+
+    /// Gets a reference to the underlying buffer.
+    pub const fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying buffer.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    /// Unwraps this cursor, returning the underlying buffer.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T: AsRef<[u8]>> Cursor<T> {
+    /// The part of the buffer after the current position.
+    pub fn remaining_slice(&self) -> &[u8] {
+        let inner = self.inner.as_ref();
+        &inner[self.pos.min(inner.len())..]
+    }
+
+    /// Returns `true` if the position is at or past the end of the buffer.
+    pub fn is_empty(&self) -> bool {
+        self.remaining_slice().is_empty()
+    }
+}
+
+impl<T: AsRef<[u8]>> Read for Cursor<T> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        let n = Read::read(&mut self.remaining_slice(), buf)?;
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+impl<T: AsRef<[u8]>> BufRead for Cursor<T> {
+    fn fill_buf(&mut self) -> Result<&[u8], Error> {
+        Ok(self.remaining_slice())
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.pos += amt;
+    }
+}
+
+impl<T: AsRef<[u8]>> Seek for Cursor<T> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<usize, Error> {
+        let (base, offset) = match pos {
+            SeekFrom::Start(n) => {
+                self.pos = n.try_into().map_err(|_| Error::InvalidParameter)?;
+                return Ok(self.pos);
+            }
+            SeekFrom::End(n) => (self.inner.as_ref().len(), n),
+            SeekFrom::Current(n) => (self.pos, n),
+        };
+
+        self.pos = isize::try_from(offset)
+            .ok()
+            .and_then(|offset| base.checked_add_signed(offset))
+            .ok_or(Error::InvalidParameter)?;
+        Ok(self.pos)
+    }
+
+    fn stream_len(&mut self) -> Result<usize, Error> {
+        Ok(self.inner.as_ref().len())
+    }
+
+    fn stream_position(&mut self) -> Result<usize, Error> {
+        Ok(self.pos)
+    }
+}
+
+/// Writes into a fixed-size slice, failing with [`Error::WriteZero`] (via
+/// [`Write::write_all`]) once it is full.
+impl Write for Cursor<&mut [u8]> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        let start = self.pos.min(self.inner.len());
+        let n = buf.len().min(self.inner.len() - start);
+        self.inner[start..start + n].copy_from_slice(&buf[..n]);
+        self.pos = start + n;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// Writes into a vector, overwriting existing bytes and growing it as needed.
+///
+/// Writing past the end of the vector fills the gap with zeros.
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+impl Write for Cursor<Vec<u8>> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        vec_write(&mut self.pos, &mut self.inner, buf)
+    }
+
+    fn flush(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// Writes into a vector, overwriting existing bytes and growing it as needed.
+///
+/// Writing past the end of the vector fills the gap with zeros.
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+impl Write for Cursor<&mut Vec<u8>> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        vec_write(&mut self.pos, self.inner, buf)
+    }
+
+    fn flush(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "alloc")]
+fn vec_write(pos: &mut usize, vec: &mut Vec<u8>, buf: &[u8]) -> Result<usize, Error> {
+    let end = pos.checked_add(buf.len()).ok_or(Error::InvalidParameter)?;
+    if vec.len() < end {
+        vec.resize(end, 0);
+    }
+    vec[*pos..end].copy_from_slice(buf);
+    *pos = end;
+    Ok(buf.len())
+}
@@ -30,6 +30,19 @@ pub enum Error {
     /// completed.
     WriteZero,
 
+    /// I/O error specific to `flipperzero-rs` to represent the case a read reached
+    /// the end of the stream before filling the buffer, e.g. in [`Read::read_exact`].
+    ///
+    /// [`Read::read_exact`]: super::Read::read_exact
+    UnexpectedEof,
+
+    /// I/O error specific to `flipperzero-rs` to represent the case the data was not
+    /// valid for the operation, e.g. bytes read into a string were not UTF-8, or a
+    /// formatting trait failed in [`Write::write_fmt`].
+    ///
+    /// [`Write::write_fmt`]: super::Write::write_fmt
+    InvalidData,
+
     /// Any I/O error from the Flipper Zero SDK that's not part of this list.
     ///
     /// Errors that are `Uncategorized` now may move to a different or a new [`Error`]
@@ -74,7 +87,14 @@ impl Error {
 
     /// Description associated with [`Error`].
     pub fn description(&self) -> &CStr {
-        unsafe { CStr::from_ptr(sys::filesystem_api_error_get_desc(self.to_sys().unwrap())) }
+        match self.to_sys() {
+            Some(err) => unsafe { CStr::from_ptr(sys::filesystem_api_error_get_desc(err)) },
+            None => match self {
+                Self::WriteZero => c"failed to write whole buffer",
+                Self::UnexpectedEof => c"failed to fill whole buffer",
+                _ => c"invalid data",
+            },
+        }
     }
 }
 
@@ -96,65 +116,3 @@ impl ufmt::uDisplay for Error {
         Ok(())
     }
 }
-
-/// Trait comparable to `std::Read` for the Flipper Zero API
-pub trait Read {
-    /// Reads some bytes from this source into the given buffer, returning how many bytes
-    /// were read.
-    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error>;
-}
-
-/// Trait comparable to `std::Seek` for the Flipper Zero API
-pub trait Seek {
-    fn seek(&mut self, pos: SeekFrom) -> Result<usize, Error>;
-
-    fn rewind(&mut self) -> Result<(), Error> {
-        self.seek(SeekFrom::Start(0))?;
-        Ok(())
-    }
-
-    fn stream_len(&mut self) -> Result<usize, Error> {
-        let old_pos = self.stream_position()?;
-        let len = self.seek(SeekFrom::End(0))?;
-
-        // Avoid seeking a third time when we were already at the end of the
-        // stream. The branch is usually way cheaper than a seek operation.
-        if old_pos != len {
-            self.seek(SeekFrom::Start(
-                old_pos.try_into().map_err(|_| Error::InvalidParameter)?,
-            ))?;
-        }
-
-        Ok(len)
-    }
-
-    fn stream_position(&mut self) -> Result<usize, Error> {
-        self.seek(SeekFrom::Current(0))
-    }
-}
-
-/// Trait comparable to `std::Write` for the Flipper Zero API
-pub trait Write {
-    fn write(&mut self, buf: &[u8]) -> Result<usize, Error>;
-    fn flush(&mut self) -> Result<(), Error>;
-
-    fn write_all(&mut self, mut buf: &[u8]) -> Result<(), Error> {
-        while !buf.is_empty() {
-            match self.write(buf) {
-                Ok(0) => return Err(Error::WriteZero),
-                Ok(n) => buf = &buf[n..],
-                Err(e) => return Err(e),
-            }
-        }
-        Ok(())
-    }
-}
-
-/// Enumeration of possible methods to seek within an I/O object.
-///
-/// It is used by the Seek trait.
-pub enum SeekFrom {
-    Start(u64),
-    End(i64),
-    Current(i64),
-}
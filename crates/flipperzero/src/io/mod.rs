@@ -0,0 +1,27 @@
+//! Traits, helpers and type definitions for I/O, comparable to `std::io`.
+//!
+//! [`Read`], [`Write`] and [`Seek`] are implemented by [`File`](crate::storage::File),
+//! and by in-memory types like `&[u8]` and [`Cursor`]. [`BufReader`] and [`BufWriter`]
+//! add buffering to any of them.
+//!
+//! Everything except [`Error`] is independent of the SDK, and is tested on the host
+//! (see `crates/host-tests`).
+
+mod buffered;
+mod cursor;
+mod error;
+mod traits;
+mod util;
+
+pub use buffered::{BufReader, BufWriter};
+pub use cursor::Cursor;
+pub use error::Error;
+#[cfg(feature = "alloc")]
+pub use traits::Lines;
+pub use traits::{BufRead, FmtWriter, Read, Seek, SeekFrom, Write};
+pub use util::{copy, copy_buf, Chain, Take};
+
+/// Size of the buffers used by `BufReader::new`, `BufWriter::new` and [`copy`].
+///
+/// This matches the sector size of the SD card.
+const DEFAULT_BUF_SIZE: usize = 512;
@@ -0,0 +1,426 @@
+use core::fmt;
+
+#[cfg(feature = "alloc")]
+use alloc::{string::String, vec::Vec};
+
+use super::{Chain, Error, Take};
+#[cfg(feature = "alloc")]
+use crate::furi::string::FuriString;
+
+/// Trait comparable to `std::Read` for the Flipper Zero API
+pub trait Read {
+    /// Reads some bytes from this source into the given buffer, returning how many bytes
+    /// were read.
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error>;
+
+    /// Reads exactly enough bytes to fill `buf`.
+    ///
+    /// Fails with [`Error::UnexpectedEof`] if the source ends first, in which case the
+    /// contents of `buf` are unspecified.
+    fn read_exact(&mut self, mut buf: &mut [u8]) -> Result<(), Error> {
+        while !buf.is_empty() {
+            match self.read(buf)? {
+                0 => return Err(Error::UnexpectedEof),
+                n => buf = &mut buf[n..],
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads all bytes until the end of the source, appending them to `buf`.
+    ///
+    /// Returns the number of bytes read. If an error occurs, the bytes read so far are
+    /// still appended to `buf`.
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> Result<usize, Error> {
+        let start = buf.len();
+        loop {
+            if buf.len() == buf.capacity() {
+                buf.reserve(super::DEFAULT_BUF_SIZE);
+            }
+
+            let len = buf.len();
+            buf.resize(buf.capacity(), 0);
+            match self.read(&mut buf[len..]) {
+                Ok(0) => {
+                    buf.truncate(len);
+                    return Ok(len - start);
+                }
+                Ok(n) => buf.truncate(len + n),
+                Err(err) => {
+                    buf.truncate(len);
+                    return Err(err);
+                }
+            }
+        }
+    }
+
+    /// Reads all bytes until the end of the source, appending them to `buf`.
+    ///
+    /// Fails with [`Error::InvalidData`] if the bytes read are not valid UTF-8, in which
+    /// case `buf` is left unchanged.
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    fn read_to_string(&mut self, buf: &mut String) -> Result<usize, Error> {
+        append_utf8(buf, |bytes| self.read_to_end(bytes))
+    }
+
+    /// Reads all bytes until the end of the source, appending them to `buf`.
+    ///
+    /// Like [`Read::read_to_string`], this fails with [`Error::InvalidData`] if the bytes
+    /// read are not valid UTF-8, in which case `buf` is left unchanged.
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    fn read_to_furi_string(&mut self, buf: &mut FuriString) -> Result<usize, Error> {
+        let mut string = String::new();
+        let result = self.read_to_string(&mut string);
+        buf.push_str(&string);
+        result
+    }
+
+    /// Creates an adaptor that reads at most `limit` bytes from this source.
+    fn take(self, limit: u64) -> Take<Self>
+    where
+        Self: Sized,
+    {
+        Take::new(self, limit)
+    }
+
+    /// Creates an adaptor that reads from `next` once this source has ended.
+    fn chain<R: Read>(self, next: R) -> Chain<Self, R>
+    where
+        Self: Sized,
+    {
+        Chain::new(self, next)
+    }
+
+    /// Borrows this source, so that adaptors like [`Read::take`] don't consume it.
+    fn by_ref(&mut self) -> &mut Self
+    where
+        Self: Sized,
+    {
+        self
+    }
+}
+
+/// Trait comparable to `std::BufRead` for the Flipper Zero API
+///
+/// A source with an internal buffer, which allows reading lines and avoids many small
+/// reads from the underlying source.
+pub trait BufRead: Read {
+    /// Returns the contents of the internal buffer, filling it from the underlying
+    /// source if it is empty.
+    ///
+    /// An empty slice means the source has ended. Call [`BufRead::consume`] to mark
+    /// bytes as read.
+    fn fill_buf(&mut self) -> Result<&[u8], Error>;
+
+    /// Marks `amt` bytes of the buffer returned by [`BufRead::fill_buf`] as read.
+    fn consume(&mut self, amt: usize);
+
+    /// Returns `true` if there is more data to read.
+    fn has_data_left(&mut self) -> Result<bool, Error> {
+        self.fill_buf().map(|buf| !buf.is_empty())
+    }
+
+    /// Reads bytes up to and including `byte` (or the end of the source), appending them
+    /// to `buf`.
+    ///
+    /// Returns the number of bytes read, which is 0 at the end of the source.
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    fn read_until(&mut self, byte: u8, buf: &mut Vec<u8>) -> Result<usize, Error> {
+        let mut read = 0;
+        loop {
+            let (done, used) = {
+                let available = self.fill_buf()?;
+                match available.iter().position(|&b| b == byte) {
+                    Some(i) => {
+                        buf.extend_from_slice(&available[..=i]);
+                        (true, i + 1)
+                    }
+                    None => {
+                        buf.extend_from_slice(available);
+                        (available.is_empty(), available.len())
+                    }
+                }
+            };
+            self.consume(used);
+            read += used;
+            if done {
+                return Ok(read);
+            }
+        }
+    }
+
+    /// Reads a line, including its `\n` terminator (if any), appending it to `buf`.
+    ///
+    /// Returns the number of bytes read, which is 0 at the end of the source. Fails with
+    /// [`Error::InvalidData`] if the line is not valid UTF-8, in which case `buf` is left
+    /// unchanged.
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    fn read_line(&mut self, buf: &mut String) -> Result<usize, Error> {
+        append_utf8(buf, |bytes| self.read_until(b'\n', bytes))
+    }
+
+    /// Returns an iterator over the lines of this source, without their `\n` or `\r\n`
+    /// terminators.
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    fn lines(self) -> Lines<Self>
+    where
+        Self: Sized,
+    {
+        Lines { inner: self }
+    }
+}
+
+/// Appends the bytes produced by `read` to `buf`, if they are valid UTF-8.
+#[cfg(feature = "alloc")]
+fn append_utf8<F>(buf: &mut String, read: F) -> Result<usize, Error>
+where
+    F: FnOnce(&mut Vec<u8>) -> Result<usize, Error>,
+{
+    let mut bytes = core::mem::take(buf).into_bytes();
+    let start = bytes.len();
+    let result = read(&mut bytes);
+
+    let result = match core::str::from_utf8(&bytes[start..]) {
+        Ok(_) => result,
+        Err(_) => {
+            bytes.truncate(start);
+            result.and(Err(Error::InvalidData))
+        }
+    };
+
+    // SAFETY: `bytes` is the original string followed by valid UTF-8.
+    *buf = unsafe { String::from_utf8_unchecked(bytes) };
+    result
+}
+
+/// Iterator over the lines of a [`BufRead`].
+///
+/// Returned by [`BufRead::lines`].
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+#[derive(Debug)]
+pub struct Lines<B> {
+    inner: B,
+}
+
+#[cfg(feature = "alloc")]
+impl<B: BufRead> Iterator for Lines<B> {
+    type Item = Result<String, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut line = String::new();
+        match self.inner.read_line(&mut line) {
+            Ok(0) => None,
+            Ok(_) => {
+                if line.ends_with('\n') {
+                    line.pop();
+                    if line.ends_with('\r') {
+                        line.pop();
+                    }
+                }
+                Some(Ok(line))
+            }
+            Err(err) => Some(Err(err)),
+        }
+    }
+}
+
+/// Trait comparable to `std::Seek` for the Flipper Zero API
+pub trait Seek {
+    fn seek(&mut self, pos: SeekFrom) -> Result<usize, Error>;
+
+    fn rewind(&mut self) -> Result<(), Error> {
+        self.seek(SeekFrom::Start(0))?;
+        Ok(())
+    }
+
+    fn stream_len(&mut self) -> Result<usize, Error> {
+        let old_pos = self.stream_position()?;
+        let len = self.seek(SeekFrom::End(0))?;
+
+        // Avoid seeking a third time when we were already at the end of the
+        // stream. The branch is usually way cheaper than a seek operation.
+        if old_pos != len {
+            self.seek(SeekFrom::Start(
+                old_pos.try_into().map_err(|_| Error::InvalidParameter)?,
+            ))?;
+        }
+
+        Ok(len)
+    }
+
+    fn stream_position(&mut self) -> Result<usize, Error> {
+        self.seek(SeekFrom::Current(0))
+    }
+}
+
+/// Trait comparable to `std::Write` for the Flipper Zero API
+pub trait Write {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Error>;
+    fn flush(&mut self) -> Result<(), Error>;
+
+    fn write_all(&mut self, mut buf: &[u8]) -> Result<(), Error> {
+        while !buf.is_empty() {
+            match self.write(buf) {
+                Ok(0) => return Err(Error::WriteZero),
+                Ok(n) => buf = &buf[n..],
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes formatted text, for use with [`core::write!`] and [`core::writeln!`].
+    ///
+    /// Fails with the first error from [`Write::write`], or with [`Error::InvalidData`]
+    /// if a formatting trait implementation failed.
+    ///
+    /// To format with [`ufmt`], use [`Write::fmt_writer`].
+    fn write_fmt(&mut self, args: fmt::Arguments<'_>) -> Result<(), Error> {
+        let mut writer = self.fmt_writer();
+        match fmt::Write::write_fmt(&mut writer, args) {
+            Ok(()) => Ok(()),
+            Err(fmt::Error) => Err(writer.error.unwrap_or(Error::InvalidData)),
+        }
+    }
+
+    /// Returns an adaptor implementing [`core::fmt::Write`] and [`ufmt::uWrite`], which
+    /// writes text to this writer.
+    ///
+    /// ```no_run
+    /// # use flipperzero::io::{Error, Write};
+    /// # fn example(file: &mut impl Write) -> Result<(), Error> {
+    /// let count = 3;
+    /// ufmt::uwriteln!(file.fmt_writer(), "count = {}", count)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn fmt_writer(&mut self) -> FmtWriter<'_, Self> {
+        FmtWriter {
+            inner: self,
+            error: None,
+        }
+    }
+
+    /// Borrows this writer, so that adaptors don't consume it.
+    fn by_ref(&mut self) -> &mut Self
+    where
+        Self: Sized,
+    {
+        self
+    }
+}
+
+/// Enumeration of possible methods to seek within an I/O object.
+///
+/// It is used by the Seek trait.
+pub enum SeekFrom {
+    Start(u64),
+    End(i64),
+    Current(i64),
+}
+
+/// Adaptor writing formatted text to a [`Write`].
+///
+/// Returned by [`Write::fmt_writer`]. As a [`ufmt::uWrite`], errors are returned
+/// directly. As a [`core::fmt::Write`], the [`Error`] is kept and can be retrieved with
+/// [`FmtWriter::take_error`], since [`core::fmt::Error`] carries no information.
+pub struct FmtWriter<'a, W: ?Sized> {
+    inner: &'a mut W,
+    error: Option<Error>,
+}
+
+impl<W: ?Sized> FmtWriter<'_, W> {
+    /// Returns the last error hit while formatting with [`core::fmt::Write`], if any.
+    pub fn take_error(&mut self) -> Option<Error> {
+        self.error.take()
+    }
+}
+
+impl<W: Write + ?Sized> fmt::Write for FmtWriter<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.inner.write_all(s.as_bytes()).map_err(|err| {
+            self.error = Some(err);
+            fmt::Error
+        })
+    }
+}
+
+impl<W: Write + ?Sized> ufmt::uWrite for FmtWriter<'_, W> {
+    type Error = Error;
+
+    fn write_str(&mut self, s: &str) -> Result<(), Error> {
+        self.inner.write_all(s.as_bytes())
+    }
+}
+
+impl<R: Read + ?Sized> Read for &mut R {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        (**self).read(buf)
+    }
+}
+
+impl<B: BufRead + ?Sized> BufRead for &mut B {
+    fn fill_buf(&mut self) -> Result<&[u8], Error> {
+        (**self).fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        (**self).consume(amt)
+    }
+}
+
+impl<S: Seek + ?Sized> Seek for &mut S {
+    fn seek(&mut self, pos: SeekFrom) -> Result<usize, Error> {
+        (**self).seek(pos)
+    }
+}
+
+impl<W: Write + ?Sized> Write for &mut W {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        (**self).write(buf)
+    }
+
+    fn flush(&mut self) -> Result<(), Error> {
+        (**self).flush()
+    }
+}
+
+impl Read for &[u8] {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        let n = buf.len().min(self.len());
+        let (head, tail) = self.split_at(n);
+        buf[..n].copy_from_slice(head);
+        *self = tail;
+        Ok(n)
+    }
+}
+
+impl BufRead for &[u8] {
+    fn fill_buf(&mut self) -> Result<&[u8], Error> {
+        Ok(self)
+    }
+
+    fn consume(&mut self, amt: usize) {
+        *self = &self[amt..];
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Write for Vec<u8> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        self.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+}
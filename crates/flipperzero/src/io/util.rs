@@ -0,0 +1,186 @@
+use super::{BufRead, Error, Read, Write, DEFAULT_BUF_SIZE};
+
+/// Copies all bytes from `reader` to `writer`, returning the number of bytes copied.
+///
+/// This uses a buffer of 512 bytes on the stack. To copy through a larger buffer, wrap
+/// `reader` in a [`BufReader`](super::BufReader) and use [`copy_buf`].
+pub fn copy<R, W>(reader: &mut R, writer: &mut W) -> Result<u64, Error>
+where
+    R: Read + ?Sized,
+    W: Write + ?Sized,
+{
+    let mut buf = [0u8; DEFAULT_BUF_SIZE];
+    let mut copied = 0;
+    loop {
+        match reader.read(&mut buf)? {
+            0 => return Ok(copied),
+            n => {
+                writer.write_all(&buf[..n])?;
+                copied += n as u64;
+            }
+        }
+    }
+}
+
+/// Copies all bytes from `reader` to `writer` through the reader's own buffer,
+/// returning the number of bytes copied.
+pub fn copy_buf<R, W>(reader: &mut R, writer: &mut W) -> Result<u64, Error>
+where
+    R: BufRead + ?Sized,
+    W: Write + ?Sized,
+{
+    let mut copied = 0;
+    loop {
+        let buf = reader.fill_buf()?;
+        if buf.is_empty() {
+            return Ok(copied);
+        }
+
+        let n = buf.len();
+        writer.write_all(buf)?;
+        reader.consume(n);
+        copied += n as u64;
+    }
+}
+
+/// Reader adaptor that reads at most a limited number of bytes.
+///
+/// Returned by [`Read::take`].
+#[derive(Debug)]
+pub struct Take<R> {
+    inner: R,
+    limit: u64,
+}
+
+impl<R> Take<R> {
+    pub(super) fn new(inner: R, limit: u64) -> Self {
+        Self { inner, limit }
+    }
+
+    /// Number of bytes that can still be read before this adaptor ends.
+    pub fn limit(&self) -> u64 {
+        self.limit
+    }
+
+    /// Changes the number of bytes that can still be read.
+    pub fn set_limit(&mut self, limit: u64) {
+        self.limit = limit;
+    }
+
+    /// Gets a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying reader.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Unwraps this adaptor, returning the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read> Read for Take<R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        if self.limit == 0 {
+            return Ok(0);
+        }
+
+        let max = buf
+            .len()
+            .min(usize::try_from(self.limit).unwrap_or(usize::MAX));
+        let n = self.inner.read(&mut buf[..max])?;
+        self.limit -= n as u64;
+        Ok(n)
+    }
+}
+
+impl<R: BufRead> BufRead for Take<R> {
+    fn fill_buf(&mut self) -> Result<&[u8], Error> {
+        if self.limit == 0 {
+            return Ok(&[]);
+        }
+
+        let buf = self.inner.fill_buf()?;
+        let max = buf
+            .len()
+            .min(usize::try_from(self.limit).unwrap_or(usize::MAX));
+        Ok(&buf[..max])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        let amt = amt.min(usize::try_from(self.limit).unwrap_or(usize::MAX));
+        self.limit -= amt as u64;
+        self.inner.consume(amt);
+    }
+}
+
+/// Reader adaptor that reads from one reader and then another.
+///
+/// Returned by [`Read::chain`].
+#[derive(Debug)]
+pub struct Chain<A, B> {
+    first: A,
+    second: B,
+    done_first: bool,
+}
+
+impl<A, B> Chain<A, B> {
+    pub(super) fn new(first: A, second: B) -> Self {
+        Self {
+            first,
+            second,
+            done_first: false,
+        }
+    }
+
+    /// Gets references to the underlying readers.
+    pub fn get_ref(&self) -> (&A, &B) {
+        (&self.first, &self.second)
+    }
+
+    /// Gets mutable references to the underlying readers.
+    pub fn get_mut(&mut self) -> (&mut A, &mut B) {
+        (&mut self.first, &mut self.second)
+    }
+
+    /// Unwraps this adaptor, returning the underlying readers.
+    pub fn into_inner(self) -> (A, B) {
+        (self.first, self.second)
+    }
+}
+
+impl<A: Read, B: Read> Read for Chain<A, B> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        if !self.done_first {
+            match self.first.read(buf)? {
+                0 if !buf.is_empty() => self.done_first = true,
+                n => return Ok(n),
+            }
+        }
+        self.second.read(buf)
+    }
+}
+
+impl<A: BufRead, B: BufRead> BufRead for Chain<A, B> {
+    fn fill_buf(&mut self) -> Result<&[u8], Error> {
+        if !self.done_first {
+            match self.first.fill_buf()? {
+                [] => self.done_first = true,
+                buf => return Ok(buf),
+            }
+        }
+        self.second.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        if self.done_first {
+            self.second.consume(amt)
+        } else {
+            self.first.consume(amt)
+        }
+    }
+}
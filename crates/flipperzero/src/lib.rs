@@ -16,14 +16,18 @@ extern crate alloc;
 
 pub mod dialogs;
 pub mod dolphin;
+pub mod flipper_format;
 pub mod furi;
 pub mod gpio;
 pub mod gui;
+pub mod ibutton;
 pub mod io;
 pub mod macros;
 pub mod notification;
+pub mod rfid;
 pub mod serial;
 pub mod storage;
+pub mod stream;
 pub mod toolbox;
 
 #[doc(hidden)]
@@ -58,13 +62,57 @@ flipperzero_test::tests_runner!(
     name = "flipperzero-rs Unit Tests",
     stack_size = 4096,
     [
+        crate::flipper_format::tests,
         crate::furi::log::metadata::tests,
         crate::furi::message_queue::tests,
+        crate::furi::pubsub::tests,
         crate::furi::rng::tests,
         crate::furi::string::tests,
         crate::furi::sync::tests,
         crate::furi::time::tests,
         crate::gpio::i2c::tests,
+        #[cfg(feature = "alloc")]
+        crate::gui::button_menu::tests,
+        #[cfg(feature = "alloc")]
+        crate::gui::byte_input::tests,
+        #[cfg(feature = "alloc")]
+        crate::gui::console::tests,
+        #[cfg(feature = "alloc")]
+        crate::gui::dialog_ex::tests,
+        #[cfg(feature = "alloc")]
+        crate::gui::file_browser::tests,
+        crate::gui::input::tests,
+        crate::gui::loading::tests,
+        #[cfg(feature = "alloc")]
+        crate::gui::number_input::tests,
+        #[cfg(feature = "alloc")]
+        crate::gui::popup::tests,
+        #[cfg(feature = "alloc")]
+        crate::gui::scene_manager::tests,
+        #[cfg(feature = "alloc")]
+        crate::gui::submenu::tests,
+        #[cfg(feature = "alloc")]
+        crate::gui::text_input::tests,
+        #[cfg(feature = "alloc")]
+        crate::gui::variable_item_list::tests,
+        crate::gui::view::tests,
+        #[cfg(feature = "alloc")]
+        crate::gui::view_dispatcher::tests,
+        crate::gui::view_port::tests,
+        #[cfg(feature = "alloc")]
+        crate::gui::widget::tests,
+        crate::ibutton::tests,
+        crate::rfid::tests,
+        crate::storage::atomic::tests,
+        crate::storage::dir::tests,
+        crate::storage::fs::tests,
+        crate::storage::path_buf::tests,
+        crate::storage::sd::tests,
+        crate::storage::tar::tests,
+        crate::storage::walk::tests,
+        crate::stream::tests,
+        #[cfg(feature = "alloc")]
+        crate::toolbox::compress::tests,
         crate::toolbox::crc32::tests,
         // crate::toolbox::md5::tests,
         // crate::toolbox::sha256::tests,
@@ -0,0 +1,664 @@
+//! 125 kHz (low frequency) RFID.
+//!
+//! Supports reading, writing and emulating EM4100, HID Prox, Indala and the other tag
+//! protocols known to the firmware, as well as loading and saving `.rfid` key files.
+
+use core::ffi::{c_void, CStr};
+use core::fmt;
+use core::ptr::NonNull;
+
+use flipperzero_sys as sys;
+use sys::furi::FuriBox;
+
+use crate::furi::string::FuriString;
+
+/// LF RFID tag protocol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Protocol {
+    EM4100 = sys::LFRFIDProtocolEM4100.0,
+    EM4100_32 = sys::LFRFIDProtocolEM410032.0,
+    EM4100_16 = sys::LFRFIDProtocolEM410016.0,
+    Electra = sys::LFRFIDProtocolElectra.0,
+    H10301 = sys::LFRFIDProtocolH10301.0,
+    Idteck = sys::LFRFIDProtocolIdteck.0,
+    Indala26 = sys::LFRFIDProtocolIndala26.0,
+    IOProxXSF = sys::LFRFIDProtocolIOProxXSF.0,
+    Awid = sys::LFRFIDProtocolAwid.0,
+    FDXA = sys::LFRFIDProtocolFDXA.0,
+    FDXB = sys::LFRFIDProtocolFDXB.0,
+    HidGeneric = sys::LFRFIDProtocolHidGeneric.0,
+    HidExGeneric = sys::LFRFIDProtocolHidExGeneric.0,
+    Pyramid = sys::LFRFIDProtocolPyramid.0,
+    Viking = sys::LFRFIDProtocolViking.0,
+    Jablotron = sys::LFRFIDProtocolJablotron.0,
+    Paradox = sys::LFRFIDProtocolParadox.0,
+    PACStanley = sys::LFRFIDProtocolPACStanley.0,
+    Keri = sys::LFRFIDProtocolKeri.0,
+    Gallagher = sys::LFRFIDProtocolGallagher.0,
+    Nexwatch = sys::LFRFIDProtocolNexwatch.0,
+    Securakey = sys::LFRFIDProtocolSecurakey.0,
+    GProxII = sys::LFRFIDProtocolGProxII.0,
+    Noralsy = sys::LFRFIDProtocolNoralsy.0,
+}
+
+impl Protocol {
+    /// All protocols, in firmware order.
+    pub const ALL: [Protocol; sys::LFRFIDProtocolMax.0 as usize] = [
+        Self::EM4100,
+        Self::EM4100_32,
+        Self::EM4100_16,
+        Self::Electra,
+        Self::H10301,
+        Self::Idteck,
+        Self::Indala26,
+        Self::IOProxXSF,
+        Self::Awid,
+        Self::FDXA,
+        Self::FDXB,
+        Self::HidGeneric,
+        Self::HidExGeneric,
+        Self::Pyramid,
+        Self::Viking,
+        Self::Jablotron,
+        Self::Paradox,
+        Self::PACStanley,
+        Self::Keri,
+        Self::Gallagher,
+        Self::Nexwatch,
+        Self::Securakey,
+        Self::GProxII,
+        Self::Noralsy,
+    ];
+
+    pub fn from_sys(protocol: sys::LFRFIDProtocol) -> Option<Self> {
+        Self::ALL.get(usize::from(protocol.0)).copied()
+    }
+
+    pub fn to_sys(&self) -> sys::LFRFIDProtocol {
+        sys::LFRFIDProtocol(*self as u8)
+    }
+
+    /// Convert a protocol dictionary index into a protocol.
+    ///
+    /// Negative indices (`PROTOCOL_NO`) and unknown protocols map to `None`.
+    fn from_id(id: sys::ProtocolId) -> Option<Self> {
+        u8::try_from(id)
+            .ok()
+            .and_then(|id| Self::from_sys(sys::LFRFIDProtocol(id)))
+    }
+
+    /// Index of this protocol in the protocol dictionary.
+    fn index(self) -> usize {
+        usize::from(self as u8)
+    }
+
+    fn base(self) -> &'static sys::ProtocolBase {
+        // SAFETY: `lfrfid_protocols` holds a pointer to a static protocol description for
+        // every protocol below `LFRFIDProtocolMax`.
+        unsafe { &**sys::lfrfid_protocols.as_ptr().add(self.index()) }
+    }
+
+    /// Name of the protocol, as used in `.rfid` key files (e.g. `EM4100`).
+    pub fn name(self) -> &'static CStr {
+        // SAFETY: Protocol names are static nul-terminated strings.
+        unsafe { CStr::from_ptr(self.base().name) }
+    }
+
+    /// Manufacturer of tags using this protocol.
+    pub fn manufacturer(self) -> &'static CStr {
+        // SAFETY: Protocol manufacturers are static nul-terminated strings.
+        unsafe { CStr::from_ptr(self.base().manufacturer) }
+    }
+
+    /// Size of the protocol's decoded data in bytes.
+    pub fn data_size(self) -> usize {
+        self.base().data_size
+    }
+}
+
+/// Decoded data of an LF RFID tag.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Tag {
+    protocol: Protocol,
+    data: [u8; Tag::MAX_DATA_SIZE],
+}
+
+impl Tag {
+    /// Maximum data size of any protocol supported by [`Tag`].
+    pub const MAX_DATA_SIZE: usize = 32;
+
+    /// Creates a tag from its protocol and decoded data.
+    ///
+    /// Returns `None` if the length of `data` does not match [`Protocol::data_size`].
+    pub fn new(protocol: Protocol, data: &[u8]) -> Option<Self> {
+        if data.len() != protocol.data_size() || data.len() > Self::MAX_DATA_SIZE {
+            return None;
+        }
+
+        let mut tag = Tag {
+            protocol,
+            data: [0; Self::MAX_DATA_SIZE],
+        };
+        tag.data[..data.len()].copy_from_slice(data);
+
+        Some(tag)
+    }
+
+    /// Loads a tag from a `.rfid` key file.
+    pub fn load(path: &CStr) -> Result<Self, Error> {
+        let dict = ProtocolDict::new();
+        let id = unsafe { sys::lfrfid_dict_file_load(dict.as_ptr(), path.as_ptr()) };
+
+        Protocol::from_id(id)
+            .map(|protocol| dict.get(protocol))
+            .ok_or(Error::Load)
+    }
+
+    /// Saves the tag to a `.rfid` key file.
+    pub fn save(&self, path: &CStr) -> Result<(), Error> {
+        let mut dict = ProtocolDict::new();
+        dict.set(self);
+
+        let id = self.protocol.index() as sys::ProtocolId;
+        if unsafe { sys::lfrfid_dict_file_save(dict.as_ptr(), id, path.as_ptr()) } {
+            Ok(())
+        } else {
+            Err(Error::Save)
+        }
+    }
+
+    /// Protocol of the tag.
+    pub fn protocol(&self) -> Protocol {
+        self.protocol
+    }
+
+    /// Name of the tag's protocol.
+    pub fn name(&self) -> &'static CStr {
+        self.protocol.name()
+    }
+
+    /// Decoded tag data.
+    pub fn data(&self) -> &[u8] {
+        &self.data[..self.protocol.data_size()]
+    }
+}
+
+impl fmt::Debug for Tag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Tag")
+            .field("protocol", &self.protocol)
+            .field("data", &self.data())
+            .finish()
+    }
+}
+
+/// Dictionary of all LF RFID protocols, holding the data of one tag per protocol.
+pub struct ProtocolDict {
+    raw: NonNull<sys::ProtocolDict>,
+}
+
+impl ProtocolDict {
+    /// Allocates a dictionary of all protocols supported by the firmware.
+    pub fn new() -> Self {
+        let protocols = unsafe { sys::lfrfid_protocols.as_ptr() };
+        let count = sys::LFRFIDProtocolMax.0 as usize;
+
+        Self {
+            // SAFETY: Alloc always returns a valid non-null pointer or `furi_panic`s.
+            raw: unsafe { NonNull::new_unchecked(sys::protocol_dict_alloc(protocols, count)) },
+        }
+    }
+
+    /// Obtain raw protocol dictionary.
+    ///
+    /// This pointer must not be `free`d or otherwise invalidated.
+    /// It must not be referenced after [`ProtocolDict`] has been dropped.
+    #[inline]
+    pub fn as_ptr(&self) -> *mut sys::ProtocolDict {
+        self.raw.as_ptr()
+    }
+
+    /// Finds a protocol by its name (e.g. `c"EM4100"`).
+    pub fn protocol_by_name(&self, name: &CStr) -> Option<Protocol> {
+        Protocol::from_id(unsafe {
+            sys::protocol_dict_get_protocol_by_name(self.as_ptr(), name.as_ptr())
+        })
+    }
+
+    /// Returns the tag currently stored for `protocol`.
+    pub fn get(&self, protocol: Protocol) -> Tag {
+        // SAFETY: Pointer is always non-null and valid `sys::ProtocolDict`
+        unsafe { get_tag(self.as_ptr(), protocol) }
+    }
+
+    /// Stores the tag's data for its protocol.
+    pub fn set(&mut self, tag: &Tag) {
+        let data = tag.data();
+
+        unsafe {
+            sys::protocol_dict_set_data(
+                self.as_ptr(),
+                tag.protocol.index(),
+                data.as_ptr(),
+                data.len(),
+            )
+        };
+    }
+
+    /// Renders the tag's UID, as shown by the firmware's RFID app.
+    pub fn render_uid(&mut self, tag: &Tag) -> FuriString {
+        self.render(tag, sys::protocol_dict_render_uid)
+    }
+
+    /// Renders a full human-readable description of the tag's data.
+    pub fn render_data(&mut self, tag: &Tag) -> FuriString {
+        self.render(tag, sys::protocol_dict_render_data)
+    }
+
+    /// Renders a short human-readable description of the tag's data.
+    pub fn render_brief_data(&mut self, tag: &Tag) -> FuriString {
+        self.render(tag, sys::protocol_dict_render_brief_data)
+    }
+
+    fn render(
+        &mut self,
+        tag: &Tag,
+        render: unsafe extern "C" fn(*mut sys::ProtocolDict, *mut sys::FuriString, usize),
+    ) -> FuriString {
+        self.set(tag);
+
+        let mut result = FuriString::new();
+        unsafe { render(self.as_ptr(), result.as_mut_ptr(), tag.protocol.index()) };
+
+        result
+    }
+}
+
+/// Copies the data stored for `protocol` out of a raw protocol dictionary.
+///
+/// # Safety
+///
+/// `dict` must point to a valid protocol dictionary allocated for `lfrfid_protocols`.
+unsafe fn get_tag(dict: *mut sys::ProtocolDict, protocol: Protocol) -> Tag {
+    let mut tag = Tag {
+        protocol,
+        data: [0; Tag::MAX_DATA_SIZE],
+    };
+    let size = protocol.data_size();
+    assert!(size <= Tag::MAX_DATA_SIZE);
+
+    unsafe { sys::protocol_dict_get_data(dict, protocol.index(), tag.data.as_mut_ptr(), size) };
+
+    tag
+}
+
+impl Drop for ProtocolDict {
+    fn drop(&mut self) {
+        unsafe { sys::protocol_dict_free(self.as_ptr()) }
+    }
+}
+
+impl Default for ProtocolDict {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Modulations to try while reading.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ReadType {
+    /// Alternate between ASK and PSK modulated protocols.
+    #[default]
+    Auto,
+    /// Only read ASK modulated protocols.
+    AskOnly,
+    /// Only read PSK modulated protocols.
+    PskOnly,
+}
+
+impl ReadType {
+    pub fn to_sys(&self) -> sys::LFRFIDWorkerReadType {
+        match self {
+            Self::Auto => sys::LFRFIDWorkerReadTypeAuto,
+            Self::AskOnly => sys::LFRFIDWorkerReadTypeASKOnly,
+            Self::PskOnly => sys::LFRFIDWorkerReadTypePSKOnly,
+        }
+    }
+}
+
+/// Progress of a [`Worker::read`] operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReadEvent {
+    /// A carrier was detected.
+    SenseStart,
+    /// The carrier was lost.
+    SenseEnd,
+    /// A card started to respond.
+    SenseCardStart,
+    /// The card stopped responding.
+    SenseCardEnd,
+    /// Started reading ASK modulated protocols.
+    StartAsk,
+    /// Started reading PSK modulated protocols.
+    StartPsk,
+    /// A tag was successfully decoded.
+    Done(Tag),
+}
+
+/// Reasons a [`Worker::write`] attempt can fail.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WriteError {
+    /// The protocol cannot be written to a T5577.
+    ProtocolCannotBeWritten,
+    /// The fob in the field cannot be written (or none is present).
+    FobCannotBeWritten,
+    /// The protocol's data is too long to be written.
+    TooLongToWrite,
+}
+
+impl WriteError {
+    fn from_sys(result: sys::LFRFIDWorkerWriteResult) -> Result<(), Self> {
+        match result {
+            sys::LFRFIDWorkerWriteOK => Ok(()),
+            sys::LFRFIDWorkerWriteProtocolCannotBeWritten => Err(Self::ProtocolCannotBeWritten),
+            sys::LFRFIDWorkerWriteTooLongToWrite => Err(Self::TooLongToWrite),
+            _ => Err(Self::FobCannotBeWritten),
+        }
+    }
+}
+
+/// Errors from loading and saving `.rfid` key files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// The file is missing, malformed or uses an unknown protocol.
+    Load,
+    /// The file could not be written.
+    Save,
+}
+
+impl Error {
+    /// Description associated with [`Error`].
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Load => "failed to load RFID key file",
+            Self::Save => "failed to save RFID key file",
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.description())
+    }
+}
+
+impl ufmt::uDisplay for Error {
+    fn fmt<W>(&self, f: &mut ufmt::Formatter<'_, W>) -> Result<(), W::Error>
+    where
+        W: ufmt::uWrite + ?Sized,
+    {
+        f.write_str(self.description())
+    }
+}
+
+/// LF RFID worker.
+///
+/// The worker runs one operation at a time on a dedicated thread. Each operation is
+/// represented by a handle, which stops the operation when dropped. If the handle is
+/// leaked instead, the operation runs until the next one is started or the worker is
+/// dropped.
+pub struct Worker {
+    raw: NonNull<sys::LFRFIDWorker>,
+    dict: ProtocolDict,
+    /// Whether the worker thread is running.
+    running: bool,
+}
+
+impl Worker {
+    /// Allocates a new worker.
+    pub fn new() -> Self {
+        let dict = ProtocolDict::new();
+
+        Self {
+            // SAFETY: Alloc always returns a valid non-null pointer or `furi_panic`s.
+            raw: unsafe { NonNull::new_unchecked(sys::lfrfid_worker_alloc(dict.as_ptr())) },
+            dict,
+            running: false,
+        }
+    }
+
+    /// Obtain raw LF RFID worker.
+    ///
+    /// This pointer must not be `free`d or otherwise invalidated.
+    /// It must not be referenced after [`Worker`] has been dropped.
+    #[inline]
+    pub fn as_ptr(&self) -> *mut sys::LFRFIDWorker {
+        self.raw.as_ptr()
+    }
+
+    /// Protocol dictionary used by the worker.
+    pub fn dict(&mut self) -> &mut ProtocolDict {
+        &mut self.dict
+    }
+
+    /// Starts reading tags.
+    ///
+    /// `on_event` is called from the worker thread for every [`ReadEvent`]. Reading
+    /// continues after a tag is decoded, until the returned [`Reader`] is dropped.
+    pub fn read<F>(&mut self, read_type: ReadType, on_event: F) -> Reader<'_, F>
+    where
+        F: FnMut(ReadEvent) + Send + 'static,
+    {
+        self.stop();
+        let mut context = FuriBox::new(ReadContext {
+            dict: self.dict.as_ptr(),
+            on_event,
+        });
+
+        self.running = true;
+        unsafe {
+            sys::lfrfid_worker_start_thread(self.as_ptr());
+            sys::lfrfid_worker_read_start(
+                self.as_ptr(),
+                read_type.to_sys(),
+                Some(read_callback::<F>),
+                FuriBox::as_mut_ptr(&mut context).cast(),
+            );
+        }
+
+        Reader {
+            worker: self,
+            _context: context,
+        }
+    }
+
+    /// Starts writing the tag to a T5577 fob.
+    ///
+    /// `on_result` is called from the worker thread after every write attempt. The worker
+    /// keeps retrying until the returned [`Writer`] is dropped.
+    pub fn write<F>(&mut self, tag: &Tag, on_result: F) -> Writer<'_, F>
+    where
+        F: FnMut(Result<(), WriteError>) + Send + 'static,
+    {
+        self.stop();
+        self.dict.set(tag);
+        let mut context = FuriBox::new(on_result);
+
+        self.running = true;
+        unsafe {
+            sys::lfrfid_worker_start_thread(self.as_ptr());
+            sys::lfrfid_worker_write_start(
+                self.as_ptr(),
+                tag.protocol.to_sys(),
+                Some(write_callback::<F>),
+                FuriBox::as_mut_ptr(&mut context).cast(),
+            );
+        }
+
+        Writer {
+            worker: self,
+            _context: context,
+        }
+    }
+
+    /// Starts emulating the tag, until the returned [`Emulator`] is dropped.
+    pub fn emulate(&mut self, tag: &Tag) -> Emulator<'_> {
+        self.stop();
+        self.dict.set(tag);
+
+        self.running = true;
+        unsafe {
+            sys::lfrfid_worker_start_thread(self.as_ptr());
+            sys::lfrfid_worker_emulate_start(self.as_ptr(), tag.protocol.to_sys());
+        }
+
+        Emulator { worker: self }
+    }
+
+    /// Stops the current operation, if any, and waits for the worker thread to exit.
+    ///
+    /// Once this returns, no more callbacks will be invoked.
+    fn stop(&mut self) {
+        if !self.running {
+            return;
+        }
+        unsafe {
+            sys::lfrfid_worker_stop(self.as_ptr());
+            sys::lfrfid_worker_stop_thread(self.as_ptr());
+        }
+        self.running = false;
+    }
+}
+
+impl Drop for Worker {
+    fn drop(&mut self) {
+        // An operation whose handle was leaked is still running.
+        self.stop();
+        // The worker must be freed before the dictionary it references.
+        unsafe { sys::lfrfid_worker_free(self.as_ptr()) }
+    }
+}
+
+impl Default for Worker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+struct ReadContext<F> {
+    dict: *mut sys::ProtocolDict,
+    on_event: F,
+}
+
+unsafe extern "C" fn read_callback<F: FnMut(ReadEvent)>(
+    result: sys::LFRFIDWorkerReadResult,
+    protocol: sys::ProtocolId,
+    context: *mut c_void,
+) {
+    let context = context.cast::<ReadContext<F>>();
+
+    let event = match result {
+        sys::LFRFIDWorkerReadSenseStart => ReadEvent::SenseStart,
+        sys::LFRFIDWorkerReadSenseEnd => ReadEvent::SenseEnd,
+        sys::LFRFIDWorkerReadSenseCardStart => ReadEvent::SenseCardStart,
+        sys::LFRFIDWorkerReadSenseCardEnd => ReadEvent::SenseCardEnd,
+        sys::LFRFIDWorkerReadStartASK => ReadEvent::StartAsk,
+        sys::LFRFIDWorkerReadStartPSK => ReadEvent::StartPsk,
+        sys::LFRFIDWorkerReadDone => match Protocol::from_id(protocol) {
+            // SAFETY: The dictionary is owned by the worker, which outlives the reader.
+            // The worker has finished updating it before invoking the callback.
+            Some(protocol) => ReadEvent::Done(unsafe { get_tag((*context).dict, protocol) }),
+            None => return,
+        },
+        _ => return,
+    };
+
+    unsafe { ((*context).on_event)(event) }
+}
+
+unsafe extern "C" fn write_callback<F: FnMut(Result<(), WriteError>)>(
+    result: sys::LFRFIDWorkerWriteResult,
+    context: *mut c_void,
+) {
+    let on_result = context.cast::<F>();
+
+    unsafe { (*on_result)(WriteError::from_sys(result)) }
+}
+
+/// Handle to a running [`Worker::read`] operation.
+///
+/// Reading stops when this handle is dropped.
+pub struct Reader<'a, F: FnMut(ReadEvent)> {
+    worker: &'a mut Worker,
+    _context: FuriBox<ReadContext<F>>,
+}
+
+impl<F: FnMut(ReadEvent)> Drop for Reader<'_, F> {
+    fn drop(&mut self) {
+        // Ensure the callback is no longer running before the context is freed.
+        self.worker.stop();
+    }
+}
+
+/// Handle to a running [`Worker::write`] operation.
+///
+/// Writing stops when this handle is dropped.
+pub struct Writer<'a, F: FnMut(Result<(), WriteError>)> {
+    worker: &'a mut Worker,
+    _context: FuriBox<F>,
+}
+
+impl<F: FnMut(Result<(), WriteError>)> Drop for Writer<'_, F> {
+    fn drop(&mut self) {
+        // Ensure the callback is no longer running before the context is freed.
+        self.worker.stop();
+    }
+}
+
+/// Handle to a running [`Worker::emulate`] operation.
+///
+/// Emulation stops when this handle is dropped.
+pub struct Emulator<'a> {
+    worker: &'a mut Worker,
+}
+
+impl Drop for Emulator<'_> {
+    fn drop(&mut self) {
+        self.worker.stop();
+    }
+}
+
+#[flipperzero_test::tests]
+mod tests {
+    use super::{Protocol, Tag};
+
+    #[test]
+    fn protocol_names() {
+        assert_eq!(Protocol::EM4100.name().to_bytes(), b"EM4100");
+        assert_eq!(Protocol::H10301.name().to_bytes(), b"H10301");
+    }
+
+    #[test]
+    fn protocols_fit_in_tag() {
+        for protocol in Protocol::ALL {
+            assert!(protocol.data_size() <= Tag::MAX_DATA_SIZE);
+            assert_eq!(Protocol::from_sys(protocol.to_sys()), Some(protocol));
+        }
+    }
+
+    #[test]
+    fn tag_data_size_is_checked() {
+        assert!(Tag::new(Protocol::EM4100, &[0xDE, 0xAD, 0xBE, 0xEF, 0x01]).is_some());
+        assert!(Tag::new(Protocol::EM4100, &[0xDE, 0xAD]).is_none());
+    }
+
+    #[test]
+    fn save_and_load() {
+        let tag = Tag::new(Protocol::EM4100, &[0x01, 0x23, 0x45, 0x67, 0x89]).unwrap();
+        let path = c"/ext/flipperzero-rs-test.rfid";
+
+        tag.save(path).unwrap();
+        assert_eq!(Tag::load(path).unwrap(), tag);
+    }
+}
@@ -0,0 +1,276 @@
+use core::ffi::CStr;
+
+use super::{File, OpenOptions, Storage};
+use crate::furi::string::FuriString;
+use crate::io::{Error, Seek, SeekFrom, Write};
+
+/// Suffix of the sibling file that new contents are written to.
+const TEMP_SUFFIX: &str = ".tmp";
+
+/// Suffix of the sibling file holding complete, synced contents that are being moved
+/// over the target.
+const STAGED_SUFFIX: &str = ".new";
+
+/// Replaces the file at `path` with the data written by `f`, such that a power loss
+/// leaves either the old or the new contents, never a mix of both.
+///
+/// The file is only replaced if `f` returns `Ok`; otherwise it is left untouched. See
+/// [`AtomicFile`] for details.
+///
+/// ```no_run
+/// # use flipperzero::io::Write;
+/// # use flipperzero::storage;
+/// storage::write_atomic(c"/ext/apps_data/example/settings.txt", |w| {
+///     writeln!(w, "volume: {}", 7)?;
+///     writeln!(w, "theme: dark")
+/// })
+/// .unwrap();
+/// ```
+pub fn write_atomic<T>(
+    path: &CStr,
+    f: impl FnOnce(&mut AtomicFile) -> Result<T, Error>,
+) -> Result<T, Error> {
+    let mut file = AtomicFile::create(path)?;
+    let value = f(&mut file)?;
+    file.commit()?;
+    Ok(value)
+}
+
+/// A file that atomically replaces another file when committed.
+///
+/// Data is written to a temporary sibling of the target (`<path>.tmp`). On
+/// [`AtomicFile::commit`], it is synced to the SD card, renamed to `<path>.new` to mark
+/// it as complete, and finally renamed over the target. Dropping an `AtomicFile`
+/// without committing removes the temporary file.
+///
+/// If power is lost part-way through, the leftover files are cleaned up by
+/// [`AtomicFile::recover`], which [`AtomicFile::create`] calls before writing: a
+/// partial `.tmp` file is removed, and a complete `.new` file is moved over the target.
+/// Apps that read the file without writing it should call [`AtomicFile::recover`]
+/// first.
+pub struct AtomicFile {
+    file: Option<File>,
+    storage: Storage,
+    target: FuriString,
+    temp: FuriString,
+    staged: FuriString,
+}
+
+impl AtomicFile {
+    /// Starts replacing the file at `path`, which need not exist yet.
+    pub fn create(path: &CStr) -> Result<Self, Error> {
+        let storage = Storage::open();
+        let temp = sibling(path, TEMP_SUFFIX);
+        let staged = sibling(path, STAGED_SUFFIX);
+        recover(&storage, path, &temp, &staged)?;
+
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create_always(true)
+            .open(temp.as_c_str())?;
+
+        Ok(Self {
+            file: Some(file),
+            storage,
+            target: FuriString::from(path),
+            temp,
+            staged,
+        })
+    }
+
+    /// Cleans up after an [`AtomicFile`] for `path` that was interrupted by a power
+    /// loss, finishing the replacement if its data was complete.
+    pub fn recover(path: &CStr) -> Result<(), Error> {
+        recover(
+            &Storage::open(),
+            path,
+            &sibling(path, TEMP_SUFFIX),
+            &sibling(path, STAGED_SUFFIX),
+        )
+    }
+
+    /// Gets a reference to the temporary file.
+    pub fn as_file(&self) -> &File {
+        self.file.as_ref().unwrap()
+    }
+
+    /// Gets a mutable reference to the temporary file.
+    pub fn as_file_mut(&mut self) -> &mut File {
+        self.file.as_mut().unwrap()
+    }
+
+    /// Syncs the written data and moves it over the target file.
+    ///
+    /// If this fails after the data was synced, the next [`AtomicFile::recover`]
+    /// finishes the replacement.
+    pub fn commit(mut self) -> Result<(), Error> {
+        let mut file = self.file.take().unwrap();
+        let synced = file.sync_all();
+        drop(file);
+        if let Err(err) = synced {
+            let _ = self.storage.remove_file(self.temp.as_c_str());
+            return Err(err);
+        }
+
+        self.storage
+            .rename(self.temp.as_c_str(), self.staged.as_c_str())?;
+        self.storage
+            .rename(self.staged.as_c_str(), self.target.as_c_str())
+    }
+}
+
+impl Write for AtomicFile {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        self.as_file_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> Result<(), Error> {
+        self.as_file_mut().flush()
+    }
+}
+
+impl Seek for AtomicFile {
+    fn seek(&mut self, pos: SeekFrom) -> Result<usize, Error> {
+        self.as_file_mut().seek(pos)
+    }
+}
+
+impl Drop for AtomicFile {
+    fn drop(&mut self) {
+        // Not committed, so discard the new contents.
+        if let Some(file) = self.file.take() {
+            drop(file);
+            let _ = self.storage.remove_file(self.temp.as_c_str());
+        }
+    }
+}
+
+fn sibling(path: &CStr, suffix: &str) -> FuriString {
+    let mut sibling = FuriString::from(path);
+    sibling.push_str(suffix);
+    sibling
+}
+
+fn recover(
+    storage: &Storage,
+    path: &CStr,
+    temp: &FuriString,
+    staged: &FuriString,
+) -> Result<(), Error> {
+    if storage.exists(staged.as_c_str()) {
+        storage.rename(staged.as_c_str(), path)?;
+    }
+    if storage.exists(temp.as_c_str()) {
+        storage.remove_file(temp.as_c_str())?;
+    }
+    Ok(())
+}
+
+#[flipperzero_test::tests]
+mod tests {
+    use core::ffi::CStr;
+
+    use super::{write_atomic, AtomicFile};
+    use crate::io::{Error, Read, Seek, SeekFrom, Write};
+    use crate::storage::{OpenOptions, Storage};
+
+    fn read_file<'a>(path: &CStr, buf: &'a mut [u8]) -> &'a [u8] {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .open_existing(true)
+            .open(path)
+            .unwrap();
+        let len = file.stream_len().unwrap();
+        file.read_exact(&mut buf[..len]).unwrap();
+        &buf[..len]
+    }
+
+    #[test]
+    fn file_len_and_sync() {
+        let file_path = c"/ext/.flipperzero-rs-file-test.txt";
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create_always(true)
+            .open(file_path)
+            .unwrap();
+
+        file.write_all(b"hello world").unwrap();
+        file.sync_all().unwrap();
+        assert_eq!(file.metadata().unwrap().len(), 11);
+        assert!(file.metadata().unwrap().is_file());
+
+        file.set_len(5).unwrap();
+        assert_eq!(file.stream_position().unwrap(), 5);
+        file.set_len(8).unwrap();
+        assert_eq!(file.stream_len().unwrap(), 8);
+        assert_eq!(file.stream_position().unwrap(), 5);
+
+        file.seek(SeekFrom::Start(0)).unwrap();
+        let mut buf = [0xff; 8];
+        file.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"hello\0\0\0");
+
+        file.seek(SeekFrom::Start(2)).unwrap();
+        file.truncate().unwrap();
+        assert_eq!(file.metadata().unwrap().len(), 2);
+
+        drop(file);
+        Storage::open().remove_file(file_path).unwrap();
+    }
+
+    #[test]
+    fn atomic_replace() {
+        let storage = Storage::open();
+        let target = c"/ext/.flipperzero-rs-atomic-test.txt";
+        let temp = c"/ext/.flipperzero-rs-atomic-test.txt.tmp";
+        let staged = c"/ext/.flipperzero-rs-atomic-test.txt.new";
+        let mut buf = [0; 16];
+
+        write_atomic(target, |w| w.write_all(b"first")).unwrap();
+        assert_eq!(read_file(target, &mut buf), b"first");
+        assert!(!storage.exists(temp));
+
+        // Failing or dropping leaves the old contents.
+        assert!(matches!(
+            write_atomic(target, |w| {
+                w.write_all(b"partial")?;
+                Err::<(), _>(Error::Internal)
+            }),
+            Err(Error::Internal)
+        ));
+        let mut file = AtomicFile::create(target).unwrap();
+        file.write_all(b"dropped").unwrap();
+        assert!(storage.exists(temp));
+        drop(file);
+        assert!(!storage.exists(temp));
+        assert_eq!(read_file(target, &mut buf), b"first");
+
+        // Simulate a power loss while writing, then after syncing.
+        OpenOptions::new()
+            .write(true)
+            .create_always(true)
+            .open(temp)
+            .unwrap()
+            .write_all(b"partial")
+            .unwrap();
+        AtomicFile::recover(target).unwrap();
+        assert!(!storage.exists(temp));
+        assert_eq!(read_file(target, &mut buf), b"first");
+
+        OpenOptions::new()
+            .write(true)
+            .create_always(true)
+            .open(staged)
+            .unwrap()
+            .write_all(b"second")
+            .unwrap();
+        let file = AtomicFile::create(target).unwrap();
+        assert!(!storage.exists(staged));
+        assert_eq!(read_file(target, &mut buf), b"second");
+        drop(file);
+
+        storage.remove_file(target).unwrap();
+    }
+}
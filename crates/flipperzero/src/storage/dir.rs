@@ -0,0 +1,295 @@
+use core::ffi::CStr;
+use core::ptr::NonNull;
+
+use flipperzero_sys as sys;
+
+use super::{check, Storage};
+use crate::furi::string::FuriString;
+use crate::io::Error;
+
+/// Size of the buffer that directory entry names are read into.
+///
+/// FAT long file names are at most 255 bytes, plus the nul terminator.
+const NAME_BUF_LEN: usize = 256;
+
+impl Storage {
+    /// Returns the metadata of the file or directory at `path`.
+    pub fn metadata(&self, path: &CStr) -> Result<Metadata, Error> {
+        let mut info = sys::FileInfo { flags: 0, size: 0 };
+        check(unsafe { sys::storage_common_stat(self.as_ptr(), path.as_ptr(), &mut info) })?;
+
+        Ok(Metadata::new(info, self.timestamp(path)))
+    }
+
+    /// Checks whether a file or directory exists at `path`.
+    pub fn exists(&self, path: &CStr) -> bool {
+        unsafe { sys::storage_common_exists(self.as_ptr(), path.as_ptr()) }
+    }
+
+    /// Checks whether `path` is an existing directory.
+    pub fn is_dir(&self, path: &CStr) -> bool {
+        unsafe { sys::storage_dir_exists(self.as_ptr(), path.as_ptr()) }
+    }
+
+    /// Checks whether `path` is an existing file.
+    pub fn is_file(&self, path: &CStr) -> bool {
+        unsafe { sys::storage_file_exists(self.as_ptr(), path.as_ptr()) }
+    }
+
+    /// Returns an iterator over the entries of the directory at `path`.
+    ///
+    /// The directory is kept open until the [`ReadDir`] is dropped. The special entries
+    /// `.` and `..` are not returned, and the order of entries is unspecified.
+    pub fn read_dir(&self, path: &CStr) -> Result<ReadDir, Error> {
+        let dir = ReadDir {
+            // SAFETY: Alloc always returns a valid non-null pointer or `furi_panic`s.
+            raw: unsafe { NonNull::new_unchecked(sys::storage_file_alloc(self.as_ptr())) },
+            storage: self.clone(),
+            path: FuriString::from(path),
+            done: false,
+        };
+
+        if unsafe { sys::storage_dir_open(dir.raw.as_ptr(), path.as_ptr()) } {
+            Ok(dir)
+        } else {
+            // The directory must be closed even if opening failed, which is handled by
+            // `Drop`.
+            Err(dir.error().unwrap_or(Error::Internal))
+        }
+    }
+
+    /// Last modification time of `path`, in seconds since the UNIX epoch.
+    pub(super) fn timestamp(&self, path: &CStr) -> Option<u32> {
+        let mut timestamp = 0;
+        check(unsafe {
+            sys::storage_common_timestamp(self.as_ptr(), path.as_ptr(), &mut timestamp)
+        })
+        .ok()
+        .map(|()| timestamp)
+    }
+}
+
+/// Metadata of a file or directory.
+///
+/// Returned by [`Storage::metadata`] and [`DirEntry::metadata`].
+#[derive(Debug, Clone, Copy)]
+pub struct Metadata {
+    info: sys::FileInfo,
+    modified: Option<u32>,
+}
+
+impl Metadata {
+    pub(super) fn new(info: sys::FileInfo, modified: Option<u32>) -> Self {
+        Self { info, modified }
+    }
+
+    /// Returns `true` if this is a directory.
+    pub fn is_dir(&self) -> bool {
+        self.info.flags & sys::FSF_DIRECTORY.0 != 0
+    }
+
+    /// Returns `true` if this is a regular file.
+    pub fn is_file(&self) -> bool {
+        !self.is_dir()
+    }
+
+    /// Size of the file, in bytes.
+    pub fn len(&self) -> u64 {
+        self.info.size
+    }
+
+    /// Returns `true` if the file is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Last modification time, in seconds since the UNIX epoch.
+    ///
+    /// Returns `None` if the filesystem could not provide it.
+    pub fn modified(&self) -> Option<u32> {
+        self.modified
+    }
+
+    /// Raw file info.
+    pub fn to_sys(&self) -> sys::FileInfo {
+        self.info
+    }
+}
+
+/// Iterator over the entries of a directory.
+///
+/// Returned by [`Storage::read_dir`]. The directory is closed when this is dropped.
+pub struct ReadDir {
+    raw: NonNull<sys::File>,
+    storage: Storage,
+    path: FuriString,
+    done: bool,
+}
+
+impl ReadDir {
+    /// Obtain raw Furi file handle of the open directory.
+    ///
+    /// This pointer must not be `free`d or otherwise invalidated.
+    /// It must not be referenced after [`ReadDir`] has been dropped.
+    pub fn as_ptr(&self) -> *mut sys::File {
+        self.raw.as_ptr()
+    }
+
+    fn error(&self) -> Option<Error> {
+        // SAFETY: Pointer is always non-null and valid `sys::File`
+        Error::from_sys(unsafe { sys::storage_file_get_error(self.as_ptr()) })
+    }
+}
+
+impl Iterator for ReadDir {
+    type Item = Result<DirEntry, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let mut info = sys::FileInfo { flags: 0, size: 0 };
+        let mut name = [0u8; NAME_BUF_LEN];
+        let found = unsafe {
+            sys::storage_dir_read(
+                self.as_ptr(),
+                &mut info,
+                name.as_mut_ptr().cast(),
+                NAME_BUF_LEN as u16,
+            )
+        };
+
+        if !found {
+            // Reading past the last entry fails with `FSE_NOT_EXIST`.
+            self.done = true;
+            return match self.error() {
+                None | Some(Error::NotExists) => None,
+                Some(err) => Some(Err(err)),
+            };
+        }
+
+        // The firmware always nul-terminates names, truncating them if necessary.
+        let name = CStr::from_bytes_until_nul(&name).unwrap_or_default();
+
+        let mut path = self.path.clone();
+        if !path.ends_with('/') {
+            path.push('/');
+        }
+        path.push_c_str(name);
+
+        Some(Ok(DirEntry::new(&self.storage, path, info)))
+    }
+}
+
+impl Drop for ReadDir {
+    fn drop(&mut self) {
+        unsafe {
+            sys::storage_dir_close(self.as_ptr());
+            sys::storage_file_free(self.as_ptr());
+        }
+    }
+}
+
+/// An entry returned by [`ReadDir`].
+#[derive(Debug, Clone)]
+pub struct DirEntry {
+    name: FuriString,
+    path: FuriString,
+    metadata: Metadata,
+}
+
+impl DirEntry {
+    pub(super) fn new(storage: &Storage, path: FuriString, info: sys::FileInfo) -> Self {
+        let modified = storage.timestamp(path.as_c_str());
+
+        let bytes = path.to_bytes_with_nul();
+        let start = path.rfind('/').map_or(0, |i| i + 1);
+        // `bytes` still ends with the nul terminator, and contains no interior nul.
+        let name = FuriString::from(CStr::from_bytes_with_nul(&bytes[start..]).unwrap());
+
+        Self {
+            name,
+            path,
+            metadata: Metadata::new(info, modified),
+        }
+    }
+
+    /// File name of this entry, without the directory.
+    pub fn name(&self) -> &FuriString {
+        &self.name
+    }
+
+    /// Full path of this entry.
+    pub fn path(&self) -> &FuriString {
+        &self.path
+    }
+
+    /// Returns `true` if this entry is a directory.
+    pub fn is_dir(&self) -> bool {
+        self.metadata.is_dir()
+    }
+
+    /// Size of this entry, in bytes.
+    pub fn size(&self) -> u64 {
+        self.metadata.len()
+    }
+
+    /// Last modification time, in seconds since the UNIX epoch.
+    pub fn mtime(&self) -> Option<u32> {
+        self.metadata.modified()
+    }
+
+    /// Metadata of this entry.
+    pub fn metadata(&self) -> Metadata {
+        self.metadata
+    }
+}
+
+#[flipperzero_test::tests]
+mod tests {
+    use crate::io::Write;
+    use crate::storage::{OpenOptions, Storage};
+    use flipperzero_sys as sys;
+
+    #[test]
+    fn metadata_and_read_dir() {
+        let storage = Storage::open();
+        let dir = c"/ext";
+        let file = c"/ext/.flipperzero-rs-read-dir-test";
+
+        {
+            let mut f = OpenOptions::new()
+                .write(true)
+                .create_always(true)
+                .open(file)
+                .unwrap();
+            f.write_all(b"hello").unwrap();
+        }
+
+        assert!(storage.exists(file));
+        assert!(storage.is_file(file));
+        assert!(!storage.is_dir(file));
+        assert!(storage.is_dir(dir));
+
+        let metadata = storage.metadata(file).unwrap();
+        assert!(metadata.is_file());
+        assert_eq!(metadata.len(), 5);
+        assert!(storage.metadata(dir).unwrap().is_dir());
+
+        let entry = storage
+            .read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap())
+            .find(|entry| entry.name() == ".flipperzero-rs-read-dir-test")
+            .expect("file not listed");
+        assert!(!entry.is_dir());
+        assert_eq!(entry.size(), 5);
+        assert_eq!(entry.path().as_c_str(), file);
+
+        unsafe { sys::storage_common_remove(storage.as_ptr(), file.as_ptr()) };
+        assert!(!storage.exists(file));
+        assert!(storage.metadata(file).is_err());
+        assert!(storage.read_dir(c"/ext/.flipperzero-rs-missing").is_err());
+    }
+}
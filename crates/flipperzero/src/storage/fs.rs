@@ -0,0 +1,230 @@
+use core::ffi::CStr;
+
+use flipperzero_sys as sys;
+
+use super::{check, Storage};
+use crate::furi::string::FuriString;
+use crate::io::Error;
+
+impl Storage {
+    /// Creates a new, empty directory at `path`.
+    ///
+    /// Fails with [`Error::Exists`] if `path` already exists, and with
+    /// [`Error::NotExists`] if its parent does not.
+    pub fn create_dir(&self, path: &CStr) -> Result<(), Error> {
+        check(unsafe { sys::storage_common_mkdir(self.as_ptr(), path.as_ptr()) })
+    }
+
+    /// Creates the directory at `path` and any missing parent directories.
+    ///
+    /// Succeeds if `path` is already a directory.
+    pub fn create_dir_all(&self, path: &CStr) -> Result<(), Error> {
+        let bytes = path.to_bytes();
+
+        // Ends of each ancestor of `path`, skipping the root and empty components.
+        let ends = bytes
+            .iter()
+            .enumerate()
+            .filter(|&(i, &b)| b == b'/' && i > 0)
+            .map(|(i, _)| i)
+            .chain(core::iter::once(bytes.len()))
+            .filter(|&end| end > 0 && bytes[end - 1] != b'/');
+
+        for end in ends {
+            let mut prefix = FuriString::from(path);
+            prefix.truncate(end);
+
+            if !self.is_dir(prefix.as_c_str()) {
+                self.create_dir(prefix.as_c_str())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Removes the file at `path`.
+    ///
+    /// Fails with [`Error::InvalidParameter`] if `path` is a directory.
+    pub fn remove_file(&self, path: &CStr) -> Result<(), Error> {
+        if self.metadata(path)?.is_dir() {
+            return Err(Error::InvalidParameter);
+        }
+        check(unsafe { sys::storage_common_remove(self.as_ptr(), path.as_ptr()) })
+    }
+
+    /// Removes the empty directory at `path`.
+    ///
+    /// Fails with [`Error::InvalidParameter`] if `path` is not a directory, and with
+    /// [`Error::Denied`] if it is not empty.
+    pub fn remove_dir(&self, path: &CStr) -> Result<(), Error> {
+        if !self.metadata(path)?.is_dir() {
+            return Err(Error::InvalidParameter);
+        }
+        check(unsafe { sys::storage_common_remove(self.as_ptr(), path.as_ptr()) })
+    }
+
+    /// Removes the directory at `path` and everything inside it.
+    ///
+    /// Fails with [`Error::InvalidParameter`] if `path` is not a directory. The firmware
+    /// does not report why a recursive removal failed, so any later failure is reported
+    /// as [`Error::Internal`]; some entries may already have been removed.
+    pub fn remove_dir_all(&self, path: &CStr) -> Result<(), Error> {
+        if !self.metadata(path)?.is_dir() {
+            return Err(Error::InvalidParameter);
+        }
+
+        if unsafe { sys::storage_simply_remove_recursive(self.as_ptr(), path.as_ptr()) } {
+            Ok(())
+        } else {
+            Err(Error::Internal)
+        }
+    }
+
+    /// Renames the file or directory at `from` to `to`, replacing `to` if it is an
+    /// existing file.
+    ///
+    /// Neither path may be open.
+    pub fn rename(&self, from: &CStr, to: &CStr) -> Result<(), Error> {
+        check(unsafe { sys::storage_common_rename(self.as_ptr(), from.as_ptr(), to.as_ptr()) })
+    }
+
+    /// Copies the file or directory at `from` to `to`.
+    ///
+    /// Directories are copied recursively. `from` must not be open.
+    pub fn copy(&self, from: &CStr, to: &CStr) -> Result<(), Error> {
+        check(unsafe { sys::storage_common_copy(self.as_ptr(), from.as_ptr(), to.as_ptr()) })
+    }
+
+    /// Copies the file or directory at `from` into `to`, keeping existing files.
+    ///
+    /// Where a file already exists in `to`, the copy is given the next free name
+    /// instead (e.g. `file1.txt`).
+    pub fn merge(&self, from: &CStr, to: &CStr) -> Result<(), Error> {
+        check(unsafe { sys::storage_common_merge(self.as_ptr(), from.as_ptr(), to.as_ptr()) })
+    }
+
+    /// Returns the total and free space of the filesystem containing `path`, such as
+    /// `/int` or `/ext`.
+    pub fn fs_info(&self, path: &CStr) -> Result<FsInfo, Error> {
+        let mut total = 0;
+        let mut free = 0;
+        check(unsafe {
+            sys::storage_common_fs_info(self.as_ptr(), path.as_ptr(), &mut total, &mut free)
+        })?;
+
+        Ok(FsInfo { total, free })
+    }
+}
+
+/// Size of a filesystem.
+///
+/// Returned by [`Storage::fs_info`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FsInfo {
+    total: u64,
+    free: u64,
+}
+
+impl FsInfo {
+    /// Total capacity, in bytes.
+    pub fn total_bytes(&self) -> u64 {
+        self.total
+    }
+
+    /// Available space, in bytes.
+    pub fn free_bytes(&self) -> u64 {
+        self.free
+    }
+}
+
+#[flipperzero_test::tests]
+mod tests {
+    use crate::io::{Error, Write};
+    use crate::storage::{OpenOptions, Storage};
+
+    #[test]
+    fn create_and_remove() {
+        let storage = Storage::open();
+        let root = c"/ext/.flipperzero-rs-fs-test";
+        let nested = c"/ext/.flipperzero-rs-fs-test/a/b/";
+        let file = c"/ext/.flipperzero-rs-fs-test/a/b/file.txt";
+
+        storage.create_dir_all(nested).unwrap();
+        storage.create_dir_all(nested).unwrap();
+        assert!(storage.is_dir(c"/ext/.flipperzero-rs-fs-test/a/b"));
+        assert!(matches!(storage.create_dir(root), Err(Error::Exists)));
+
+        OpenOptions::new()
+            .write(true)
+            .create_always(true)
+            .open(file)
+            .unwrap()
+            .write_all(b"data")
+            .unwrap();
+        assert!(matches!(
+            storage.remove_dir(file),
+            Err(Error::InvalidParameter)
+        ));
+        assert!(matches!(
+            storage.remove_file(nested),
+            Err(Error::InvalidParameter)
+        ));
+        assert!(storage.remove_dir(nested).is_err());
+
+        storage.remove_file(file).unwrap();
+        assert!(matches!(storage.remove_file(file), Err(Error::NotExists)));
+        storage.remove_dir(nested).unwrap();
+
+        OpenOptions::new()
+            .write(true)
+            .create_always(true)
+            .open(c"/ext/.flipperzero-rs-fs-test/a/file.txt")
+            .unwrap()
+            .write_all(b"data")
+            .unwrap();
+        assert!(storage.remove_dir(root).is_err());
+        storage.remove_dir_all(root).unwrap();
+        assert!(!storage.exists(root));
+    }
+
+    #[test]
+    fn rename_copy_merge() {
+        let storage = Storage::open();
+        let root = c"/ext/.flipperzero-rs-fs-test2";
+        let a = c"/ext/.flipperzero-rs-fs-test2/a.txt";
+        let b = c"/ext/.flipperzero-rs-fs-test2/b.txt";
+        let dest = c"/ext/.flipperzero-rs-fs-test2/dest";
+        let dest_b = c"/ext/.flipperzero-rs-fs-test2/dest/b.txt";
+
+        storage.create_dir_all(dest).unwrap();
+        OpenOptions::new()
+            .write(true)
+            .create_always(true)
+            .open(a)
+            .unwrap()
+            .write_all(b"hello")
+            .unwrap();
+
+        storage.rename(a, b).unwrap();
+        assert!(!storage.exists(a));
+        assert_eq!(storage.metadata(b).unwrap().len(), 5);
+
+        storage.copy(b, dest_b).unwrap();
+        assert!(storage.exists(b));
+        assert_eq!(storage.metadata(dest_b).unwrap().len(), 5);
+
+        // Merging onto an existing file keeps both.
+        storage.merge(b, dest_b).unwrap();
+        assert_eq!(storage.read_dir(dest).unwrap().count(), 2);
+
+        storage.remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn fs_info() {
+        let storage = Storage::open();
+        let info = storage.fs_info(c"/int").unwrap();
+        assert!(info.total_bytes() > 0);
+        assert!(info.free_bytes() <= info.total_bytes());
+    }
+}
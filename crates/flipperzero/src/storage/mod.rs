@@ -1,3 +1,15 @@
+//! Storage service.
+
+pub(crate) mod atomic;
+pub(crate) mod dir;
+pub(crate) mod fs;
+pub(crate) mod path;
+pub(crate) mod path_buf;
+pub(crate) mod sd;
+pub mod tar;
+pub(crate) mod walk;
+
+use core::cmp::Ordering;
 use core::ffi::{c_void, CStr};
 use core::ptr::NonNull;
 
@@ -6,6 +18,14 @@ use flipperzero_sys::{self as sys, HasFlag};
 
 use crate::io::*;
 
+pub use atomic::{write_atomic, AtomicFile};
+pub use dir::{DirEntry, Metadata, ReadDir};
+pub use fs::FsInfo;
+pub use path::Path;
+pub use path_buf::PathBuf;
+pub use sd::{SdInfo, SdStatus, StorageEvent};
+pub use walk::{NoFilter, WalkDir, WalkDirIter};
+
 /// Storage service handle.
 #[derive(Clone)]
 pub struct Storage {
@@ -29,6 +49,21 @@ impl Storage {
     }
 }
 
+/// Converts the result of a `storage_common_*` call.
+fn check(err: sys::FS_Error) -> Result<(), Error> {
+    match Error::from_sys(err) {
+        Some(err) => Err(err),
+        None => Ok(()),
+    }
+}
+
+/// Returns `true` if `path` is on the internal storage rather than the SD card.
+fn is_internal(path: &CStr) -> bool {
+    let path = path.to_bytes();
+    path.strip_prefix(b"/int")
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with(b"/"))
+}
+
 #[derive(Debug, Default, Clone, Copy)]
 pub struct OpenOptions {
     access_mode: sys::FS_AccessMode,
@@ -131,7 +166,8 @@ impl OpenOptions {
         )
     }
 
-    pub fn open(self, path: &CStr) -> Result<File, Error> {
+    /// The access mode and the canonical open mode to pass to the SDK.
+    pub(crate) fn to_sys(self) -> (sys::FS_AccessMode, sys::FS_OpenMode) {
         // It's possible to produce a nonsensical `open_mode` using the above
         // operations, so we have some logic here to drop any extraneous
         // information. The possible open modes form a partial order (for
@@ -150,14 +186,16 @@ impl OpenOptions {
             sys::FSOM_OPEN_EXISTING
         };
 
-        let f = File::new();
+        (self.access_mode, canonicalized_open_mode)
+    }
+
+    pub fn open(self, path: &CStr) -> Result<File, Error> {
+        let (access_mode, open_mode) = self.to_sys();
+
+        let mut f = File::new();
+        f.on_sd_card = !is_internal(path);
         if unsafe {
-            sys::storage_file_open(
-                f.as_ptr(),
-                path.as_ptr().cast(),
-                self.access_mode,
-                canonicalized_open_mode,
-            )
+            sys::storage_file_open(f.as_ptr(), path.as_ptr().cast(), access_mode, open_mode)
         } {
             Ok(f)
         } else {
@@ -169,10 +207,15 @@ impl OpenOptions {
 }
 
 /// Basic, unbuffered file handle
-#[allow(dead_code)]
+///
+/// If the SD card is removed while the file is open, operations on it fail with
+/// [`Error::NotReady`]. The handle can't be used again, even if the card is put back;
+/// drop it and open the file again.
 pub struct File {
     raw: NonNull<sys::File>,
     storage: Storage,
+    /// Whether the file may be on the SD card, i.e. its path is not on `/int`.
+    on_sd_card: bool,
 }
 
 impl File {
@@ -182,6 +225,7 @@ impl File {
             // SAFETY: Alloc always returns a valid non-null pointer or `furi_panic`s.
             raw: unsafe { NonNull::new_unchecked(sys::storage_file_alloc(storage.as_ptr())) },
             storage,
+            on_sd_card: false,
         }
     }
 
@@ -198,6 +242,102 @@ impl File {
         // SAFETY: Pointer is always non-null and valid `sys::File`
         unsafe { sys::storage_file_get_error(self.as_ptr()) }
     }
+
+    /// Converts the result of a `storage_file_*` call that returns `bool`.
+    fn check(&self, ok: bool) -> Result<(), Error> {
+        if ok {
+            Ok(())
+        } else {
+            Err(self.error(Error::from_sys(self.get_raw_error()).unwrap_or(Error::Internal)))
+        }
+    }
+
+    /// Replaces `err` with [`Error::NotReady`] if the SD card has gone away, which the
+    /// storage service reports as whatever error the filesystem ran into.
+    fn error(&self, err: Error) -> Error {
+        if self.sd_card_removed() {
+            Error::NotReady
+        } else {
+            err
+        }
+    }
+
+    /// Returns `true` if the SD card is not mounted.
+    fn card_removed(&self) -> bool {
+        !self.storage.sd_status().is_mounted()
+    }
+
+    /// Like [`File::card_removed`], but without asking the storage service about files
+    /// that can't be on the SD card.
+    fn sd_card_removed(&self) -> bool {
+        self.on_sd_card && self.card_removed()
+    }
+
+    /// Writes any data cached by the storage service to the SD card.
+    ///
+    /// Closing the file also does this, but errors on close can't be reported, so call
+    /// this before dropping a file whose contents matter.
+    pub fn sync_all(&mut self) -> Result<(), Error> {
+        // SAFETY: Pointer is always non-null and valid `sys::File`
+        self.check(unsafe { sys::storage_file_sync(self.as_ptr()) })
+    }
+
+    /// Truncates the file at the current position.
+    pub fn truncate(&mut self) -> Result<(), Error> {
+        // SAFETY: Pointer is always non-null and valid `sys::File`
+        self.check(unsafe { sys::storage_file_truncate(self.as_ptr()) })
+    }
+
+    /// Truncates or extends the file to `len` bytes.
+    ///
+    /// Extending the file fills the new space with zeros. The position is unchanged,
+    /// unless it was past the new end of the file, in which case it is moved there.
+    pub fn set_len(&mut self, len: u64) -> Result<(), Error> {
+        let len: usize = len.try_into().map_err(|_| Error::InvalidParameter)?;
+        let pos = self.stream_position()?;
+        let current = self.stream_len()?;
+
+        match len.cmp(&current) {
+            Ordering::Less => {
+                self.seek(SeekFrom::Start(len as u64))?;
+                self.truncate()?;
+            }
+            Ordering::Greater => {
+                self.seek(SeekFrom::Start(current as u64))?;
+                let zeros = [0; 64];
+                let mut remaining = len - current;
+                while remaining > 0 {
+                    let n = remaining.min(zeros.len());
+                    self.write_all(&zeros[..n])?;
+                    remaining -= n;
+                }
+            }
+            Ordering::Equal => {}
+        }
+
+        self.seek(SeekFrom::Start(pos.min(len) as u64))?;
+        Ok(())
+    }
+
+    /// Queries metadata about the open file.
+    ///
+    /// The storage service can't look up the modification time of an open file, so
+    /// [`Metadata::modified`] is always `None`; use [`Storage::metadata`] for that.
+    pub fn metadata(&self) -> Result<Metadata, Error> {
+        // SAFETY: Pointer is always non-null and valid `sys::File`
+        let (is_dir, size) = unsafe {
+            if !sys::storage_file_is_open(self.as_ptr()) {
+                return Err(Error::InvalidParameter);
+            }
+            (
+                sys::storage_file_is_dir(self.as_ptr()),
+                sys::storage_file_size(self.as_ptr()),
+            )
+        };
+
+        let flags = if is_dir { sys::FSF_DIRECTORY.0 } else { 0 };
+        Ok(Metadata::new(sys::FileInfo { flags, size }, None))
+    }
 }
 
 impl Drop for File {
@@ -217,7 +357,11 @@ impl Read for File {
         };
 
         match Error::from_sys(self.get_raw_error()) {
-            Some(err) => Err(err),
+            Some(err) => Err(self.error(err)),
+            // Reads from a removed card can look like the end of the file.
+            None if bytes_read == 0 && !buf.is_empty() && self.sd_card_removed() => {
+                Err(Error::NotReady)
+            }
             None => Ok(bytes_read),
         }
     }
@@ -249,7 +393,7 @@ impl Seek for File {
                     .try_into()
                     .map_err(|_| Error::InvalidParameter)?)
             } else {
-                Err(Error::from_sys(self.get_raw_error()).unwrap())
+                Err(self.error(Error::from_sys(self.get_raw_error()).unwrap()))
             }
         }
     }
@@ -282,7 +426,10 @@ impl Write for File {
         };
 
         match Error::from_sys(self.get_raw_error()) {
-            Some(err) => Err(err),
+            Some(err) => Err(self.error(err)),
+            None if bytes_written == 0 && !buf.is_empty() && self.sd_card_removed() => {
+                Err(Error::NotReady)
+            }
             None => Ok(bytes_written),
         }
     }
@@ -0,0 +1,252 @@
+//! Borrowed storage paths.
+//!
+//! This module only depends on `core` and `ufmt`, so that it can be tested on the host (see
+//! `crates/host-tests`).
+
+use core::fmt;
+
+/// A borrowed path on the Flipper's storage, such as `/ext/subghz/garage.sub`.
+///
+/// Paths are `/`-separated UTF-8 strings. Like [`std::path::Path`], this only
+/// manipulates the path as text: nothing is resolved or checked against the filesystem.
+///
+/// Use [`PathBuf`](super::PathBuf) to build paths and pass them to [`Storage`](super::Storage).
+///
+/// [`std::path::Path`]: https://doc.rust-lang.org/std/path/struct.Path.html
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct Path {
+    inner: str,
+}
+
+impl Path {
+    /// The root directory, `/`.
+    pub const ROOT: &'static Path = Path::new("/");
+
+    /// Wraps a string slice as a `Path`.
+    pub const fn new(path: &str) -> &Path {
+        // SAFETY: `Path` is a `repr(transparent)` wrapper around `str`.
+        unsafe { &*(path as *const str as *const Path) }
+    }
+
+    /// The path as a string slice.
+    pub const fn as_str(&self) -> &str {
+        &self.inner
+    }
+
+    /// Returns `true` if the path starts with `/`.
+    pub fn is_absolute(&self) -> bool {
+        self.inner.starts_with('/')
+    }
+
+    /// Returns `true` if the path is the root directory (`/`, or only slashes).
+    pub fn is_root(&self) -> bool {
+        !self.inner.is_empty() && self.inner.bytes().all(|b| b == b'/')
+    }
+
+    /// Returns the path without its final component, if there is one.
+    ///
+    /// Returns `None` for the root directory and the empty path. The parent of a
+    /// relative path with a single component is the empty path.
+    ///
+    /// ```
+    /// # use flipperzero::storage::Path;
+    /// assert_eq!(Path::new("/ext/subghz/").parent(), Some(Path::new("/ext")));
+    /// assert_eq!(Path::new("/ext").parent(), Some(Path::ROOT));
+    /// assert_eq!(Path::ROOT.parent(), None);
+    /// ```
+    pub fn parent(&self) -> Option<&Path> {
+        if self.inner.is_empty() || self.is_root() {
+            return None;
+        }
+
+        let trimmed = self.trimmed();
+        Some(match trimmed.rfind('/') {
+            None => Path::new(""),
+            Some(i) => {
+                let parent = trimmed[..i].trim_end_matches('/');
+                if parent.is_empty() {
+                    Path::ROOT
+                } else {
+                    Path::new(parent)
+                }
+            }
+        })
+    }
+
+    /// Returns the final component of the path, if there is one.
+    ///
+    /// Trailing slashes are ignored. Returns `None` for the root directory, the empty
+    /// path and paths ending in `..`.
+    pub fn file_name(&self) -> Option<&str> {
+        let name = self.name();
+        match name {
+            "" | ".." => None,
+            name => Some(name),
+        }
+    }
+
+    /// Returns the file name without its extension.
+    ///
+    /// See [`Path::extension`] for how the extension is found.
+    pub fn file_stem(&self) -> Option<&str> {
+        self.file_name().map(|name| split_extension(name).0)
+    }
+
+    /// Returns the extension of the file name, without the `.`.
+    ///
+    /// The extension is the part after the last `.`, unless that `.` starts the name
+    /// (as in `.hidden`).
+    ///
+    /// ```
+    /// # use flipperzero::storage::Path;
+    /// assert_eq!(Path::new("/ext/infrared/tv.ir").extension(), Some("ir"));
+    /// assert_eq!(Path::new("/ext/archive.tar.gz").extension(), Some("gz"));
+    /// assert_eq!(Path::new("/ext/.hidden").extension(), None);
+    /// ```
+    pub fn extension(&self) -> Option<&str> {
+        self.file_name().and_then(|name| split_extension(name).1)
+    }
+
+    /// Returns `true` if `base` is a prefix of this path, comparing whole components.
+    pub fn starts_with(&self, base: &Path) -> bool {
+        let mut components = self.components();
+        base.components().all(|c| components.next() == Some(c))
+            && self.is_absolute() == base.is_absolute()
+    }
+
+    /// Iterates over the non-empty components of the path, skipping the separators.
+    pub fn components(&self) -> impl DoubleEndedIterator<Item = &str> {
+        self.inner.split('/').filter(|c| !c.is_empty())
+    }
+
+    /// The path without trailing slashes.
+    fn trimmed(&self) -> &str {
+        self.inner.trim_end_matches('/')
+    }
+
+    /// The final component of the path, which may be empty or `..`.
+    fn name(&self) -> &str {
+        let trimmed = self.trimmed();
+        match trimmed.rfind('/') {
+            Some(i) => &trimmed[i + 1..],
+            None => trimmed,
+        }
+    }
+}
+
+/// Splits a file name into its stem and extension.
+fn split_extension(name: &str) -> (&str, Option<&str>) {
+    match name.rfind('.') {
+        Some(0) | None => (name, None),
+        Some(i) => (&name[..i], Some(&name[i + 1..])),
+    }
+}
+
+/// The pieces that make up `base` joined with `path`, in order.
+///
+/// As with [`std::path::Path::join`], an absolute `path` replaces `base`.
+///
+/// [`std::path::Path::join`]: https://doc.rust-lang.org/std/path/struct.Path.html#method.join
+pub(crate) fn join_parts<'a>(base: &'a Path, path: &'a Path) -> [&'a str; 3] {
+    if path.is_absolute() || base.inner.is_empty() {
+        ["", "", path.as_str()]
+    } else if base.inner.ends_with('/') {
+        [base.as_str(), "", path.as_str()]
+    } else {
+        [base.as_str(), "/", path.as_str()]
+    }
+}
+
+/// The pieces that make up `path` with its extension replaced by `extension`, in order.
+///
+/// A leading `.` in `extension` is ignored, and an empty `extension` removes the
+/// extension. Trailing slashes are dropped. Paths without a file name are returned
+/// unchanged.
+pub(crate) fn with_extension_parts<'a>(path: &'a Path, extension: &'a str) -> [&'a str; 3] {
+    let extension = extension.strip_prefix('.').unwrap_or(extension);
+
+    if path.file_name().is_none() {
+        return [path.as_str(), "", ""];
+    }
+
+    let trimmed = path.trimmed();
+    let stem_end = match path.extension() {
+        Some(ext) => trimmed.len() - ext.len() - 1,
+        None => trimmed.len(),
+    };
+
+    if extension.is_empty() {
+        [&trimmed[..stem_end], "", ""]
+    } else {
+        [&trimmed[..stem_end], ".", extension]
+    }
+}
+
+impl AsRef<Path> for Path {
+    fn as_ref(&self) -> &Path {
+        self
+    }
+}
+
+impl AsRef<Path> for str {
+    fn as_ref(&self) -> &Path {
+        Path::new(self)
+    }
+}
+
+impl AsRef<str> for Path {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<'a> From<&'a str> for &'a Path {
+    fn from(path: &'a str) -> Self {
+        Path::new(path)
+    }
+}
+
+impl PartialEq<str> for Path {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for Path {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl fmt::Debug for Path {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Display for Path {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl ufmt::uDebug for Path {
+    fn fmt<W>(&self, f: &mut ufmt::Formatter<'_, W>) -> Result<(), W::Error>
+    where
+        W: ufmt::uWrite + ?Sized,
+    {
+        f.write_char('"')?;
+        f.write_str(self.as_str())?;
+        f.write_char('"')
+    }
+}
+
+impl ufmt::uDisplay for Path {
+    fn fmt<W>(&self, f: &mut ufmt::Formatter<'_, W>) -> Result<(), W::Error>
+    where
+        W: ufmt::uWrite + ?Sized,
+    {
+        f.write_str(self.as_str())
+    }
+}
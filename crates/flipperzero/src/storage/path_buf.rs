@@ -0,0 +1,382 @@
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::ffi::CStr;
+use core::fmt;
+use core::hash;
+use core::ops::Deref;
+
+use flipperzero_sys as sys;
+
+use super::path::{join_parts, with_extension_parts};
+use super::{Path, Storage};
+use crate::furi::string::FuriString;
+
+/// An owned, mutable storage path, backed by a [`FuriString`].
+///
+/// Dereferences to [`Path`], and can be passed to [`Storage`] methods with
+/// [`PathBuf::as_c_str`].
+///
+/// Comparison and hashing agree with [`Path`], so `PathBuf` can be looked up by `&Path`
+/// in maps and sets.
+#[derive(Clone, Default)]
+pub struct PathBuf {
+    inner: FuriString,
+}
+
+impl PathBuf {
+    /// Creates an empty path.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Converts a C string to a path, if it is valid UTF-8.
+    pub fn from_c_str(path: &CStr) -> Option<Self> {
+        path.to_str().ok().map(Self::from)
+    }
+
+    /// Converts a [`FuriString`] to a path, if it is valid UTF-8.
+    pub fn from_furi_string(path: FuriString) -> Option<Self> {
+        core::str::from_utf8(path.to_bytes())
+            .is_ok()
+            .then_some(Self { inner: path })
+    }
+
+    /// The path as a [`Path`].
+    pub fn as_path(&self) -> &Path {
+        // SAFETY: `PathBuf` is only ever built from valid UTF-8.
+        Path::new(unsafe { core::str::from_utf8_unchecked(self.inner.to_bytes()) })
+    }
+
+    /// The path as a C string, for passing to [`Storage`] and the SDK.
+    pub fn as_c_str(&self) -> &CStr {
+        self.inner.as_c_str()
+    }
+
+    /// The underlying [`FuriString`].
+    pub fn as_furi_string(&self) -> &FuriString {
+        &self.inner
+    }
+
+    /// Converts into the underlying [`FuriString`].
+    pub fn into_furi_string(self) -> FuriString {
+        self.inner
+    }
+
+    /// Appends `path`, adding a `/` separator if needed.
+    ///
+    /// If `path` is absolute, it replaces the current path.
+    pub fn push<P: AsRef<Path>>(&mut self, path: P) {
+        let parts = join_parts(self.as_path(), path.as_ref());
+        self.inner = concat(parts);
+    }
+
+    /// Truncates the path to its parent.
+    ///
+    /// Returns `false` and does nothing if there is no parent.
+    pub fn pop(&mut self) -> bool {
+        match self.as_path().parent().map(|parent| parent.as_str().len()) {
+            Some(len) => {
+                self.inner.truncate(len);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Replaces the extension of the file name, or removes it if `extension` is empty.
+    ///
+    /// Returns `false` and does nothing if there is no file name.
+    pub fn set_extension(&mut self, extension: &str) -> bool {
+        if self.as_path().file_name().is_none() {
+            return false;
+        }
+
+        let parts = with_extension_parts(self.as_path(), extension);
+        self.inner = concat(parts);
+        true
+    }
+}
+
+impl Path {
+    /// Creates an owned [`PathBuf`] with `path` joined onto this path.
+    ///
+    /// If `path` is absolute, it replaces this path.
+    ///
+    /// ```no_run
+    /// # use flipperzero::storage::Path;
+    /// let path = Path::new("/ext/subghz").join("garage.sub");
+    /// assert_eq!(path.as_path(), "/ext/subghz/garage.sub");
+    /// ```
+    pub fn join<P: AsRef<Path>>(&self, path: P) -> PathBuf {
+        PathBuf {
+            inner: concat(join_parts(self, path.as_ref())),
+        }
+    }
+
+    /// Creates an owned [`PathBuf`] with the extension replaced by `extension`.
+    ///
+    /// See [`PathBuf::set_extension`].
+    pub fn with_extension(&self, extension: &str) -> PathBuf {
+        PathBuf {
+            inner: concat(with_extension_parts(self, extension)),
+        }
+    }
+
+    /// Creates an owned copy of this path.
+    pub fn to_path_buf(&self) -> PathBuf {
+        PathBuf::from(self)
+    }
+}
+
+impl Storage {
+    /// Returns the data directory of the running app, creating it if necessary.
+    ///
+    /// This resolves the firmware's `/data` alias (`APP_DATA_PATH`), normally to
+    /// `/ext/apps_data/<appid>`.
+    pub fn app_data_dir(&self) -> PathBuf {
+        self.resolve_app_alias(c"/data")
+    }
+
+    /// Returns the assets directory of the running app, creating it if necessary.
+    ///
+    /// This resolves the firmware's `/assets` alias (`APP_ASSETS_PATH`), normally to
+    /// `/ext/apps_assets/<appid>`. Assets are unpacked there from the FAP when it is
+    /// launched. The firmware only offers a resolver that also creates the directory, so
+    /// this leaves an empty directory behind for apps that have no assets.
+    pub fn app_assets_dir(&self) -> PathBuf {
+        self.resolve_app_alias(c"/assets")
+    }
+
+    /// Returns the first path in `dir` of the form `<name><extension>`,
+    /// `<name>1<extension>`, `<name>2<extension>`, ... that does not exist yet.
+    ///
+    /// `extension` includes the `.`, e.g. `".sub"`. If the numbered name would be longer
+    /// than `max_len`, `name` is used as it is.
+    ///
+    /// ```no_run
+    /// # use flipperzero::storage::{Path, Storage};
+    /// let storage = Storage::open();
+    /// // "/ext/subghz/Capture.sub", "/ext/subghz/Capture1.sub", ...
+    /// let path = storage.next_free_filename(Path::new("/ext/subghz"), "Capture", ".sub", 32);
+    /// ```
+    pub fn next_free_filename(
+        &self,
+        dir: &Path,
+        name: &str,
+        extension: &str,
+        max_len: u8,
+    ) -> PathBuf {
+        let c_dir = FuriString::from(dir.as_str());
+        let c_name = FuriString::from(name);
+        let c_extension = FuriString::from(extension);
+
+        let mut next = FuriString::new();
+        unsafe {
+            sys::storage_get_next_filename(
+                self.as_ptr(),
+                c_dir.as_c_str().as_ptr(),
+                c_name.as_c_str().as_ptr(),
+                c_extension.as_c_str().as_ptr(),
+                next.as_mut_ptr(),
+                max_len,
+            );
+        }
+        next.push_str(extension);
+
+        // The firmware only appends decimal digits to `name`, so `next` is still UTF-8.
+        let next = PathBuf::from_furi_string(next).expect("next file name is UTF-8");
+        dir.join(next.as_path())
+    }
+
+    /// Resolves an app alias, creating the directory it points to if it does not exist.
+    fn resolve_app_alias(&self, alias: &CStr) -> PathBuf {
+        let mut path = FuriString::from(alias);
+        unsafe {
+            sys::storage_common_resolve_path_and_ensure_app_directory(
+                self.as_ptr(),
+                path.as_mut_ptr(),
+            );
+        }
+        // App IDs are ASCII, as are the app directories they resolve to.
+        PathBuf::from_furi_string(path).expect("app directory is UTF-8")
+    }
+}
+
+fn concat(parts: [&str; 3]) -> FuriString {
+    let mut s = FuriString::with_capacity(parts.iter().map(|p| p.len()).sum());
+    for part in parts {
+        s.push_str(part);
+    }
+    s
+}
+
+impl Deref for PathBuf {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        self.as_path()
+    }
+}
+
+impl AsRef<Path> for PathBuf {
+    fn as_ref(&self) -> &Path {
+        self.as_path()
+    }
+}
+
+impl AsRef<CStr> for PathBuf {
+    fn as_ref(&self) -> &CStr {
+        self.as_c_str()
+    }
+}
+
+impl Borrow<Path> for PathBuf {
+    fn borrow(&self) -> &Path {
+        self.as_path()
+    }
+}
+
+impl From<&Path> for PathBuf {
+    fn from(path: &Path) -> Self {
+        Self {
+            inner: FuriString::from(path.as_str()),
+        }
+    }
+}
+
+impl From<&str> for PathBuf {
+    fn from(path: &str) -> Self {
+        Self::from(Path::new(path))
+    }
+}
+
+impl From<PathBuf> for FuriString {
+    fn from(path: PathBuf) -> Self {
+        path.inner
+    }
+}
+
+impl PartialEq for PathBuf {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_path() == other.as_path()
+    }
+}
+
+impl Eq for PathBuf {}
+
+impl PartialOrd for PathBuf {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PathBuf {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_path().cmp(other.as_path())
+    }
+}
+
+impl hash::Hash for PathBuf {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.as_path().hash(state);
+    }
+}
+
+impl PartialEq<Path> for PathBuf {
+    fn eq(&self, other: &Path) -> bool {
+        self.as_path() == other
+    }
+}
+
+impl PartialEq<&str> for PathBuf {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_path() == *other
+    }
+}
+
+impl fmt::Debug for PathBuf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_path(), f)
+    }
+}
+
+impl fmt::Display for PathBuf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_path(), f)
+    }
+}
+
+impl ufmt::uDebug for PathBuf {
+    fn fmt<W>(&self, f: &mut ufmt::Formatter<'_, W>) -> Result<(), W::Error>
+    where
+        W: ufmt::uWrite + ?Sized,
+    {
+        ufmt::uDebug::fmt(self.as_path(), f)
+    }
+}
+
+impl ufmt::uDisplay for PathBuf {
+    fn fmt<W>(&self, f: &mut ufmt::Formatter<'_, W>) -> Result<(), W::Error>
+    where
+        W: ufmt::uWrite + ?Sized,
+    {
+        ufmt::uDisplay::fmt(self.as_path(), f)
+    }
+}
+
+#[flipperzero_test::tests]
+mod tests {
+    use super::PathBuf;
+    use crate::storage::{OpenOptions, Path, Storage};
+
+    #[test]
+    fn edit() {
+        let mut path = PathBuf::from("/ext/subghz");
+        path.push("remotes/garage.sub");
+        assert_eq!(path, "/ext/subghz/remotes/garage.sub");
+        assert_eq!(path.file_stem(), Some("garage"));
+
+        assert!(path.set_extension("txt"));
+        assert_eq!(path, "/ext/subghz/remotes/garage.txt");
+        assert!(path.set_extension(""));
+        assert_eq!(path.as_c_str().to_bytes(), b"/ext/subghz/remotes/garage");
+
+        assert!(path.pop());
+        assert!(path.pop());
+        assert_eq!(path, "/ext/subghz");
+        path.push("/int");
+        assert_eq!(path, "/int");
+        assert!(path.pop());
+        assert_eq!(path, "/");
+        assert!(!path.pop());
+
+        assert_eq!(
+            Path::new("/ext/a.tar.gz").with_extension("zip"),
+            "/ext/a.tar.zip"
+        );
+    }
+
+    #[test]
+    fn app_dirs_and_next_free_filename() {
+        let storage = Storage::open();
+        let data = storage.app_data_dir();
+        assert!(data.starts_with(Path::new("/ext/apps_data")));
+        assert!(storage.is_dir(data.as_c_str()));
+        assert!(storage
+            .app_assets_dir()
+            .starts_with(Path::new("/ext/apps_assets")));
+
+        let first = storage.next_free_filename(&data, "test", ".txt", 32);
+        assert_eq!(first, data.join("test.txt"));
+
+        OpenOptions::new()
+            .write(true)
+            .create_always(true)
+            .open(first.as_c_str())
+            .unwrap();
+        let second = storage.next_free_filename(&data, "test", ".txt", 32);
+        assert_eq!(second.file_name(), Some("test1.txt"));
+
+        storage.remove_file(first.as_c_str()).unwrap();
+    }
+}
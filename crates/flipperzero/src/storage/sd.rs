@@ -0,0 +1,294 @@
+use core::ffi::{c_char, CStr};
+use core::ptr::NonNull;
+
+#[cfg(feature = "alloc")]
+use alloc::{boxed::Box, sync::Arc};
+
+use flipperzero_sys as sys;
+
+use super::{check, Storage};
+#[cfg(feature = "alloc")]
+use crate::furi::message_queue::MessageQueue;
+use crate::furi::pubsub::{Message, Subscription};
+#[cfg(feature = "alloc")]
+use crate::furi::time::FuriDuration;
+use crate::io::Error;
+
+impl Storage {
+    /// Returns whether the SD card is mounted and usable.
+    pub fn sd_status(&self) -> SdStatus {
+        match Error::from_sys(unsafe { sys::storage_sd_status(self.as_ptr()) }) {
+            None => SdStatus::Mounted,
+            Some(Error::NotReady) => SdStatus::NotPresent,
+            Some(err) => SdStatus::Error(err),
+        }
+    }
+
+    /// Returns information about the SD card and its filesystem.
+    pub fn sd_info(&self) -> Result<SdInfo, Error> {
+        // SAFETY: `SDInfo` is plain data, for which all zeros is valid.
+        let mut info: sys::SDInfo = unsafe { core::mem::zeroed() };
+        check(unsafe { sys::storage_sd_info(self.as_ptr(), &mut info) })?;
+        Ok(SdInfo { info })
+    }
+
+    /// Calls `f` with every [`StorageEvent`] until the returned subscription is dropped.
+    ///
+    /// `f` is called on the storage service's thread; see [`Subscription`] for what it
+    /// may do.
+    ///
+    /// ```no_run
+    /// # use core::sync::atomic::{AtomicBool, Ordering};
+    /// # use flipperzero::storage::{Storage, StorageEvent};
+    /// static CARD_REMOVED: AtomicBool = AtomicBool::new(false);
+    ///
+    /// let storage = Storage::open();
+    /// let _subscription = storage.subscribe(|event| {
+    ///     if event == StorageEvent::CardUnmount {
+    ///         CARD_REMOVED.store(true, Ordering::Relaxed);
+    ///     }
+    /// });
+    /// ```
+    pub fn subscribe<F>(&self, f: F) -> Subscription<'_, StorageEvent, F>
+    where
+        F: FnMut(StorageEvent) + Send + 'static,
+    {
+        // SAFETY: The storage service allocates its pubsub when it starts.
+        let pubsub = unsafe { NonNull::new_unchecked(sys::storage_get_pubsub(self.as_ptr())) };
+
+        // SAFETY: The pubsub belongs to the storage service, which is never freed, and
+        // only carries `sys::StorageEvent`s.
+        unsafe { Subscription::new(pubsub, f) }
+    }
+
+    /// Puts every [`StorageEvent`] on `queue` until the returned subscription is
+    /// dropped.
+    ///
+    /// Events are dropped if the queue is full.
+    ///
+    /// ```no_run
+    /// # extern crate alloc;
+    /// # use alloc::sync::Arc;
+    /// # use flipperzero::furi::message_queue::MessageQueue;
+    /// # use flipperzero::furi::time::FuriDuration;
+    /// # use flipperzero::storage::{Storage, StorageEvent};
+    /// let storage = Storage::open();
+    /// let events = Arc::new(MessageQueue::new(8));
+    /// let _subscription = storage.subscribe_queue(events.clone());
+    ///
+    /// while let Ok(event) = events.get(FuriDuration::MAX) {
+    ///     if event == StorageEvent::CardUnmount {
+    ///         break;
+    ///     }
+    /// }
+    /// ```
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    pub fn subscribe_queue(
+        &self,
+        queue: Arc<MessageQueue<StorageEvent>>,
+    ) -> Subscription<'_, StorageEvent, Box<dyn FnMut(StorageEvent) + Send>> {
+        self.subscribe(Box::new(move |event| {
+            // The storage service can't wait for space in the queue.
+            let _ = queue.put(event, FuriDuration::ZERO);
+        }))
+    }
+}
+
+/// Whether the SD card is usable.
+///
+/// Returned by [`Storage::sd_status`].
+#[derive(Debug, Clone, Copy)]
+pub enum SdStatus {
+    /// The card is mounted.
+    Mounted,
+    /// There is no card, or it has not been mounted yet.
+    NotPresent,
+    /// The card is present, but could not be mounted, e.g. because it has no supported
+    /// filesystem.
+    Error(Error),
+}
+
+impl SdStatus {
+    /// Returns `true` if the card is mounted.
+    pub fn is_mounted(&self) -> bool {
+        matches!(self, SdStatus::Mounted)
+    }
+}
+
+/// Information about an SD card.
+///
+/// Returned by [`Storage::sd_info`].
+#[derive(Clone)]
+pub struct SdInfo {
+    info: sys::SDInfo,
+}
+
+impl SdInfo {
+    /// Name of the filesystem type, such as `FAT32` or `exFAT`.
+    pub fn fs_type(&self) -> &'static CStr {
+        unsafe { CStr::from_ptr(sys::sd_api_get_fs_type_text(self.info.fs_type)) }
+    }
+
+    /// Total capacity of the filesystem, in KiB.
+    pub fn total_kib(&self) -> u32 {
+        self.info.kb_total
+    }
+
+    /// Free space on the filesystem, in KiB.
+    pub fn free_kib(&self) -> u32 {
+        self.info.kb_free
+    }
+
+    /// Size of a filesystem cluster, in sectors.
+    pub fn cluster_size(&self) -> u16 {
+        self.info.cluster_size
+    }
+
+    /// Size of a sector, in bytes.
+    pub fn sector_size(&self) -> u16 {
+        self.info.sector_size
+    }
+
+    /// Volume label.
+    pub fn label(&self) -> &CStr {
+        c_str(&self.info.label)
+    }
+
+    /// Manufacturer ID from the card's CID register.
+    pub fn manufacturer_id(&self) -> u8 {
+        self.info.manufacturer_id
+    }
+
+    /// OEM/application ID from the card's CID register.
+    pub fn oem_id(&self) -> &CStr {
+        c_str(&self.info.oem_id)
+    }
+
+    /// Product name from the card's CID register.
+    pub fn product_name(&self) -> &CStr {
+        c_str(&self.info.product_name)
+    }
+
+    /// Product revision, as `(major, minor)`.
+    pub fn product_revision(&self) -> (u8, u8) {
+        (
+            self.info.product_revision_major,
+            self.info.product_revision_minor,
+        )
+    }
+
+    /// Product serial number.
+    pub fn serial_number(&self) -> u32 {
+        self.info.product_serial_number
+    }
+
+    /// Manufacturing date, as `(year, month)`.
+    pub fn manufacturing_date(&self) -> (u16, u8) {
+        (self.info.manufacturing_year, self.info.manufacturing_month)
+    }
+
+    /// Returns the underlying SDK struct.
+    pub fn to_sys(&self) -> sys::SDInfo {
+        self.info
+    }
+}
+
+/// Reads a nul-terminated string from a fixed-size field, which is empty if the field
+/// is not terminated.
+fn c_str(field: &[c_char]) -> &CStr {
+    // SAFETY: `c_char` and `u8` have the same layout.
+    let bytes = unsafe { core::slice::from_raw_parts(field.as_ptr().cast(), field.len()) };
+    CStr::from_bytes_until_nul(bytes).unwrap_or(c"")
+}
+
+/// An event published by the storage service.
+///
+/// Delivered by [`Storage::subscribe`] and `Storage::subscribe_queue`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageEvent {
+    /// The SD card was mounted.
+    CardMount,
+    /// The SD card was unmounted, e.g. because it was removed. Open files on it can no
+    /// longer be used, and return [`Error::NotReady`].
+    CardUnmount,
+    /// An SD card was inserted, but could not be mounted.
+    CardMountError,
+    /// A file was closed.
+    FileClose,
+    /// A directory was closed.
+    DirClose,
+}
+
+impl StorageEvent {
+    /// Converts an SDK event, returning `None` for unknown event types.
+    pub fn from_sys(event: sys::StorageEvent) -> Option<Self> {
+        Some(match event.type_ {
+            sys::StorageEventTypeCardMount => Self::CardMount,
+            sys::StorageEventTypeCardUnmount => Self::CardUnmount,
+            sys::StorageEventTypeCardMountError => Self::CardMountError,
+            sys::StorageEventTypeFileClose => Self::FileClose,
+            sys::StorageEventTypeDirClose => Self::DirClose,
+            _ => return None,
+        })
+    }
+}
+
+impl Message for StorageEvent {
+    type Raw = sys::StorageEvent;
+
+    fn from_raw(raw: &sys::StorageEvent) -> Option<Self> {
+        Self::from_sys(*raw)
+    }
+}
+
+#[flipperzero_test::tests]
+mod tests {
+    use core::sync::atomic::{AtomicU32, Ordering};
+
+    use crate::storage::{OpenOptions, Storage, StorageEvent};
+
+    #[test]
+    fn card_is_mounted() {
+        let storage = Storage::open();
+        assert!(storage.sd_status().is_mounted());
+
+        let info = storage.sd_info().unwrap();
+        assert!(info.total_kib() > 0);
+        assert!(info.free_kib() <= info.total_kib());
+        assert!(info.sector_size() > 0);
+        assert!(!info.fs_type().is_empty());
+    }
+
+    static FILE_CLOSES: AtomicU32 = AtomicU32::new(0);
+
+    #[test]
+    fn file_close_event() {
+        let storage = Storage::open();
+        let path = c"/ext/.flipperzero-rs-sd-test.txt";
+        let open = || {
+            OpenOptions::new()
+                .write(true)
+                .create_always(true)
+                .open(path)
+                .unwrap()
+        };
+
+        let subscription = storage.subscribe(|event| {
+            if event == StorageEvent::FileClose {
+                FILE_CLOSES.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+        // The storage service publishes the event before it replies to the close. Other
+        // apps may close files at the same time, so only a lower bound is checked.
+        drop(open());
+        assert!(FILE_CLOSES.load(Ordering::Relaxed) >= 1);
+
+        drop(subscription);
+        let closes = FILE_CLOSES.load(Ordering::Relaxed);
+        drop(open());
+        assert_eq!(FILE_CLOSES.load(Ordering::Relaxed), closes);
+
+        storage.remove_file(path).unwrap();
+    }
+}
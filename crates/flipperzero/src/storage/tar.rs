@@ -0,0 +1,358 @@
+//! Tar archives, as used by the firmware for update packages and resource bundles.
+//!
+//! ```no_run
+//! # use flipperzero::storage::tar::{Mode, TarArchive};
+//! let mut archive = TarArchive::open(c"/ext/export.tar", Mode::Write).unwrap();
+//! archive
+//!     .add_dir(c"/ext/apps_data/example", c"example")
+//!     .unwrap();
+//! archive.finish().unwrap();
+//!
+//! let mut archive = TarArchive::open(c"/ext/export.tar", Mode::Read).unwrap();
+//! archive
+//!     .unpack_to_with(c"/ext/restored", |entry, progress| {
+//!         // Skip the cache, and report progress for everything else.
+//!         let _ = (progress.processed, progress.total);
+//!         !entry.name().to_bytes().starts_with(b"example/cache/")
+//!     })
+//!     .unwrap();
+//! ```
+
+use core::ffi::{c_char, c_void, CStr};
+use core::ptr::{self, NonNull};
+
+use flipperzero_sys as sys;
+
+use super::Storage;
+use crate::io::Error;
+
+/// How a [`TarArchive`] is opened.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// Read an uncompressed archive (`.tar`).
+    Read,
+    /// Create a new uncompressed archive, replacing any existing file.
+    Write,
+    /// Read a heatshrink-compressed archive (`.tar.hs`), as used by update packages.
+    ReadHeatshrink,
+}
+
+impl Mode {
+    /// Guesses the mode for reading the archive at `path` from its extension.
+    pub fn for_path(path: &CStr) -> Self {
+        match unsafe { sys::tar_archive_get_mode_for_path(path.as_ptr()) } {
+            sys::TarOpenModeReadHeatshrink => Mode::ReadHeatshrink,
+            sys::TarOpenModeWrite => Mode::Write,
+            _ => Mode::Read,
+        }
+    }
+
+    fn to_sys(self) -> sys::TarOpenMode {
+        match self {
+            Mode::Read => sys::TarOpenModeRead,
+            Mode::Write => sys::TarOpenModeWrite,
+            Mode::ReadHeatshrink => sys::TarOpenModeReadHeatshrink,
+        }
+    }
+
+    fn is_read(self) -> bool {
+        self != Mode::Write
+    }
+}
+
+/// A file or directory in a [`TarArchive`].
+#[derive(Debug, Clone, Copy)]
+pub struct Entry<'a> {
+    name: &'a CStr,
+    is_dir: bool,
+}
+
+impl Entry<'_> {
+    /// Path of the entry inside the archive.
+    pub fn name(&self) -> &CStr {
+        self.name
+    }
+
+    /// Returns `true` if this is a directory.
+    pub fn is_dir(&self) -> bool {
+        self.is_dir
+    }
+}
+
+/// How far [`TarArchive::unpack_to_with`] has got.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    /// Number of entries before the current one.
+    pub processed: usize,
+    /// Number of entries in the archive.
+    pub total: usize,
+}
+
+/// A tar archive on the SD card, opened for reading or writing.
+///
+/// Most operations return [`Error::Internal`] on failure, since the SDK doesn't report
+/// the cause.
+pub struct TarArchive {
+    raw: NonNull<sys::TarArchive>,
+    mode: Mode,
+    storage: Storage,
+}
+
+impl TarArchive {
+    /// Opens the archive at `path`.
+    ///
+    /// Archives opened with [`Mode::Write`] must be completed with
+    /// [`TarArchive::finish`].
+    pub fn open(path: &CStr, mode: Mode) -> Result<Self, Error> {
+        let storage = Storage::open();
+        let archive = Self {
+            // SAFETY: Alloc always returns a valid non-null pointer or `furi_panic`s.
+            raw: unsafe { NonNull::new_unchecked(sys::tar_archive_alloc(storage.as_ptr())) },
+            mode,
+            storage,
+        };
+
+        // `Drop` frees the archive if opening fails.
+        check(unsafe { sys::tar_archive_open(archive.as_ptr(), path.as_ptr(), mode.to_sys()) })?;
+        Ok(archive)
+    }
+
+    /// Obtain raw Furi tar archive handle.
+    ///
+    /// This pointer must not be `free`d or otherwise invalidated. It must not be
+    /// referenced after [`TarArchive`] has been dropped.
+    pub fn as_ptr(&self) -> *mut sys::TarArchive {
+        self.raw.as_ptr()
+    }
+
+    /// The mode this archive was opened with.
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    fn ensure_read(&self) -> Result<(), Error> {
+        if self.mode.is_read() {
+            Ok(())
+        } else {
+            Err(Error::InvalidParameter)
+        }
+    }
+
+    fn ensure_write(&self) -> Result<(), Error> {
+        if self.mode.is_read() {
+            Err(Error::InvalidParameter)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Counts the entries in the archive, by reading through it.
+    pub fn entries_count(&mut self) -> Result<usize, Error> {
+        self.ensure_read()?;
+        let count = unsafe { sys::tar_archive_get_entries_count(self.as_ptr()) };
+        count.try_into().map_err(|_| Error::Internal)
+    }
+
+    /// Calls `f` with each entry in the archive, in order.
+    pub fn for_each_entry(&mut self, mut f: impl FnMut(&Entry<'_>)) -> Result<(), Error> {
+        self.ensure_read()?;
+        // Every entry is skipped, so nothing is written to the destination.
+        self.unpack_with_callback(c"/ext", &mut |entry: &Entry<'_>| {
+            f(entry);
+            false
+        })
+    }
+
+    /// Extracts every entry into the directory `destination`, creating it if needed.
+    pub fn unpack_to(&mut self, destination: &CStr) -> Result<(), Error> {
+        self.ensure_read()?;
+        self.storage.create_dir_all(destination)?;
+        check(unsafe { sys::tar_archive_unpack_to(self.as_ptr(), destination.as_ptr(), None) })
+    }
+
+    /// Extracts selected entries into the directory `destination`, creating it if
+    /// needed.
+    ///
+    /// `f` is called before each entry with the progress so far, and returns whether
+    /// to extract that entry. The total in [`Progress`] comes from
+    /// [`entries_count`](Self::entries_count), so the archive is read through once
+    /// more before extraction starts; use [`unpack_to`](Self::unpack_to) to avoid
+    /// that for large archives.
+    pub fn unpack_to_with(
+        &mut self,
+        destination: &CStr,
+        mut f: impl FnMut(&Entry<'_>, Progress) -> bool,
+    ) -> Result<(), Error> {
+        let total = self.entries_count()?;
+        self.storage.create_dir_all(destination)?;
+        let mut processed = 0;
+        self.unpack_with_callback(destination, &mut |entry: &Entry<'_>| {
+            let extract = f(entry, Progress { processed, total });
+            processed += 1;
+            extract
+        })
+    }
+
+    /// Extracts the single file `name` to the file path `destination`.
+    pub fn unpack_file(&mut self, name: &CStr, destination: &CStr) -> Result<(), Error> {
+        self.ensure_read()?;
+        check(unsafe {
+            sys::tar_archive_unpack_file(self.as_ptr(), name.as_ptr(), destination.as_ptr())
+        })
+    }
+
+    fn unpack_with_callback<F>(&mut self, destination: &CStr, f: &mut F) -> Result<(), Error>
+    where
+        F: FnMut(&Entry<'_>) -> bool,
+    {
+        unsafe {
+            sys::tar_archive_set_file_callback(
+                self.as_ptr(),
+                Some(file_callback::<F>),
+                ptr::from_mut(f).cast(),
+            );
+        }
+        let result =
+            unsafe { sys::tar_archive_unpack_to(self.as_ptr(), destination.as_ptr(), None) };
+        // `f` doesn't outlive this call.
+        unsafe { sys::tar_archive_set_file_callback(self.as_ptr(), None, ptr::null_mut()) };
+
+        check(result)
+    }
+
+    /// Adds the file at `path` to the archive as `name`.
+    pub fn add_file(&mut self, path: &CStr, name: &CStr) -> Result<(), Error> {
+        self.ensure_write()?;
+        let size = self.storage.metadata(path)?.len();
+        let size = size.try_into().map_err(|_| Error::InvalidParameter)?;
+        check(unsafe {
+            sys::tar_archive_add_file(self.as_ptr(), path.as_ptr(), name.as_ptr(), size)
+        })
+    }
+
+    /// Adds the directory at `path` and everything in it to the archive, under the
+    /// directory `prefix`.
+    pub fn add_dir(&mut self, path: &CStr, prefix: &CStr) -> Result<(), Error> {
+        self.ensure_write()?;
+        check(unsafe { sys::tar_archive_add_dir(self.as_ptr(), path.as_ptr(), prefix.as_ptr()) })
+    }
+
+    /// Adds an empty directory `name` to the archive.
+    pub fn add_empty_dir(&mut self, name: &CStr) -> Result<(), Error> {
+        self.ensure_write()?;
+        check(unsafe { sys::tar_archive_dir_add_element(self.as_ptr(), name.as_ptr()) })
+    }
+
+    /// Adds a file `name` containing `data` to the archive.
+    pub fn add_data(&mut self, name: &CStr, data: &[u8]) -> Result<(), Error> {
+        self.ensure_write()?;
+        let len = data.len().try_into().map_err(|_| Error::InvalidParameter)?;
+        check(unsafe {
+            sys::tar_archive_store_data(self.as_ptr(), name.as_ptr(), data.as_ptr(), len)
+        })
+    }
+
+    /// Writes the end-of-archive marker and closes an archive opened with
+    /// [`Mode::Write`].
+    ///
+    /// Without this, the archive is incomplete.
+    pub fn finish(self) -> Result<(), Error> {
+        self.ensure_write()?;
+        check(unsafe { sys::tar_archive_finalize(self.as_ptr()) })
+    }
+}
+
+impl Drop for TarArchive {
+    fn drop(&mut self) {
+        unsafe { sys::tar_archive_free(self.as_ptr()) };
+    }
+}
+
+fn check(ok: bool) -> Result<(), Error> {
+    if ok {
+        Ok(())
+    } else {
+        Err(Error::Internal)
+    }
+}
+
+unsafe extern "C" fn file_callback<F>(
+    name: *const c_char,
+    is_dir: bool,
+    context: *mut c_void,
+) -> bool
+where
+    F: FnMut(&Entry<'_>) -> bool,
+{
+    let f = unsafe { &mut *context.cast::<F>() };
+    let name = unsafe { CStr::from_ptr(name) };
+    f(&Entry { name, is_dir })
+}
+
+#[flipperzero_test::tests]
+mod tests {
+    use super::{Mode, TarArchive};
+    use crate::io::Read;
+    use crate::storage::{OpenOptions, Storage};
+
+    #[test]
+    fn pack_and_unpack() {
+        let storage = Storage::open();
+        let archive_path = c"/ext/.flipperzero-rs-tar-test.tar";
+        let out = c"/ext/.flipperzero-rs-tar-test";
+
+        let mut archive = TarArchive::open(archive_path, Mode::Write).unwrap();
+        archive.add_empty_dir(c"empty").unwrap();
+        archive.add_data(c"a.txt", b"first").unwrap();
+        archive.add_data(c"b.txt", b"second").unwrap();
+        assert!(archive.entries_count().is_err());
+        archive.finish().unwrap();
+
+        let mut archive = TarArchive::open(archive_path, Mode::for_path(archive_path)).unwrap();
+        assert_eq!(archive.mode(), Mode::Read);
+        assert_eq!(archive.entries_count().unwrap(), 3);
+
+        let mut dirs = 0;
+        let mut files = 0;
+        archive
+            .for_each_entry(|entry| {
+                if entry.is_dir() {
+                    dirs += 1;
+                } else {
+                    files += 1;
+                }
+            })
+            .unwrap();
+        assert_eq!((dirs, files), (1, 2));
+
+        // Unpacking creates the destination directory.
+        let _ = storage.remove_dir_all(out);
+        assert!(!storage.exists(out));
+
+        let mut seen = 0;
+        archive
+            .unpack_to_with(out, |entry, progress| {
+                assert_eq!(progress.processed, seen);
+                assert_eq!(progress.total, 3);
+                seen += 1;
+                entry.name() == c"b.txt"
+            })
+            .unwrap();
+        assert_eq!(seen, 3);
+        assert!(!storage.exists(c"/ext/.flipperzero-rs-tar-test/a.txt"));
+
+        let mut buf = [0; 6];
+        OpenOptions::new()
+            .read(true)
+            .open_existing(true)
+            .open(c"/ext/.flipperzero-rs-tar-test/b.txt")
+            .unwrap()
+            .read_exact(&mut buf)
+            .unwrap();
+        assert_eq!(&buf, b"second");
+
+        drop(archive);
+        storage.remove_dir_all(out).unwrap();
+        storage.remove_file(archive_path).unwrap();
+    }
+}
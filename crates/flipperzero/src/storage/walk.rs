@@ -0,0 +1,356 @@
+use core::ffi::{c_char, c_void, CStr};
+use core::ptr::NonNull;
+
+use flipperzero_sys::{self as sys, furi::FuriBox};
+
+use super::{DirEntry, Metadata, Storage};
+use crate::furi::string::FuriString;
+use crate::io::Error;
+
+/// Default filter type of [`WalkDir`], used when no filter has been set.
+pub type NoFilter = fn(&CStr, &Metadata) -> bool;
+
+/// Walk over all entries below a directory, built on the firmware's `dir_walk`.
+///
+/// The options are set before iterating, which starts with [`WalkDir::into_iter`]
+/// (or a `for` loop). Only the current entry, one open directory and the position within each parent
+/// directory are held at a time, so memory use depends on the depth of the tree rather
+/// than its size. Stop iterating (or drop the `WalkDir`) to end the walk early.
+///
+/// ```no_run
+/// # use flipperzero::storage::{Storage, WalkDir};
+/// let storage = Storage::open();
+/// for entry in WalkDir::new(&storage, c"/ext/subghz").extension(c"sub") {
+///     match entry {
+///         Ok(entry) => { /* entry.path(), entry.size(), ... */ }
+///         Err(_) => { /* the walk continues with the next entry */ }
+///     }
+/// }
+/// ```
+///
+/// An error reading one entry is returned as an `Err` item, and the walk then carries
+/// on. If reading fails twice in a row, the walk ends after the second error.
+pub struct WalkDir<'a, F = NoFilter> {
+    storage: Storage,
+    path: &'a CStr,
+    recursive: bool,
+    extension: Option<&'a CStr>,
+    filter: Option<F>,
+}
+
+/// Iterator over the entries of a [`WalkDir`].
+pub struct WalkDirIter<'a, F = NoFilter> {
+    options: WalkDir<'a, F>,
+    state: State<'a, F>,
+}
+
+enum State<'a, F> {
+    Pending,
+    Open(Walk<'a, F>),
+    Done,
+}
+
+/// An open `dir_walk`, together with the filter context it points to.
+struct Walk<'a, F> {
+    raw: NonNull<sys::DirWalk>,
+    context: FuriBox<Filter<'a, F>>,
+    failed: bool,
+}
+
+struct Filter<'a, F> {
+    extension: Option<&'a [u8]>,
+    predicate: Option<F>,
+}
+
+impl<'a> WalkDir<'a> {
+    /// Walks the tree below `path`, recursively and without any filter.
+    ///
+    /// Nothing is opened until the first call to [`Iterator::next`]. If `path` cannot
+    /// be opened, that error is the first and only item.
+    pub fn new(storage: &Storage, path: &'a CStr) -> Self {
+        Self {
+            storage: storage.clone(),
+            path,
+            recursive: true,
+            extension: None,
+            filter: None,
+        }
+    }
+}
+
+impl<'a, F> WalkDir<'a, F>
+where
+    F: FnMut(&CStr, &Metadata) -> bool,
+{
+    /// Whether to descend into subdirectories (`true` by default).
+    pub fn recursive(self, recursive: bool) -> Self {
+        Self { recursive, ..self }
+    }
+
+    /// Only returns files with the given extension, e.g. `c"sub"` or `c".sub"`.
+    ///
+    /// The comparison ignores ASCII case, like the SD card's filesystem. Directories are
+    /// not returned, but are still descended into when walking recursively.
+    pub fn extension(self, extension: &'a CStr) -> Self {
+        Self {
+            extension: Some(extension),
+            ..self
+        }
+    }
+
+    /// Only returns entries for which `filter` returns `true`.
+    ///
+    /// `filter` is called with the name of each entry (without its directory) and its
+    /// metadata, which does not include the modification time. Directories that are
+    /// filtered out are still descended into when walking recursively.
+    ///
+    /// When combined with [`WalkDir::extension`], `filter` is only called for entries
+    /// with a matching extension.
+    pub fn filter<G>(self, filter: G) -> WalkDir<'a, G>
+    where
+        G: FnMut(&CStr, &Metadata) -> bool,
+    {
+        WalkDir {
+            storage: self.storage,
+            path: self.path,
+            recursive: self.recursive,
+            extension: self.extension,
+            filter: Some(filter),
+        }
+    }
+}
+
+impl<'a, F> IntoIterator for WalkDir<'a, F>
+where
+    F: FnMut(&CStr, &Metadata) -> bool,
+{
+    type Item = Result<DirEntry, Error>;
+    type IntoIter = WalkDirIter<'a, F>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        WalkDirIter {
+            options: self,
+            state: State::Pending,
+        }
+    }
+}
+
+impl<'a, F> WalkDirIter<'a, F>
+where
+    F: FnMut(&CStr, &Metadata) -> bool,
+{
+    fn open(&mut self) -> Result<Walk<'a, F>, Error> {
+        let options = &mut self.options;
+        let extension = options.extension.map(|ext| {
+            let ext = ext.to_bytes();
+            ext.strip_prefix(b".").unwrap_or(ext)
+        });
+        let context = FuriBox::new(Filter {
+            extension,
+            predicate: options.filter.take(),
+        });
+
+        let mut walk = Walk {
+            // SAFETY: Alloc always returns a valid non-null pointer or `furi_panic`s.
+            raw: unsafe { NonNull::new_unchecked(sys::dir_walk_alloc(options.storage.as_ptr())) },
+            context,
+            failed: false,
+        };
+
+        unsafe {
+            sys::dir_walk_set_recursive(walk.raw.as_ptr(), options.recursive);
+            if walk.context.extension.is_some() || walk.context.predicate.is_some() {
+                sys::dir_walk_set_filter_cb(
+                    walk.raw.as_ptr(),
+                    Some(filter_callback::<F>),
+                    FuriBox::as_mut_ptr(&mut walk.context).cast(),
+                );
+            }
+        }
+
+        if unsafe { sys::dir_walk_open(walk.raw.as_ptr(), options.path.as_ptr()) } {
+            Ok(walk)
+        } else {
+            // `Drop` closes the walk, which is required even if opening failed.
+            Err(walk.error().unwrap_or(Error::Internal))
+        }
+    }
+}
+
+impl<F> Iterator for WalkDirIter<'_, F>
+where
+    F: FnMut(&CStr, &Metadata) -> bool,
+{
+    type Item = Result<DirEntry, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let State::Pending = self.state {
+            match self.open() {
+                Ok(walk) => self.state = State::Open(walk),
+                Err(err) => {
+                    self.state = State::Done;
+                    return Some(Err(err));
+                }
+            }
+        }
+
+        let State::Open(walk) = &mut self.state else {
+            return None;
+        };
+
+        let mut path = FuriString::new();
+        let mut info = sys::FileInfo { flags: 0, size: 0 };
+        let result = unsafe { sys::dir_walk_read(walk.raw.as_ptr(), path.as_mut_ptr(), &mut info) };
+
+        match result {
+            sys::DirWalkOK => {
+                walk.failed = false;
+                Some(Ok(DirEntry::new(&self.options.storage, path, info)))
+            }
+            sys::DirWalkError if !walk.failed => {
+                walk.failed = true;
+                Some(Err(walk.error().unwrap_or(Error::Internal)))
+            }
+            sys::DirWalkError => {
+                let err = walk.error().unwrap_or(Error::Internal);
+                self.state = State::Done;
+                Some(Err(err))
+            }
+            _ => {
+                self.state = State::Done;
+                None
+            }
+        }
+    }
+}
+
+impl<F> Walk<'_, F> {
+    fn error(&self) -> Option<Error> {
+        Error::from_sys(unsafe { sys::dir_walk_get_error(self.raw.as_ptr()) })
+    }
+}
+
+impl<F> Drop for Walk<'_, F> {
+    fn drop(&mut self) {
+        unsafe {
+            sys::dir_walk_close(self.raw.as_ptr());
+            sys::dir_walk_free(self.raw.as_ptr());
+        }
+    }
+}
+
+unsafe extern "C" fn filter_callback<F>(
+    name: *const c_char,
+    info: *mut sys::FileInfo,
+    context: *mut c_void,
+) -> bool
+where
+    F: FnMut(&CStr, &Metadata) -> bool,
+{
+    let filter = unsafe { &mut *context.cast::<Filter<F>>() };
+    let name = unsafe { CStr::from_ptr(name) };
+    let metadata = Metadata::new(unsafe { *info }, None);
+
+    if let Some(extension) = filter.extension {
+        let name = name.to_bytes();
+        let matches = !metadata.is_dir()
+            && name
+                .iter()
+                .rposition(|&b| b == b'.')
+                .is_some_and(|i| name[i + 1..].eq_ignore_ascii_case(extension));
+        if !matches {
+            return false;
+        }
+    }
+
+    match &mut filter.predicate {
+        Some(predicate) => predicate(name, &metadata),
+        None => true,
+    }
+}
+
+#[flipperzero_test::tests]
+mod tests {
+    use core::sync::atomic::{AtomicBool, Ordering};
+
+    use super::WalkDir;
+    use crate::io::Write;
+    use crate::storage::{OpenOptions, Storage};
+
+    #[test]
+    fn walk() {
+        let storage = Storage::open();
+        let root = c"/ext/.flipperzero-rs-walk-test";
+
+        storage
+            .create_dir_all(c"/ext/.flipperzero-rs-walk-test/sub")
+            .unwrap();
+        for (path, contents) in [
+            (c"/ext/.flipperzero-rs-walk-test/a.sub", &b"a"[..]),
+            (c"/ext/.flipperzero-rs-walk-test/b.txt", b"bb"),
+            (c"/ext/.flipperzero-rs-walk-test/sub/c.SUB", b""),
+            (c"/ext/.flipperzero-rs-walk-test/sub/d.sub", b"dddd"),
+        ] {
+            OpenOptions::new()
+                .write(true)
+                .create_always(true)
+                .open(path)
+                .unwrap()
+                .write_all(contents)
+                .unwrap();
+        }
+
+        // Four files and the subdirectory.
+        assert_eq!(
+            WalkDir::new(&storage, root).into_iter().flatten().count(),
+            5
+        );
+        assert_eq!(
+            WalkDir::new(&storage, root)
+                .recursive(false)
+                .into_iter()
+                .flatten()
+                .count(),
+            3
+        );
+
+        let subs = WalkDir::new(&storage, root).extension(c".sub");
+        assert_eq!(
+            subs.into_iter().flatten().filter(|e| !e.is_dir()).count(),
+            3
+        );
+
+        let mut found = WalkDir::new(&storage, root)
+            .extension(c"sub")
+            .filter(|_, metadata| !metadata.is_empty())
+            .into_iter()
+            .map(|entry| entry.unwrap());
+        let first = found.next().unwrap();
+        assert!(first.path().starts_with(root));
+        assert!(first.name() == "a.sub" || first.name() == "d.sub");
+        // Stopping early closes the walk.
+        drop(found);
+
+        // The filter is dropped with the walk.
+        static DROPPED: AtomicBool = AtomicBool::new(false);
+        struct Guard;
+        impl Drop for Guard {
+            fn drop(&mut self) {
+                DROPPED.store(true, Ordering::Relaxed);
+            }
+        }
+        let guard = Guard;
+        let walk = WalkDir::new(&storage, root).filter(move |_, _| {
+            let _ = &guard;
+            true
+        });
+        assert_eq!(walk.into_iter().flatten().count(), 5);
+        assert!(DROPPED.load(Ordering::Relaxed));
+
+        let mut missing = WalkDir::new(&storage, c"/ext/.flipperzero-rs-missing").into_iter();
+        assert!(matches!(missing.next(), Some(Err(_))));
+        assert!(missing.next().is_none());
+
+        storage.remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,462 @@
+//! Streams over files and strings.
+//!
+//! [`Stream`] wraps the SDK's `Stream`, which is used by Flipper Format and many of the
+//! firmware's parsers. Besides [`Read`], [`Write`] and [`Seek`], it provides fast line
+//! reading and in-place editing: [`Stream::insert`], [`Stream::delete`] and
+//! [`Stream::delete_and_insert`] shift the rest of the stream, which [`File`] cannot.
+//!
+//! [`File`]: crate::storage::File
+
+use core::ffi::{c_char, c_void, CStr};
+use core::ptr::NonNull;
+
+use flipperzero_sys as sys;
+
+use crate::furi::string::FuriString;
+use crate::io::{Error, Read, Seek, SeekFrom, Write};
+use crate::storage::{OpenOptions, Storage};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Backing {
+    File,
+    BufferedFile,
+    String,
+}
+
+/// Direction to search in with [`Stream::seek_to_char`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Forward,
+    Backward,
+}
+
+impl Direction {
+    fn to_sys(self) -> sys::StreamDirection {
+        match self {
+            Self::Forward => sys::StreamDirectionForward,
+            Self::Backward => sys::StreamDirectionBackward,
+        }
+    }
+}
+
+/// A readable, writable and seekable stream, backed by a file or by memory.
+///
+/// ```no_run
+/// # use flipperzero::io::Write;
+/// # use flipperzero::storage::{OpenOptions, Storage};
+/// # use flipperzero::stream::Stream;
+/// let storage = Storage::open();
+/// let options = OpenOptions::new().read(true).write(true).open_existing(true);
+/// let mut stream = Stream::buffered_file(&storage, c"/ext/notes.txt", options).unwrap();
+///
+/// // Insert a title line, moving the existing contents down.
+/// stream.insert(b"# Notes\n").unwrap();
+/// stream.flush().unwrap();
+/// ```
+pub struct Stream {
+    raw: NonNull<sys::Stream>,
+    backing: Backing,
+    _storage: Option<Storage>,
+}
+
+impl Stream {
+    /// Creates an empty memory-backed stream (`string_stream`).
+    pub fn string() -> Self {
+        // SAFETY: Alloc always returns a valid non-null pointer or `furi_panic`s.
+        let raw = unsafe { NonNull::new_unchecked(sys::string_stream_alloc()) };
+
+        Self {
+            raw,
+            backing: Backing::String,
+            _storage: None,
+        }
+    }
+
+    /// Opens the file at `path` as an unbuffered stream (`file_stream`).
+    pub fn file(storage: &Storage, path: &CStr, options: OpenOptions) -> Result<Self, Error> {
+        // SAFETY: Alloc always returns a valid non-null pointer or `furi_panic`s.
+        let raw = unsafe { NonNull::new_unchecked(sys::file_stream_alloc(storage.as_ptr())) };
+        Self::open(raw, Backing::File, storage, path, options)
+    }
+
+    /// Opens the file at `path` as a buffered stream (`buffered_file_stream`).
+    ///
+    /// Reads and writes go through a cache, which makes many small operations much
+    /// faster. Writes are only guaranteed to reach the file after [`Write::flush`], or
+    /// when the stream is dropped.
+    pub fn buffered_file(
+        storage: &Storage,
+        path: &CStr,
+        options: OpenOptions,
+    ) -> Result<Self, Error> {
+        // SAFETY: Alloc always returns a valid non-null pointer or `furi_panic`s.
+        let raw =
+            unsafe { NonNull::new_unchecked(sys::buffered_file_stream_alloc(storage.as_ptr())) };
+        Self::open(raw, Backing::BufferedFile, storage, path, options)
+    }
+
+    fn open(
+        raw: NonNull<sys::Stream>,
+        backing: Backing,
+        storage: &Storage,
+        path: &CStr,
+        options: OpenOptions,
+    ) -> Result<Self, Error> {
+        let stream = Self {
+            raw,
+            backing,
+            _storage: Some(storage.clone()),
+        };
+
+        let (access_mode, open_mode) = options.to_sys();
+        let opened = unsafe {
+            match backing {
+                Backing::File => {
+                    sys::file_stream_open(raw.as_ptr(), path.as_ptr(), access_mode, open_mode)
+                }
+                Backing::BufferedFile => sys::buffered_file_stream_open(
+                    raw.as_ptr(),
+                    path.as_ptr(),
+                    access_mode,
+                    open_mode,
+                ),
+                Backing::String => unreachable!(),
+            }
+        };
+
+        if opened {
+            Ok(stream)
+        } else {
+            // The file must be closed even if opening failed, which is handled by `Drop`.
+            Err(stream.last_error())
+        }
+    }
+
+    /// Obtain raw stream.
+    ///
+    /// This pointer must not be `free`d or otherwise invalidated.
+    /// It must not be referenced after [`Stream`] has been dropped.
+    #[inline]
+    pub fn as_ptr(&self) -> *mut sys::Stream {
+        self.raw.as_ptr()
+    }
+
+    /// Size of the stream, in bytes.
+    pub fn len(&self) -> usize {
+        unsafe { sys::stream_size(self.as_ptr()) }
+    }
+
+    /// Returns `true` if the stream is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if the read/write position is at the end of the stream.
+    pub fn eof(&self) -> bool {
+        unsafe { sys::stream_eof(self.as_ptr()) }
+    }
+
+    /// Empties the stream.
+    pub fn clear(&mut self) {
+        unsafe { sys::stream_clean(self.as_ptr()) }
+    }
+
+    /// Reads the next line into `line`, replacing its contents.
+    ///
+    /// Both `\n` and `\r\n` line endings are accepted, and the line ending is kept.
+    /// Returns `false` at the end of the stream.
+    pub fn read_line(&mut self, line: &mut FuriString) -> bool {
+        unsafe { sys::stream_read_line(self.as_ptr(), line.as_mut_ptr()) }
+    }
+
+    /// Moves the read/write position to the next occurrence of `c` in `direction`.
+    ///
+    /// The position is left at `c`. Returns `false` if `c` was not found.
+    pub fn seek_to_char(&mut self, c: u8, direction: Direction) -> bool {
+        unsafe { sys::stream_seek_to_char(self.as_ptr(), c as c_char, direction.to_sys()) }
+    }
+
+    /// Inserts `data` at the read/write position, moving the rest of the stream forward.
+    ///
+    /// The position is left after the inserted data.
+    pub fn insert(&mut self, data: &[u8]) -> Result<(), Error> {
+        if unsafe { sys::stream_insert(self.as_ptr(), data.as_ptr(), data.len()) } {
+            Ok(())
+        } else {
+            Err(self.last_error())
+        }
+    }
+
+    /// Removes `len` bytes at the read/write position, moving the rest of the stream
+    /// back.
+    ///
+    /// If `len` goes past the end, everything after the position is removed.
+    pub fn delete(&mut self, len: usize) -> Result<(), Error> {
+        if unsafe { sys::stream_delete(self.as_ptr(), len) } {
+            Ok(())
+        } else {
+            Err(self.last_error())
+        }
+    }
+
+    /// Replaces `len` bytes at the read/write position with `data`, moving the rest of
+    /// the stream as needed.
+    ///
+    /// This is faster than [`Stream::delete`] followed by [`Stream::insert`], since the
+    /// rest of the stream is only moved once. The position is left after the inserted
+    /// data.
+    pub fn delete_and_insert(&mut self, len: usize, data: &[u8]) -> Result<(), Error> {
+        unsafe extern "C" fn write_data(stream: *mut sys::Stream, context: *const c_void) -> bool {
+            let data = unsafe { &*context.cast::<&[u8]>() };
+            unsafe { sys::stream_write(stream, data.as_ptr(), data.len()) == data.len() }
+        }
+
+        let context: *const &[u8] = &data;
+        if unsafe {
+            sys::stream_delete_and_insert(
+                self.as_ptr(),
+                len,
+                Some(write_data),
+                context.cast::<c_void>(),
+            )
+        } {
+            Ok(())
+        } else {
+            Err(self.last_error())
+        }
+    }
+
+    /// Copies everything from the read/write position to the end into `to`, at its
+    /// read/write position.
+    ///
+    /// Returns the number of bytes copied.
+    pub fn copy_to(&mut self, to: &mut Stream) -> usize {
+        let len = self.len().saturating_sub(self.tell());
+        unsafe { sys::stream_copy(self.as_ptr(), to.as_ptr(), len) }
+    }
+
+    fn tell(&self) -> usize {
+        unsafe { sys::stream_tell(self.as_ptr()) }
+    }
+
+    fn error(&self) -> Option<Error> {
+        let err = unsafe {
+            match self.backing {
+                Backing::File => sys::file_stream_get_error(self.as_ptr()),
+                Backing::BufferedFile => sys::buffered_file_stream_get_error(self.as_ptr()),
+                Backing::String => sys::FSE_OK,
+            }
+        };
+        Error::from_sys(err)
+    }
+
+    /// The error of the last failed operation.
+    ///
+    /// String streams don't report errors, so this falls back to [`Error::Internal`].
+    fn last_error(&self) -> Error {
+        self.error().unwrap_or(Error::Internal)
+    }
+}
+
+impl Drop for Stream {
+    fn drop(&mut self) {
+        unsafe {
+            // Buffered file streams only write out their cache when closed.
+            match self.backing {
+                Backing::File => {
+                    sys::file_stream_close(self.as_ptr());
+                }
+                Backing::BufferedFile => {
+                    sys::buffered_file_stream_close(self.as_ptr());
+                }
+                Backing::String => {}
+            }
+            sys::stream_free(self.as_ptr());
+        }
+    }
+}
+
+impl Read for Stream {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        let n = unsafe { sys::stream_read(self.as_ptr(), buf.as_mut_ptr(), buf.len()) };
+        match self.error() {
+            Some(err) if n == 0 => Err(err),
+            _ => Ok(n),
+        }
+    }
+}
+
+impl Write for Stream {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        let n = unsafe { sys::stream_write(self.as_ptr(), buf.as_ptr(), buf.len()) };
+        match self.error() {
+            Some(err) if n == 0 && !buf.is_empty() => Err(err),
+            _ => Ok(n),
+        }
+    }
+
+    fn flush(&mut self) -> Result<(), Error> {
+        if self.backing != Backing::BufferedFile
+            || unsafe { sys::buffered_file_stream_sync(self.as_ptr()) }
+        {
+            Ok(())
+        } else {
+            Err(self.last_error())
+        }
+    }
+}
+
+impl Seek for Stream {
+    fn seek(&mut self, pos: SeekFrom) -> Result<usize, Error> {
+        let (offset, offset_type) = match pos {
+            SeekFrom::Start(n) => (
+                i32::try_from(n).map_err(|_| Error::InvalidParameter)?,
+                sys::StreamOffsetFromStart,
+            ),
+            SeekFrom::End(n) => (
+                i32::try_from(n).map_err(|_| Error::InvalidParameter)?,
+                sys::StreamOffsetFromEnd,
+            ),
+            SeekFrom::Current(n) => (
+                i32::try_from(n).map_err(|_| Error::InvalidParameter)?,
+                sys::StreamOffsetFromCurrent,
+            ),
+        };
+
+        if unsafe { sys::stream_seek(self.as_ptr(), offset, offset_type) } {
+            Ok(self.tell())
+        } else {
+            Err(self.error().unwrap_or(Error::InvalidParameter))
+        }
+    }
+
+    fn rewind(&mut self) -> Result<(), Error> {
+        if unsafe { sys::stream_rewind(self.as_ptr()) } {
+            Ok(())
+        } else {
+            Err(self.last_error())
+        }
+    }
+
+    fn stream_len(&mut self) -> Result<usize, Error> {
+        Ok(self.len())
+    }
+
+    fn stream_position(&mut self) -> Result<usize, Error> {
+        Ok(self.tell())
+    }
+}
+
+#[flipperzero_test::tests]
+mod tests {
+    use super::{Direction, Stream};
+    use crate::furi::string::FuriString;
+    use crate::io::{Read, Seek, SeekFrom, Write};
+    use crate::storage::{OpenOptions, Storage};
+
+    fn contents(stream: &mut Stream) -> FuriString {
+        stream.rewind().unwrap();
+        let mut buf = [0u8; 64];
+        let n = stream.read(&mut buf).unwrap();
+        let mut s = FuriString::new();
+        s.push_str(core::str::from_utf8(&buf[..n]).unwrap());
+        s
+    }
+
+    #[test]
+    fn string_stream() {
+        let mut stream = Stream::string();
+        assert!(stream.is_empty());
+
+        stream.write_all(b"one\r\ntwo\nthree").unwrap();
+        assert_eq!(stream.len(), 14);
+        assert!(stream.eof());
+
+        stream.rewind().unwrap();
+        let mut line = FuriString::new();
+        assert!(stream.read_line(&mut line));
+        assert_eq!(line, "one\r\n");
+        assert!(stream.read_line(&mut line));
+        assert_eq!(line, "two\n");
+
+        assert!(stream.seek_to_char(b'o', Direction::Backward));
+        let mut c = [0];
+        stream.read_exact(&mut c).unwrap();
+        assert_eq!(&c, b"o");
+        assert!(stream.seek_to_char(b'e', Direction::Forward));
+        stream.read_exact(&mut c).unwrap();
+        assert_eq!(&c, b"e");
+        assert!(!stream.seek_to_char(b'x', Direction::Forward));
+
+        assert_eq!(stream.seek(SeekFrom::End(-5)).unwrap(), 9);
+        let mut word = [0; 5];
+        stream.read_exact(&mut word).unwrap();
+        assert_eq!(&word, b"three");
+    }
+
+    #[test]
+    fn edit_in_place() {
+        let mut stream = Stream::string();
+        stream.write_all(b"hello world").unwrap();
+
+        stream.seek(SeekFrom::Start(5)).unwrap();
+        stream.insert(b",").unwrap();
+        assert_eq!(contents(&mut stream), "hello, world");
+
+        stream.seek(SeekFrom::Start(7)).unwrap();
+        stream.delete_and_insert(5, b"flipper").unwrap();
+        assert_eq!(stream.stream_position().unwrap(), 14);
+        assert_eq!(contents(&mut stream), "hello, flipper");
+
+        stream.seek(SeekFrom::Start(5)).unwrap();
+        stream.delete(100).unwrap();
+        assert_eq!(contents(&mut stream), "hello");
+
+        stream.clear();
+        assert!(stream.is_empty());
+    }
+
+    #[test]
+    fn file_streams() {
+        let storage = Storage::open();
+        let path = c"/ext/.flipperzero-rs-stream-test";
+        let options = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create_always(true);
+
+        {
+            let mut stream = Stream::file(&storage, path, options).unwrap();
+            stream.write_all(b"line 1\nline 3\n").unwrap();
+            stream.seek(SeekFrom::Start(7)).unwrap();
+            stream.insert(b"line 2\n").unwrap();
+            assert_eq!(contents(&mut stream), "line 1\nline 2\nline 3\n");
+        }
+
+        {
+            let options = OpenOptions::new()
+                .read(true)
+                .write(true)
+                .open_existing(true);
+            let mut stream = Stream::buffered_file(&storage, path, options).unwrap();
+            let mut source = Stream::string();
+            let mut line = FuriString::new();
+            assert!(stream.read_line(&mut line));
+            assert_eq!(line, "line 1\n");
+
+            stream.delete_and_insert(6, b"second").unwrap();
+            stream.flush().unwrap();
+            assert_eq!(stream.copy_to(&mut source), 8);
+            assert_eq!(contents(&mut source), "\nline 3\n");
+        }
+
+        assert_eq!(storage.metadata(path).unwrap().len(), 21);
+        assert!(Stream::file(
+            &storage,
+            path,
+            OpenOptions::new().read(true).create_new(true)
+        )
+        .is_err());
+        storage.remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,714 @@
+//! Heatshrink compression, compatible with the firmware's compressed assets.
+//!
+//! [`Compressor`] and [`Decompressor`] compress and decompress data on the fly as it is
+//! written to or read from any [`Write`] or [`Read`], such as a
+//! [`File`](crate::storage::File). [`compress`] and [`decompress`] do the same for
+//! slices.
+//!
+//! The SDK compresses whole buffers with `compress_encode`, so [`Compressor`] splits
+//! its input into frames of up to 4 KiB and encodes each of them in turn.
+//! [`Decompressor`] reads frames with the SDK's stream decoder, which only holds a small
+//! buffer of compressed data at a time.
+//!
+//! # Format
+//!
+//! Each frame is the firmware's compressed asset header (`0x01 0x00 LEN_LOWER
+//! LEN_UPPER`, see `docs/icons.md`) followed by an independent heatshrink stream of
+//! `LEN` bytes. A frame that doesn't compress is stored as a `0x02 0x00 LEN_LOWER
+//! LEN_UPPER` header followed by the data itself.
+//!
+//! Output that fits in a single frame is exactly what the firmware's `compress_encode`
+//! produces, including the uncompressed asset (a `0x00` header followed by the data),
+//! so it can be decoded as an asset if it was compressed with [`Config::ASSETS`].
+
+use core::ffi::c_void;
+use core::mem::ManuallyDrop;
+use core::ptr::{self, NonNull};
+use core::slice;
+
+use alloc::vec::Vec;
+
+use flipperzero_sys::{self as sys, furi::FuriBox};
+
+use crate::io::{Error, Read, Write};
+
+/// First byte of a frame of compressed data, followed by a reserved `0x00` byte and the
+/// compressed length as a little-endian `u16`.
+const COMPRESSED: u8 = 0x01;
+
+/// First byte of a frame of data that didn't compress, with the same header as a
+/// compressed frame.
+const STORED: u8 = 0x02;
+
+/// First byte of an uncompressed asset, followed by the data itself.
+const UNCOMPRESSED: u8 = 0x00;
+
+const HEADER_LEN: usize = 4;
+
+/// Most input [`Compressor`] encodes as one frame.
+const FRAME_LEN: usize = 4096;
+
+/// Size of the buffer the stream decoder reads compressed data into.
+const INPUT_BUF_LEN: u16 = 256;
+
+/// Parameters of the heatshrink codec.
+///
+/// Data must be decompressed with the same parameters it was compressed with. Larger
+/// windows find more repetition, at the cost of `2^window_bits` bytes of memory for
+/// decompression and twice that for compression, and slower compression.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Config {
+    window_bits: u8,
+    lookahead_bits: u8,
+}
+
+impl Config {
+    /// The parameters the firmware uses for icons and other assets (`-w 8 -l 4`),
+    /// matching `compress_config_heatshrink_default`.
+    pub const ASSETS: Config = Config {
+        window_bits: 8,
+        lookahead_bits: 4,
+    };
+
+    /// Creates a config with a window of `2^window_bits` bytes, and back-references of
+    /// up to `2^lookahead_bits` bytes.
+    ///
+    /// `window_bits` must be between 4 and 15, and `lookahead_bits` must be at least 3
+    /// and less than `window_bits`; otherwise [`Error::InvalidParameter`] is returned.
+    pub const fn new(window_bits: u8, lookahead_bits: u8) -> Result<Self, Error> {
+        if window_bits < 4 || window_bits > 15 || lookahead_bits < 3 {
+            return Err(Error::InvalidParameter);
+        }
+        if lookahead_bits >= window_bits {
+            return Err(Error::InvalidParameter);
+        }
+        Ok(Config {
+            window_bits,
+            lookahead_bits,
+        })
+    }
+
+    /// Base-2 logarithm of the window size.
+    pub const fn window_bits(&self) -> u8 {
+        self.window_bits
+    }
+
+    /// Base-2 logarithm of the longest back-reference.
+    pub const fn lookahead_bits(&self) -> u8 {
+        self.lookahead_bits
+    }
+
+    fn to_sys(self) -> sys::CompressConfigHeatshrink {
+        sys::CompressConfigHeatshrink {
+            window_sz2: self.window_bits.into(),
+            lookahead_sz2: self.lookahead_bits.into(),
+            input_buffer_sz: INPUT_BUF_LEN,
+        }
+    }
+}
+
+impl Default for Config {
+    /// Returns [`Config::ASSETS`].
+    fn default() -> Self {
+        Self::ASSETS
+    }
+}
+
+/// Owned `Compress` instance.
+struct Encoder(NonNull<sys::Compress>);
+
+impl Encoder {
+    fn new(config: Config) -> Self {
+        let config = config.to_sys();
+        // SAFETY: Alloc always returns a valid non-null pointer or `furi_panic`s.
+        Self(unsafe {
+            NonNull::new_unchecked(sys::compress_alloc(
+                sys::CompressTypeHeatshrink,
+                ptr::from_ref(&config).cast(),
+            ))
+        })
+    }
+}
+
+impl Drop for Encoder {
+    fn drop(&mut self) {
+        unsafe { sys::compress_free(self.0.as_ptr()) }
+    }
+}
+
+/// Compresses data written to it, and writes it to an underlying writer.
+///
+/// Written data is collected until there are 4 KiB of it, [`Write::flush`] is called,
+/// or the compressor is finished, and then written out as a frame (see the
+/// [module documentation](self)). Each frame is compressed without any history, so
+/// flushing often makes the output larger.
+///
+/// Dropping a `Compressor` writes out the last frame, ignoring any errors; call
+/// [`Compressor::finish`] to handle them.
+///
+/// ```no_run
+/// # use flipperzero::io::Write;
+/// # use flipperzero::storage::OpenOptions;
+/// # use flipperzero::toolbox::compress::Compressor;
+/// let file = OpenOptions::new()
+///     .write(true)
+///     .create_always(true)
+///     .open(c"/ext/log.txt.hs")
+///     .unwrap();
+/// let mut log = Compressor::new(file);
+/// for i in 0..1000 {
+///     writeln!(log, "tick {i}").unwrap();
+/// }
+/// log.finish().unwrap();
+/// ```
+pub struct Compressor<W: Write> {
+    inner: W,
+    encoder: Encoder,
+    /// Data of the current frame.
+    input: Vec<u8>,
+    /// Space for `compress_encode` to write a frame to.
+    output: Vec<u8>,
+    /// Whether a frame has been written.
+    started: bool,
+}
+
+impl<W: Write> Compressor<W> {
+    /// Creates a compressor using [`Config::ASSETS`].
+    pub fn new(inner: W) -> Self {
+        Self::with_config(inner, Config::default())
+    }
+
+    /// Creates a compressor using `config`.
+    pub fn with_config(inner: W, config: Config) -> Self {
+        Self {
+            inner,
+            encoder: Encoder::new(config),
+            input: Vec::new(),
+            output: Vec::new(),
+            started: false,
+        }
+    }
+
+    /// Gets a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying writer.
+    ///
+    /// Writing to it directly corrupts the compressed stream.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Writes out the last frame and unwraps this compressor, returning the underlying
+    /// writer.
+    pub fn finish(mut self) -> Result<W, Error> {
+        self.end_frame(true)?;
+
+        let this = ManuallyDrop::new(self);
+        // SAFETY: `this` is never used or dropped again, so each field is moved out
+        // exactly once, and the others are dropped here.
+        unsafe {
+            drop(ptr::read(&this.encoder));
+            drop(ptr::read(&this.input));
+            drop(ptr::read(&this.output));
+            Ok(ptr::read(&this.inner))
+        }
+    }
+
+    /// Compresses and writes out the current frame, if there is one.
+    ///
+    /// If this is the `last` frame and the only one, it is written exactly as
+    /// `compress_encode` returned it.
+    fn end_frame(&mut self, last: bool) -> Result<(), Error> {
+        if self.input.is_empty() {
+            return Ok(());
+        }
+
+        // `compress_encode` needs room for a header and the data when it doesn't
+        // compress.
+        self.output.resize(self.input.len() + HEADER_LEN, 0);
+        let mut len = 0;
+        let encoded = unsafe {
+            sys::compress_encode(
+                self.encoder.0.as_ptr(),
+                self.input.as_mut_ptr(),
+                self.input.len(),
+                self.output.as_mut_ptr(),
+                self.output.len(),
+                &mut len,
+            )
+        };
+        if !encoded {
+            return Err(Error::Internal);
+        }
+
+        let output = &self.output[..len];
+        if output[0] == COMPRESSED || (last && !self.started) {
+            self.inner.write_all(output)?;
+        } else {
+            // The firmware stores data that doesn't compress without its length, which
+            // only works at the end of a stream.
+            let [len_lower, len_upper] = (self.input.len() as u16).to_le_bytes();
+            self.inner
+                .write_all(&[STORED, 0x00, len_lower, len_upper])?;
+            self.inner.write_all(&self.input)?;
+        }
+        self.started = true;
+        self.input.clear();
+        Ok(())
+    }
+}
+
+impl<W: Write> Write for Compressor<W> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        if self.input.len() == FRAME_LEN {
+            self.end_frame(false)?;
+        }
+
+        let len = buf.len().min(FRAME_LEN - self.input.len());
+        self.input.extend_from_slice(&buf[..len]);
+        Ok(len)
+    }
+
+    /// Writes out the current frame, so that everything written so far can be
+    /// decompressed, and flushes the underlying writer.
+    fn flush(&mut self) -> Result<(), Error> {
+        self.end_frame(false)?;
+        self.inner.flush()
+    }
+}
+
+impl<W: Write> Drop for Compressor<W> {
+    fn drop(&mut self) {
+        // Errors can't be reported from `drop`; see the type documentation.
+        let _ = self.end_frame(true);
+    }
+}
+
+/// Owned `CompressStreamDecoder` instance.
+struct StreamDecoder(NonNull<sys::CompressStreamDecoder>);
+
+impl Drop for StreamDecoder {
+    fn drop(&mut self) {
+        unsafe { sys::compress_stream_decoder_free(self.0.as_ptr()) }
+    }
+}
+
+/// Context of the stream decoder's read callback.
+struct Source<R> {
+    /// The decompressor's reader, set before each call into the decoder.
+    reader: *mut R,
+    /// Compressed bytes left in the current frame.
+    remaining: usize,
+    /// The error that made the read callback fail.
+    error: Option<Error>,
+}
+
+unsafe extern "C" fn read_callback<R: Read>(
+    context: *mut c_void,
+    buffer: *mut u8,
+    size: usize,
+) -> i32 {
+    let source = unsafe { &mut *context.cast::<Source<R>>() };
+    // The decoder reaches the end of its stream at the end of the frame.
+    let len = size.min(source.remaining);
+    if len == 0 {
+        return 0;
+    }
+
+    let buf = unsafe { slice::from_raw_parts_mut(buffer, len) };
+    match unsafe { &mut *source.reader }.read(buf) {
+        Ok(0) => {
+            source.error = Some(Error::UnexpectedEof);
+            -1
+        }
+        Ok(read) => {
+            source.remaining -= read;
+            read as i32
+        }
+        Err(err) => {
+            source.error = Some(err);
+            -1
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum State {
+    /// Before the first frame, where an uncompressed asset is also accepted.
+    Start,
+    /// Between frames.
+    Header,
+    /// Inside a compressed frame.
+    Compressed,
+    /// Inside a stored frame.
+    Stored,
+    /// Inside an uncompressed asset, which lasts until the end of the reader.
+    Uncompressed,
+}
+
+/// Decompresses data read from an underlying reader.
+///
+/// Reads the frames written by [`Compressor`] (see the [module documentation](self)),
+/// and firmware assets, which are either a single compressed frame or an uncompressed
+/// `0x00` header followed by the data.
+///
+/// ```no_run
+/// # use flipperzero::io::{BufRead, BufReader};
+/// # use flipperzero::storage::OpenOptions;
+/// # use flipperzero::toolbox::compress::Decompressor;
+/// let file = OpenOptions::new().read(true).open(c"/ext/log.txt.hs").unwrap();
+/// for line in BufReader::new(Decompressor::new(file)).lines() {
+///     let line = line.unwrap();
+/// }
+/// ```
+pub struct Decompressor<R> {
+    // Declared first, so that it is freed before the context it points to.
+    decoder: StreamDecoder,
+    source: FuriBox<Source<R>>,
+    inner: R,
+    state: State,
+}
+
+impl<R: Read> Decompressor<R> {
+    /// Creates a decompressor using [`Config::ASSETS`].
+    pub fn new(inner: R) -> Self {
+        Self::with_config(inner, Config::default())
+    }
+
+    /// Creates a decompressor using `config`, which must match the one the data was
+    /// compressed with.
+    pub fn with_config(inner: R, config: Config) -> Self {
+        let config = config.to_sys();
+        let mut source = FuriBox::new(Source {
+            reader: ptr::null_mut(),
+            remaining: 0,
+            error: None,
+        });
+        // SAFETY: Alloc always returns a valid non-null pointer or `furi_panic`s.
+        let decoder = StreamDecoder(unsafe {
+            NonNull::new_unchecked(sys::compress_stream_decoder_alloc(
+                sys::CompressTypeHeatshrink,
+                ptr::from_ref(&config).cast(),
+                Some(read_callback::<R>),
+                FuriBox::as_mut_ptr(&mut source).cast(),
+            ))
+        });
+
+        Self {
+            decoder,
+            source,
+            inner,
+            state: State::Start,
+        }
+    }
+
+    /// Gets a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying reader.
+    ///
+    /// Reading from it directly corrupts the compressed stream.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Unwraps this decompressor, returning the underlying reader.
+    ///
+    /// Any compressed data that has been read but not decompressed is lost.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Reads the header of the next frame, returning `false` at the end of the stream.
+    fn read_header(&mut self) -> Result<bool, Error> {
+        let mut kind = [0];
+        if self.inner.read(&mut kind)? == 0 {
+            return Ok(false);
+        }
+
+        self.state = match (kind[0], self.state) {
+            (COMPRESSED | STORED, _) => {
+                let mut header = [0; HEADER_LEN - 1];
+                self.inner.read_exact(&mut header)?;
+                if header[0] != 0x00 {
+                    return Err(Error::InvalidData);
+                }
+                self.source.remaining = u16::from_le_bytes([header[1], header[2]]).into();
+                if kind[0] == COMPRESSED {
+                    State::Compressed
+                } else {
+                    State::Stored
+                }
+            }
+            (UNCOMPRESSED, State::Start) => State::Uncompressed,
+            _ => return Err(Error::InvalidData),
+        };
+        Ok(true)
+    }
+
+    /// Decompresses from the current frame into `out`, returning 0 at its end.
+    fn read_compressed(&mut self, out: &mut [u8]) -> Result<usize, Error> {
+        self.source.reader = &mut self.inner;
+        let decoder = self.decoder.0.as_ptr();
+        // `compress_stream_decoder_read` only returns `true` if it filled `out`, but
+        // the position in the decompressed stream counts what it read before running
+        // out of input.
+        let (filled, read) = unsafe {
+            let start = sys::compress_stream_decoder_tell(decoder);
+            let filled = sys::compress_stream_decoder_read(decoder, out.as_mut_ptr(), out.len());
+            (filled, sys::compress_stream_decoder_tell(decoder) - start)
+        };
+        self.source.reader = ptr::null_mut();
+
+        if let Some(err) = self.source.error.take() {
+            return Err(err);
+        }
+        if filled || read > 0 {
+            return Ok(read);
+        }
+        if self.source.remaining > 0 {
+            // The decoder failed before the end of the frame.
+            return Err(Error::InvalidData);
+        }
+        // Start the next frame with a fresh decoder.
+        unsafe { sys::compress_stream_decoder_rewind(decoder) };
+        Ok(0)
+    }
+}
+
+impl<R: Read> Read for Decompressor<R> {
+    fn read(&mut self, out: &mut [u8]) -> Result<usize, Error> {
+        if out.is_empty() {
+            return Ok(0);
+        }
+
+        loop {
+            match self.state {
+                State::Start | State::Header => {
+                    if !self.read_header()? {
+                        return Ok(0);
+                    }
+                }
+                State::Uncompressed => return self.inner.read(out),
+                State::Compressed => match self.read_compressed(out)? {
+                    0 => self.state = State::Header,
+                    read => return Ok(read),
+                },
+                State::Stored if self.source.remaining == 0 => self.state = State::Header,
+                State::Stored => {
+                    let len = out.len().min(self.source.remaining);
+                    let read = self.inner.read(&mut out[..len])?;
+                    if read == 0 {
+                        return Err(Error::UnexpectedEof);
+                    }
+                    self.source.remaining -= read;
+                    return Ok(read);
+                }
+            }
+        }
+    }
+}
+
+/// Compresses `data` into a new vector, in the format written by [`Compressor`].
+///
+/// If `data` is at most 4 KiB, the result is what the firmware's `compress_encode`
+/// returns, which the firmware can decode as an asset when compressed with
+/// [`Config::ASSETS`].
+pub fn compress(data: &[u8], config: Config) -> Vec<u8> {
+    let mut compressor = Compressor::with_config(Vec::new(), config);
+    // Writing to a vector can't fail.
+    compressor.write_all(data).unwrap();
+    compressor.finish().unwrap()
+}
+
+/// Decompresses `data`, as written by [`Compressor`] or a firmware asset, into a new
+/// vector.
+pub fn decompress(data: &[u8], config: Config) -> Result<Vec<u8>, Error> {
+    let mut out = Vec::new();
+    Decompressor::with_config(data, config).read_to_end(&mut out)?;
+    Ok(out)
+}
+
+#[flipperzero_test::tests]
+mod tests {
+    use core::ptr;
+
+    use alloc::{vec, vec::Vec};
+    use flipperzero_sys as sys;
+
+    use super::{compress, decompress, Compressor, Config, Decompressor};
+    use crate::io::{Error, Read, Write};
+
+    fn text() -> Vec<u8> {
+        (0..1000u32)
+            .flat_map(|i| [b'a' + (i % 7) as u8, b' ', b'x', (i / 100) as u8])
+            .collect()
+    }
+
+    /// Bytes from a linear congruential generator, which don't compress.
+    fn noise(len: usize) -> Vec<u8> {
+        let mut state = 1u32;
+        (0..len)
+            .map(|_| {
+                state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                (state >> 16) as u8
+            })
+            .collect()
+    }
+
+    #[test]
+    fn config() {
+        assert_eq!(Config::default(), Config::ASSETS);
+        assert_eq!(Config::new(8, 4).unwrap(), Config::ASSETS);
+        assert_eq!(Config::ASSETS.window_bits(), 8);
+        assert_eq!(Config::ASSETS.lookahead_bits(), 4);
+        assert!(matches!(Config::new(3, 3), Err(Error::InvalidParameter)));
+        assert!(matches!(Config::new(16, 4), Err(Error::InvalidParameter)));
+        assert!(matches!(Config::new(8, 8), Err(Error::InvalidParameter)));
+    }
+
+    #[test]
+    fn round_trip() {
+        for config in [Config::ASSETS, Config::new(10, 5).unwrap()] {
+            for data in [text(), noise(5000), vec![0; 10000], b"x".to_vec()] {
+                let compressed = compress(&data, config);
+                assert_eq!(decompress(&compressed, config).unwrap(), data);
+            }
+        }
+
+        let data = text();
+        assert!(compress(&data, Config::ASSETS).len() < data.len() / 3);
+    }
+
+    #[test]
+    fn firmware_decodes_compressed() {
+        let data = text();
+        let mut compressed = compress(&data, Config::ASSETS);
+        assert!(compressed.len() < data.len());
+        assert_eq!(compressed[..2], [0x01, 0x00]);
+
+        let mut out = vec![0; data.len()];
+        let mut out_len = 0;
+        unsafe {
+            let compress = sys::compress_alloc(
+                sys::CompressTypeHeatshrink,
+                ptr::from_ref(&sys::compress_config_heatshrink_default).cast(),
+            );
+            assert!(sys::compress_decode(
+                compress,
+                compressed.as_mut_ptr(),
+                compressed.len(),
+                out.as_mut_ptr(),
+                out.len(),
+                &mut out_len,
+            ));
+            sys::compress_free(compress);
+        }
+        assert_eq!(out_len, data.len());
+        assert_eq!(out, data);
+    }
+
+    #[test]
+    fn frames() {
+        // Each frame holds up to 4 KiB, and data that doesn't compress is stored.
+        let mut data = text();
+        data.extend(noise(5000));
+        let compressed = compress(&data, Config::ASSETS);
+        let mut kinds = Vec::new();
+        let mut offset = 0;
+        while offset < compressed.len() {
+            kinds.push(compressed[offset]);
+            let len = u16::from_le_bytes([compressed[offset + 2], compressed[offset + 3]]);
+            offset += 4 + usize::from(len);
+        }
+        assert_eq!(kinds, [0x01, 0x02, 0x02]);
+        assert_eq!(decompress(&compressed, Config::ASSETS).unwrap(), data);
+
+        // Flushing ends a frame, so everything written so far can be read back.
+        let mut compressor = Compressor::new(Vec::new());
+        compressor.write_all(b"hello ").unwrap();
+        compressor.flush().unwrap();
+        let first = compressor.get_ref().len();
+        assert_eq!(
+            decompress(compressor.get_ref(), Config::ASSETS).unwrap(),
+            b"hello "
+        );
+        compressor.write_all(b"world").unwrap();
+        let out = compressor.finish().unwrap();
+        assert!(out.len() > first);
+        assert_eq!(decompress(&out, Config::ASSETS).unwrap(), b"hello world");
+
+        // Dropping ends the frame too.
+        let mut out = Vec::new();
+        Compressor::new(&mut out).write_all(b"dropped").unwrap();
+        assert_eq!(decompress(&out, Config::ASSETS).unwrap(), b"dropped");
+    }
+
+    #[test]
+    fn uncompressed_asset() {
+        assert_eq!(compress(b"abc", Config::ASSETS), b"\x00abc");
+        assert_eq!(
+            decompress(b"\x00raw data", Config::ASSETS).unwrap(),
+            b"raw data"
+        );
+
+        // Only valid as the whole stream.
+        let mut data = compress(&text(), Config::ASSETS);
+        data.extend(b"\x00raw");
+        assert!(matches!(
+            decompress(&data, Config::ASSETS),
+            Err(Error::InvalidData)
+        ));
+    }
+
+    #[test]
+    fn invalid_input() {
+        assert!(matches!(
+            decompress(b"\x03", Config::ASSETS),
+            Err(Error::InvalidData)
+        ));
+        assert!(matches!(
+            decompress(b"\x01\x01\x00\x00", Config::ASSETS),
+            Err(Error::InvalidData)
+        ));
+        assert!(matches!(
+            decompress(b"\x01\x00", Config::ASSETS),
+            Err(Error::UnexpectedEof)
+        ));
+
+        // Truncated compressed and stored frames.
+        let mut mixed = text();
+        mixed.extend(noise(5000));
+        for data in [&text()[..1000], &mixed[..]] {
+            let compressed = compress(data, Config::ASSETS);
+            assert!(matches!(
+                decompress(&compressed[..compressed.len() - 1], Config::ASSETS),
+                Err(Error::UnexpectedEof)
+            ));
+        }
+    }
+
+    #[test]
+    fn small_reads() {
+        let data = text();
+        let compressed = compress(&data, Config::ASSETS);
+        let mut decompressor = Decompressor::new(compressed.as_slice());
+
+        let mut out = Vec::new();
+        let mut buf = [0; 7];
+        loop {
+            let n = decompressor.read(&mut buf).unwrap();
+            if n == 0 {
+                break;
+            }
+            out.extend_from_slice(&buf[..n]);
+        }
+        assert_eq!(out, data);
+        assert!(matches!(decompressor.read(&mut buf), Ok(0)));
+    }
+}
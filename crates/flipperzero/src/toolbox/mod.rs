@@ -11,5 +11,8 @@
 //! - Using an equivalent pure-Rust type enables the Rust compiler to optimize the
 //!   application more effectively, at the cost of larger binary size.
 
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub mod compress;
 pub(crate) mod crc32;
 pub use self::crc32::Crc32;
@@ -0,0 +1,334 @@
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::{
+    parse, punctuated::Punctuated, spanned::Spanned, Attribute, Data, DataEnum, DataStruct,
+    DeriveInput, Expr, ExprLit, Fields, Lit, LitStr, Meta, Path, Token,
+};
+
+/// Options from `#[flipper_format(...)]` on a struct or enum.
+struct Container {
+    krate: Path,
+    header: Option<(LitStr, u32)>,
+    migrate: Option<Path>,
+}
+
+impl Container {
+    fn parse(input: &DeriveInput) -> parse::Result<Self> {
+        let mut krate = syn::parse_quote!(::flipperzero_format);
+        let mut filetype = None;
+        let mut version = None;
+        let mut migrate = None;
+
+        for meta in flipper_format_attrs(&input.attrs)? {
+            match &meta {
+                Meta::NameValue(nv) if nv.path.is_ident("crate") => {
+                    krate = lit_str(&nv.value)?.parse()?;
+                }
+                Meta::NameValue(nv) if nv.path.is_ident("filetype") => {
+                    filetype = Some(lit_str(&nv.value)?.clone());
+                }
+                Meta::NameValue(nv) if nv.path.is_ident("version") => match &nv.value {
+                    Expr::Lit(ExprLit {
+                        lit: Lit::Int(v), ..
+                    }) => version = Some(v.base10_parse::<u32>()?),
+                    value => return Err(parse::Error::new(value.span(), "expected an integer")),
+                },
+                Meta::NameValue(nv) if nv.path.is_ident("migrate") => {
+                    migrate = Some(lit_str(&nv.value)?.parse()?);
+                }
+                _ => return Err(parse::Error::new(meta.span(), "unknown attribute")),
+            }
+        }
+
+        let header = match (filetype, version) {
+            (Some(filetype), Some(version)) => Some((filetype, version)),
+            (None, None) => None,
+            _ => {
+                return Err(parse::Error::new(
+                    Span::call_site(),
+                    "`filetype` and `version` must be specified together",
+                ))
+            }
+        };
+
+        if migrate.is_some() && header.is_none() {
+            return Err(parse::Error::new(
+                Span::call_site(),
+                "`migrate` requires `filetype` and `version`",
+            ));
+        }
+
+        Ok(Container {
+            krate,
+            header,
+            migrate,
+        })
+    }
+}
+
+/// Options from `#[flipper_format(...)]` on a field or variant.
+#[derive(Default)]
+struct Member {
+    rename: Option<LitStr>,
+    default: bool,
+    skip: bool,
+}
+
+impl Member {
+    fn parse(attrs: &[Attribute], allow_field_options: bool) -> parse::Result<Self> {
+        let mut member = Member::default();
+
+        for meta in flipper_format_attrs(attrs)? {
+            match &meta {
+                Meta::NameValue(nv) if nv.path.is_ident("rename") => {
+                    member.rename = Some(lit_str(&nv.value)?.clone());
+                }
+                Meta::Path(path) if allow_field_options && path.is_ident("default") => {
+                    member.default = true;
+                }
+                Meta::Path(path) if allow_field_options && path.is_ident("skip") => {
+                    member.skip = true;
+                }
+                _ => return Err(parse::Error::new(meta.span(), "unknown attribute")),
+            }
+        }
+
+        Ok(member)
+    }
+
+    fn key(&self, ident: &syn::Ident) -> LitStr {
+        self.rename
+            .clone()
+            .unwrap_or_else(|| LitStr::new(&ident.to_string(), ident.span()))
+    }
+}
+
+/// Collects the items of all `#[flipper_format(...)]` attributes.
+fn flipper_format_attrs(attrs: &[Attribute]) -> parse::Result<Vec<Meta>> {
+    let mut items = vec![];
+
+    for attr in attrs {
+        if !attr.path().is_ident("flipper_format") {
+            continue;
+        }
+
+        match &attr.meta {
+            Meta::List(list) => {
+                items
+                    .extend(list.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?);
+            }
+            meta => {
+                return Err(parse::Error::new(
+                    meta.span(),
+                    "expected `#[flipper_format(...)]`",
+                ))
+            }
+        }
+    }
+
+    Ok(items)
+}
+
+fn lit_str(value: &Expr) -> parse::Result<&LitStr> {
+    match value {
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(s), ..
+        }) => Ok(s),
+        value => Err(parse::Error::new(value.span(), "expected a string")),
+    }
+}
+
+#[proc_macro_derive(FlipperFormat, attributes(flipper_format))]
+pub fn derive_flipper_format(input: TokenStream) -> TokenStream {
+    match derive_impl(input) {
+        Ok(ts) => ts.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn derive_impl(input: TokenStream) -> parse::Result<TokenStream2> {
+    let input: DeriveInput = syn::parse(input)?;
+    let container = Container::parse(&input)?;
+
+    match &input.data {
+        Data::Struct(data) => derive_struct(&input, &container, data),
+        Data::Enum(data) => derive_enum(&input, &container, data),
+        Data::Union(_) => Err(parse::Error::new(
+            input.span(),
+            "`FlipperFormat` cannot be derived for unions",
+        )),
+    }
+}
+
+fn derive_struct(
+    input: &DeriveInput,
+    container: &Container,
+    data: &DataStruct,
+) -> parse::Result<TokenStream2> {
+    let Container {
+        krate,
+        header,
+        migrate,
+    } = container;
+
+    let fields = match &data.fields {
+        Fields::Named(fields) => &fields.named,
+        _ => {
+            return Err(parse::Error::new(
+                input.span(),
+                "`FlipperFormat` can only be derived for structs with named fields",
+            ))
+        }
+    };
+
+    let mut loads = vec![];
+    let mut saves = vec![];
+    for field in fields {
+        let member = Member::parse(&field.attrs, true)?;
+        let ident = field.ident.as_ref().expect("named field");
+        let ty = &field.ty;
+        let key = member.key(ident);
+
+        if member.skip {
+            loads.push(quote!(#ident: ::core::default::Default::default()));
+            continue;
+        }
+
+        let read = quote!(<#ty as #krate::Value>::read(backend, #key)?);
+        let read = if member.default {
+            quote!(if #krate::Backend::key_exists(backend, #key) {
+                #read
+            } else {
+                ::core::default::Default::default()
+            })
+        } else {
+            read
+        };
+
+        loads.push(quote!(#ident: {
+            #krate::Backend::rewind(backend)?;
+            #read
+        }));
+        saves.push(quote!(#krate::Value::write(&self.#ident, backend, #key)?;));
+    }
+
+    let (load_header, migrate_call, save_header) = match header {
+        Some((filetype, version)) => {
+            let allow_older = migrate.is_some();
+            let migrate_call = migrate.as_ref().map(|migrate| {
+                quote!(if version != #version {
+                    #migrate(&mut this, version)?;
+                })
+            });
+
+            (
+                quote!(let version = #krate::__macro_support::read_header(
+                    backend,
+                    #filetype,
+                    #version,
+                    #allow_older,
+                )?;),
+                migrate_call,
+                quote!(#krate::Backend::write_header(backend, #filetype, #version)?;),
+            )
+        }
+        None => (quote!(), None, quote!()),
+    };
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote!(
+        impl #impl_generics #krate::Load for #name #ty_generics #where_clause {
+            fn load<B: #krate::Backend + ?Sized>(
+                backend: &mut B,
+            ) -> ::core::result::Result<Self, #krate::Error> {
+                #krate::Backend::rewind(backend)?;
+                #load_header
+
+                #[allow(unused_mut)]
+                let mut this = Self {
+                    #(#loads,)*
+                };
+                #migrate_call
+
+                ::core::result::Result::Ok(this)
+            }
+        }
+
+        impl #impl_generics #krate::Save for #name #ty_generics #where_clause {
+            fn save<B: #krate::Backend + ?Sized>(
+                &self,
+                backend: &mut B,
+            ) -> ::core::result::Result<(), #krate::Error> {
+                #save_header
+                #(#saves)*
+
+                ::core::result::Result::Ok(())
+            }
+        }
+    ))
+}
+
+fn derive_enum(
+    input: &DeriveInput,
+    container: &Container,
+    data: &DataEnum,
+) -> parse::Result<TokenStream2> {
+    let Container { krate, header, .. } = container;
+
+    if header.is_some() {
+        return Err(parse::Error::new(
+            Span::call_site(),
+            "`filetype` and `version` are only supported on structs",
+        ));
+    }
+
+    let mut reads = vec![];
+    let mut writes = vec![];
+    for variant in &data.variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(parse::Error::new(
+                variant.span(),
+                "`FlipperFormat` can only be derived for enums with unit variants",
+            ));
+        }
+
+        let member = Member::parse(&variant.attrs, false)?;
+        let ident = &variant.ident;
+        let value = member.key(ident);
+
+        reads.push(quote!(#value => ::core::result::Result::Ok(Self::#ident)));
+        writes.push(quote!(Self::#ident => #value));
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote!(
+        impl #impl_generics #krate::Value for #name #ty_generics #where_clause {
+            fn read<B: #krate::Backend + ?Sized>(
+                backend: &mut B,
+                key: &str,
+            ) -> ::core::result::Result<Self, #krate::Error> {
+                let value = #krate::Backend::read_string(backend, key)?;
+                match value.as_str() {
+                    #(#reads,)*
+                    _ => ::core::result::Result::Err(#krate::__macro_support::invalid_value(key)),
+                }
+            }
+
+            fn write<B: #krate::Backend + ?Sized>(
+                &self,
+                backend: &mut B,
+                key: &str,
+            ) -> ::core::result::Result<(), #krate::Error> {
+                let value = match self {
+                    #(#writes,)*
+                };
+                #krate::Backend::write_string(backend, key, value)
+            }
+        }
+    ))
+}
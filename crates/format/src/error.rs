@@ -0,0 +1,90 @@
+use alloc::string::String;
+use core::fmt;
+
+/// Flipper Format errors.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// Seeking in, or closing, the underlying storage failed.
+    Io,
+    /// The key was not found.
+    MissingKey(String),
+    /// The key was found, but its value could not be parsed as the requested type.
+    InvalidValue(String),
+    /// The key and its value could not be written.
+    Write(String),
+    /// The file's `Filetype` header did not match.
+    Filetype { expected: String, found: String },
+    /// The file's `Version` header is not supported.
+    Version { expected: u32, found: u32 },
+    /// The text could not be parsed as Flipper Format (1-based line number).
+    Parse { line: usize },
+}
+
+impl Error {
+    /// Description associated with [`Error`].
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Io => "stream operation failed",
+            Self::MissingKey(_) => "missing key",
+            Self::InvalidValue(_) => "invalid value for key",
+            Self::Write(_) => "failed to write key",
+            Self::Filetype { .. } => "unexpected filetype",
+            Self::Version { .. } => "unsupported version",
+            Self::Parse { .. } => "invalid Flipper Format text",
+        }
+    }
+
+    /// The key this error refers to, if any.
+    pub fn key(&self) -> Option<&str> {
+        match self {
+            Self::MissingKey(key) | Self::InvalidValue(key) | Self::Write(key) => Some(key),
+            Self::Io | Self::Filetype { .. } | Self::Version { .. } | Self::Parse { .. } => None,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.description())?;
+        match self {
+            Self::MissingKey(key) | Self::InvalidValue(key) | Self::Write(key) => {
+                write!(f, " `{}`", key)
+            }
+            Self::Filetype { expected, found } => {
+                write!(f, " `{}` (expected `{}`)", found, expected)
+            }
+            Self::Version { expected, found } => write!(f, " {} (expected {})", found, expected),
+            Self::Parse { line } => write!(f, " on line {}", line),
+            Self::Io => Ok(()),
+        }
+    }
+}
+
+impl ufmt::uDisplay for Error {
+    fn fmt<W>(&self, f: &mut ufmt::Formatter<'_, W>) -> Result<(), W::Error>
+    where
+        W: ufmt::uWrite + ?Sized,
+    {
+        f.write_str(self.description())?;
+        match self {
+            Self::MissingKey(key) | Self::InvalidValue(key) | Self::Write(key) => {
+                ufmt::uwrite!(f, " `{}`", key.as_str())
+            }
+            Self::Filetype { expected, found } => {
+                ufmt::uwrite!(
+                    f,
+                    " `{}` (expected `{}`)",
+                    found.as_str(),
+                    expected.as_str()
+                )
+            }
+            Self::Version { expected, found } => {
+                ufmt::uwrite!(f, " {} (expected {})", found, expected)
+            }
+            Self::Parse { line } => ufmt::uwrite!(f, " on line {}", line),
+            Self::Io => Ok(()),
+        }
+    }
+}
+
+impl core::error::Error for Error {}
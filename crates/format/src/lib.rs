@@ -0,0 +1,324 @@
+//! Flipper Format key-value files for Flipper Zero.
+//!
+//! Flipper Format is the line-based `Key: value` text format used for `.sub`, `.ir`,
+//! `.nfc`, `.rfid` and settings files. This crate is pure Rust (`no_std` + `alloc`), so
+//! the same code can run on the device and on a PC.
+//!
+//! Storage is abstracted by the [`Backend`] trait. [`FlipperFormat`](struct@FlipperFormat) is an in-memory
+//! backend that can parse and print the text format, for host tools and tests; on the
+//! device, `flipperzero::flipper_format::FlipperFormat` implements
+//! [`Backend`] when the `flipperzero-format` feature of `flipperzero` is enabled.
+//!
+//! With the `derive` feature, `#[derive(FlipperFormat)]` generates [`Load`] and [`Save`]
+//! implementations for structs, and a [`Value`] implementation for unit-only enums:
+//!
+//! ```
+//! # #[cfg(feature = "derive")] {
+//! use flipperzero_format::{FlipperFormat, Load, Save};
+//!
+//! #[derive(FlipperFormat, Clone, Copy, PartialEq)]
+//! enum Modulation {
+//!     #[flipper_format(rename = "AM650")]
+//!     Am,
+//!     #[flipper_format(rename = "FM238")]
+//!     Fm,
+//! }
+//!
+//! #[derive(FlipperFormat)]
+//! #[flipper_format(filetype = "Example Settings", version = 2, migrate = "Settings::migrate")]
+//! struct Settings {
+//!     #[flipper_format(rename = "Frequency")]
+//!     frequency: u32,
+//!     #[flipper_format(rename = "Preset")]
+//!     modulation: Modulation,
+//!     #[flipper_format(rename = "Key")]
+//!     key: [u8; 4],
+//!     #[flipper_format(rename = "Comment")]
+//!     comment: Option<String>,
+//!     #[flipper_format(rename = "Timings", default)]
+//!     timings: Vec<i32>,
+//! }
+//!
+//! impl Settings {
+//!     /// Version 1 files stored the frequency in kHz.
+//!     fn migrate(&mut self, version: u32) -> Result<(), flipperzero_format::Error> {
+//!         if version == 1 {
+//!             self.frequency *= 1000;
+//!         }
+//!         Ok(())
+//!     }
+//! }
+//!
+//! let settings = Settings {
+//!     frequency: 433_920_000,
+//!     modulation: Modulation::Am,
+//!     key: [0xDE, 0xAD, 0xBE, 0xEF],
+//!     comment: None,
+//!     timings: vec![],
+//! };
+//!
+//! let mut ff = FlipperFormat::new();
+//! settings.save(&mut ff).unwrap();
+//!
+//! let loaded = Settings::load(&mut ff).unwrap();
+//! assert_eq!(loaded.frequency, 433_920_000);
+//! # }
+//! ```
+//!
+//! # Features
+//!
+//! - `derive`: Enables `#[derive(FlipperFormat)]`.
+
+#![no_std]
+#![cfg_attr(docsrs, feature(doc_cfg))]
+#![deny(rustdoc::broken_intra_doc_links)]
+
+extern crate alloc;
+
+mod error;
+mod memory;
+mod value;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+pub use error::Error;
+pub use memory::FlipperFormat;
+pub use value::Value;
+
+/// Derives [`Load`] and [`Save`] for structs, or [`Value`] for unit-only enums.
+///
+/// Container attributes (`#[flipper_format(...)]` on the struct):
+///
+/// - `filetype = "..."` and `version = N`: read and check, or write, the file header.
+/// - `migrate = "path"`: called as `path(&mut self, version)` after loading a file with
+///   an older version. Without it, only files with exactly `version` are accepted.
+/// - `crate = "path"`: path to this crate, if it is not `::flipperzero_format`.
+///
+/// Field attributes:
+///
+/// - `rename = "..."`: key to use instead of the field name.
+/// - `default`: use [`Default::default`] if the key is missing.
+/// - `skip`: neither load nor save the field, using [`Default::default`] when loading.
+///
+/// Variant attributes:
+///
+/// - `rename = "..."`: string to use instead of the variant name.
+#[cfg(feature = "derive")]
+#[cfg_attr(docsrs, doc(cfg(feature = "derive")))]
+pub use flipperzero_format_macros::FlipperFormat;
+
+/// Storage for Flipper Format keys and values.
+///
+/// Reads search for the key starting at the current position. Writes are made at the
+/// current position, which is normally the end.
+pub trait Backend {
+    /// Moves the read/write position back to the start.
+    fn rewind(&mut self) -> Result<(), Error>;
+
+    /// Checks whether `key` exists anywhere, without moving the read/write position.
+    fn key_exists(&mut self, key: &str) -> bool;
+
+    /// Counts the values stored under the next occurrence of `key`.
+    fn value_count(&mut self, key: &str) -> Result<usize, Error>;
+
+    /// Reads the string value of `key`.
+    fn read_string(&mut self, key: &str) -> Result<String, Error>;
+
+    /// Reads `values.len()` unsigned integer values of `key`.
+    fn read_u32_array(&mut self, key: &str, values: &mut [u32]) -> Result<(), Error>;
+
+    /// Reads `values.len()` signed integer values of `key`.
+    fn read_i32_array(&mut self, key: &str, values: &mut [i32]) -> Result<(), Error>;
+
+    /// Reads `values.len()` floating-point values of `key`.
+    fn read_float_array(&mut self, key: &str, values: &mut [f32]) -> Result<(), Error>;
+
+    /// Reads `values.len()` boolean values of `key`.
+    fn read_bool_array(&mut self, key: &str, values: &mut [bool]) -> Result<(), Error>;
+
+    /// Reads `bytes.len()` hex-encoded bytes (e.g. `DE AD BE EF`) of `key`.
+    fn read_hex(&mut self, key: &str, bytes: &mut [u8]) -> Result<(), Error>;
+
+    /// Writes a string value.
+    fn write_string(&mut self, key: &str, value: &str) -> Result<(), Error>;
+
+    /// Writes an array of unsigned integer values.
+    fn write_u32_array(&mut self, key: &str, values: &[u32]) -> Result<(), Error>;
+
+    /// Writes an array of signed integer values.
+    fn write_i32_array(&mut self, key: &str, values: &[i32]) -> Result<(), Error>;
+
+    /// Writes an array of floating-point values.
+    fn write_float_array(&mut self, key: &str, values: &[f32]) -> Result<(), Error>;
+
+    /// Writes an array of boolean values.
+    fn write_bool_array(&mut self, key: &str, values: &[bool]) -> Result<(), Error>;
+
+    /// Writes bytes as hex (e.g. `DE AD BE EF`).
+    fn write_hex(&mut self, key: &str, bytes: &[u8]) -> Result<(), Error>;
+
+    /// Removes the next occurrence of `key` and its value.
+    fn delete_key(&mut self, key: &str) -> Result<(), Error>;
+
+    /// Reads the `Filetype` and `Version` header.
+    fn read_header(&mut self) -> Result<(String, u32), Error> {
+        let filetype = self.read_string("Filetype")?;
+        let version = self.read_u32("Version")?;
+
+        Ok((filetype, version))
+    }
+
+    /// Writes the `Filetype` and `Version` header.
+    fn write_header(&mut self, filetype: &str, version: u32) -> Result<(), Error> {
+        self.write_string("Filetype", filetype)?;
+        self.write_u32("Version", version)
+    }
+
+    /// Reads the unsigned integer value of `key`.
+    fn read_u32(&mut self, key: &str) -> Result<u32, Error> {
+        let mut value = 0;
+        self.read_u32_array(key, core::slice::from_mut(&mut value))?;
+        Ok(value)
+    }
+
+    /// Reads the signed integer value of `key`.
+    fn read_i32(&mut self, key: &str) -> Result<i32, Error> {
+        let mut value = 0;
+        self.read_i32_array(key, core::slice::from_mut(&mut value))?;
+        Ok(value)
+    }
+
+    /// Reads the floating-point value of `key`.
+    fn read_float(&mut self, key: &str) -> Result<f32, Error> {
+        let mut value = 0.0;
+        self.read_float_array(key, core::slice::from_mut(&mut value))?;
+        Ok(value)
+    }
+
+    /// Reads the boolean (`true` or `false`) value of `key`.
+    fn read_bool(&mut self, key: &str) -> Result<bool, Error> {
+        let mut value = false;
+        self.read_bool_array(key, core::slice::from_mut(&mut value))?;
+        Ok(value)
+    }
+
+    /// Writes an unsigned integer value.
+    fn write_u32(&mut self, key: &str, value: u32) -> Result<(), Error> {
+        self.write_u32_array(key, &[value])
+    }
+
+    /// Writes a signed integer value.
+    fn write_i32(&mut self, key: &str, value: i32) -> Result<(), Error> {
+        self.write_i32_array(key, &[value])
+    }
+
+    /// Writes a floating-point value.
+    fn write_float(&mut self, key: &str, value: f32) -> Result<(), Error> {
+        self.write_float_array(key, &[value])
+    }
+
+    /// Writes a boolean value.
+    fn write_bool(&mut self, key: &str, value: bool) -> Result<(), Error> {
+        self.write_bool_array(key, &[value])
+    }
+
+    /// Reads an array that is split over consecutive lines with the same key, such as
+    /// `RAW_Data` in `.sub` files.
+    ///
+    /// The values of every remaining occurrence of `key` are concatenated. At least one
+    /// occurrence must exist.
+    fn read_multiline<T>(&mut self, key: &str) -> Result<Vec<T>, Error>
+    where
+        Self: Sized,
+        Vec<T>: Value,
+    {
+        let mut values = Vec::<T>::read(self, key)?;
+        loop {
+            match Vec::<T>::read(self, key) {
+                Ok(more) => values.extend(more),
+                Err(Error::MissingKey(_)) => return Ok(values),
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Writes an array as consecutive lines with the same key, with at most `per_line`
+    /// values on each line.
+    ///
+    /// An empty array is written as a single key with no values.
+    fn write_multiline<T: Clone>(
+        &mut self,
+        key: &str,
+        values: &[T],
+        per_line: usize,
+    ) -> Result<(), Error>
+    where
+        Self: Sized,
+        Vec<T>: Value,
+    {
+        if values.is_empty() {
+            return Vec::<T>::new().write(self, key);
+        }
+
+        for chunk in values.chunks(per_line.max(1)) {
+            chunk.to_vec().write(self, key)?;
+        }
+        Ok(())
+    }
+}
+
+/// Types that can be loaded from a Flipper Format file.
+///
+/// Usually implemented with `#[derive(FlipperFormat)]`.
+pub trait Load: Sized {
+    /// Loads `Self` from the start of `backend`.
+    fn load<B: Backend + ?Sized>(backend: &mut B) -> Result<Self, Error>;
+}
+
+/// Types that can be saved to a Flipper Format file.
+///
+/// Usually implemented with `#[derive(FlipperFormat)]`.
+pub trait Save {
+    /// Writes `self` at the current position of `backend`, which should be empty.
+    fn save<B: Backend + ?Sized>(&self, backend: &mut B) -> Result<(), Error>;
+}
+
+#[doc(hidden)]
+pub mod __macro_support {
+    use alloc::string::{String, ToString};
+
+    use crate::{Backend, Error};
+
+    /// Reads the header and checks it against the expected filetype and version.
+    ///
+    /// Returns the version found in the file.
+    pub fn read_header<B: Backend + ?Sized>(
+        backend: &mut B,
+        filetype: &str,
+        version: u32,
+        allow_older: bool,
+    ) -> Result<u32, Error> {
+        let (found_filetype, found_version) = backend.read_header()?;
+
+        if found_filetype != filetype {
+            return Err(Error::Filetype {
+                expected: filetype.to_string(),
+                found: found_filetype,
+            });
+        }
+
+        if found_version == version || (allow_older && found_version < version) {
+            Ok(found_version)
+        } else {
+            Err(Error::Version {
+                expected: version,
+                found: found_version,
+            })
+        }
+    }
+
+    pub fn invalid_value(key: &str) -> Error {
+        Error::InvalidValue(String::from(key))
+    }
+}
@@ -0,0 +1,475 @@
+use alloc::borrow::ToOwned;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::{self, Write};
+use core::str::FromStr;
+
+use crate::{Backend, Error};
+
+/// In-memory Flipper Format.
+///
+/// Mirrors the API of the on-device `flipperzero::flipper_format::FlipperFormat`, with
+/// `&str` keys and [`String`] values. Reads search for the key starting at the current
+/// position; writes insert at the current position.
+///
+/// Text is loaded with [`FlipperFormat::parse`] (or [`str::parse`]) and produced with
+/// [`Display`](fmt::Display), so files can be inspected and generated on a PC:
+///
+/// ```
+/// use flipperzero_format::FlipperFormat;
+///
+/// let text = "\
+/// Filetype: IR signals file
+/// Version: 1
+/// #
+/// name: Power
+/// type: parsed
+/// protocol: NEC
+/// address: 04 00 00 00
+/// command: 08 00 00 00
+/// ";
+///
+/// let mut ff = FlipperFormat::parse(text).unwrap();
+/// assert_eq!(ff.read_header().unwrap(), ("IR signals file".into(), 1));
+///
+/// let mut command = [0; 4];
+/// ff.read_hex("command", &mut command).unwrap();
+/// assert_eq!(command, [0x08, 0, 0, 0]);
+///
+/// assert_eq!(ff.to_string(), text);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FlipperFormat {
+    lines: Vec<Line>,
+    position: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Line {
+    Pair { key: String, value: String },
+    Comment(String),
+    Empty,
+}
+
+impl FlipperFormat {
+    /// Creates an empty Flipper Format.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses Flipper Format text.
+    ///
+    /// Each line is a `Key: value` pair, a `#` comment or empty. Keys may contain spaces
+    /// and may be repeated; reads return the next occurrence after the current position.
+    /// Both `\n` and `\r\n` line endings are accepted.
+    ///
+    /// Values are kept as text and only parsed when read, using the firmware's formats:
+    /// decimal integers with an optional sign, floats in any form accepted by `strtof`
+    /// except hexadecimal, `true`/`false`, and space-separated two-digit hex bytes.
+    ///
+    /// The read/write position is left at the start.
+    pub fn parse(text: &str) -> Result<Self, Error> {
+        let lines = text
+            .lines()
+            .enumerate()
+            .map(|(i, line)| Line::parse(line).ok_or(Error::Parse { line: i + 1 }))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { lines, position: 0 })
+    }
+
+    /// Iterates over all keys and their (unparsed) values, in file order.
+    ///
+    /// Comments and empty lines are skipped. Repeated keys are returned once per
+    /// occurrence.
+    pub fn entries(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.lines.iter().filter_map(|line| match line {
+            Line::Pair { key, value } => Some((key.as_str(), value.as_str())),
+            _ => None,
+        })
+    }
+
+    /// Moves the read/write position back to the start.
+    pub fn rewind(&mut self) -> Result<(), Error> {
+        self.position = 0;
+        Ok(())
+    }
+
+    /// Moves the read/write position to the end.
+    pub fn seek_to_end(&mut self) -> Result<(), Error> {
+        self.position = self.lines.len();
+        Ok(())
+    }
+
+    /// Checks whether `key` exists anywhere.
+    ///
+    /// The read/write position is left unchanged.
+    pub fn key_exists(&self, key: &str) -> bool {
+        self.lines.iter().any(|line| line.has_key(key))
+    }
+
+    /// Counts the values stored under the next occurrence of `key`.
+    pub fn value_count(&self, key: &str) -> Result<usize, Error> {
+        let index = self.find(key)?;
+        Ok(self.value(index).split_ascii_whitespace().count())
+    }
+
+    /// Reads the `Filetype` and `Version` header.
+    pub fn read_header(&mut self) -> Result<(String, u32), Error> {
+        let filetype = self.read_string("Filetype")?;
+        let version = self.read_u32("Version")?;
+
+        Ok((filetype, version))
+    }
+
+    /// Writes the `Filetype` and `Version` header.
+    pub fn write_header(&mut self, filetype: &str, version: u32) -> Result<(), Error> {
+        self.write_string("Filetype", filetype)?;
+        self.write_u32("Version", version)
+    }
+
+    /// Reads the string value of `key`.
+    pub fn read_string(&mut self, key: &str) -> Result<String, Error> {
+        let index = self.find(key)?;
+        self.position = index + 1;
+
+        Ok(self.value(index).to_owned())
+    }
+
+    /// Reads the unsigned integer value of `key`.
+    pub fn read_u32(&mut self, key: &str) -> Result<u32, Error> {
+        let mut value = 0;
+        self.read_u32_array(key, core::slice::from_mut(&mut value))?;
+        Ok(value)
+    }
+
+    /// Reads `values.len()` unsigned integer values of `key`.
+    pub fn read_u32_array(&mut self, key: &str, values: &mut [u32]) -> Result<(), Error> {
+        self.read_values(key, values, parse_u32)
+    }
+
+    /// Reads the signed integer value of `key`.
+    pub fn read_i32(&mut self, key: &str) -> Result<i32, Error> {
+        let mut value = 0;
+        self.read_i32_array(key, core::slice::from_mut(&mut value))?;
+        Ok(value)
+    }
+
+    /// Reads `values.len()` signed integer values of `key`.
+    pub fn read_i32_array(&mut self, key: &str, values: &mut [i32]) -> Result<(), Error> {
+        self.read_values(key, values, parse_i32)
+    }
+
+    /// Reads the floating-point value of `key`.
+    pub fn read_float(&mut self, key: &str) -> Result<f32, Error> {
+        let mut value = 0.0;
+        self.read_float_array(key, core::slice::from_mut(&mut value))?;
+        Ok(value)
+    }
+
+    /// Reads `values.len()` floating-point values of `key`.
+    pub fn read_float_array(&mut self, key: &str, values: &mut [f32]) -> Result<(), Error> {
+        self.read_values(key, values, parse_float)
+    }
+
+    /// Reads the boolean (`true` or `false`) value of `key`.
+    pub fn read_bool(&mut self, key: &str) -> Result<bool, Error> {
+        let mut value = false;
+        self.read_bool_array(key, core::slice::from_mut(&mut value))?;
+        Ok(value)
+    }
+
+    /// Reads `values.len()` boolean values of `key`.
+    pub fn read_bool_array(&mut self, key: &str, values: &mut [bool]) -> Result<(), Error> {
+        self.read_values(key, values, parse_bool)
+    }
+
+    /// Reads `bytes.len()` hex-encoded bytes (e.g. `DE AD BE EF`) of `key`.
+    pub fn read_hex(&mut self, key: &str, bytes: &mut [u8]) -> Result<(), Error> {
+        self.read_values(key, bytes, parse_hex)
+    }
+
+    /// Writes a string value.
+    pub fn write_string(&mut self, key: &str, value: &str) -> Result<(), Error> {
+        self.insert(Line::Pair {
+            key: key.to_owned(),
+            value: value.to_owned(),
+        });
+        Ok(())
+    }
+
+    /// Writes an unsigned integer value.
+    pub fn write_u32(&mut self, key: &str, value: u32) -> Result<(), Error> {
+        self.write_u32_array(key, &[value])
+    }
+
+    /// Writes an array of unsigned integer values.
+    pub fn write_u32_array(&mut self, key: &str, values: &[u32]) -> Result<(), Error> {
+        self.write_values(key, values, |s, v| write!(s, "{}", v))
+    }
+
+    /// Writes a signed integer value.
+    pub fn write_i32(&mut self, key: &str, value: i32) -> Result<(), Error> {
+        self.write_i32_array(key, &[value])
+    }
+
+    /// Writes an array of signed integer values.
+    pub fn write_i32_array(&mut self, key: &str, values: &[i32]) -> Result<(), Error> {
+        self.write_values(key, values, |s, v| write!(s, "{}", v))
+    }
+
+    /// Writes a floating-point value.
+    pub fn write_float(&mut self, key: &str, value: f32) -> Result<(), Error> {
+        self.write_float_array(key, &[value])
+    }
+
+    /// Writes an array of floating-point values.
+    ///
+    /// Like the firmware, values are written with six decimal places.
+    pub fn write_float_array(&mut self, key: &str, values: &[f32]) -> Result<(), Error> {
+        self.write_values(key, values, |s, v| write!(s, "{:.6}", v))
+    }
+
+    /// Writes a boolean value.
+    pub fn write_bool(&mut self, key: &str, value: bool) -> Result<(), Error> {
+        self.write_bool_array(key, &[value])
+    }
+
+    /// Writes an array of boolean values.
+    pub fn write_bool_array(&mut self, key: &str, values: &[bool]) -> Result<(), Error> {
+        self.write_values(key, values, |s, v| write!(s, "{}", v))
+    }
+
+    /// Writes bytes as hex (e.g. `DE AD BE EF`).
+    pub fn write_hex(&mut self, key: &str, bytes: &[u8]) -> Result<(), Error> {
+        self.write_values(key, bytes, |s, v| write!(s, "{:02X}", v))
+    }
+
+    /// Writes a `# comment` line.
+    pub fn write_comment(&mut self, comment: &str) -> Result<(), Error> {
+        self.insert(Line::Comment(comment.to_owned()));
+        Ok(())
+    }
+
+    /// Writes an empty line.
+    pub fn write_empty_line(&mut self) -> Result<(), Error> {
+        self.insert(Line::Empty);
+        Ok(())
+    }
+
+    /// Removes the next occurrence of `key` and its value.
+    ///
+    /// The read/write position is left where the deleted line was.
+    pub fn delete_key(&mut self, key: &str) -> Result<(), Error> {
+        let index = self.find(key)?;
+        self.lines.remove(index);
+        self.position = index;
+
+        Ok(())
+    }
+
+    /// Index of the next line holding `key`.
+    fn find(&self, key: &str) -> Result<usize, Error> {
+        self.lines[self.position..]
+            .iter()
+            .position(|line| line.has_key(key))
+            .map(|offset| self.position + offset)
+            .ok_or_else(|| Error::MissingKey(key.to_string()))
+    }
+
+    fn value(&self, index: usize) -> &str {
+        match &self.lines[index] {
+            Line::Pair { value, .. } => value,
+            _ => unreachable!("`find` only returns key-value lines"),
+        }
+    }
+
+    fn read_values<T>(
+        &mut self,
+        key: &str,
+        values: &mut [T],
+        parse: fn(&str) -> Option<T>,
+    ) -> Result<(), Error> {
+        let index = self.find(key)?;
+        self.position = index + 1;
+
+        let mut tokens = self.value(index).split_ascii_whitespace();
+        for value in values {
+            *value = tokens
+                .next()
+                .and_then(parse)
+                .ok_or_else(|| Error::InvalidValue(key.to_string()))?;
+        }
+
+        Ok(())
+    }
+
+    fn write_values<T>(
+        &mut self,
+        key: &str,
+        values: &[T],
+        format: impl Fn(&mut String, &T) -> core::fmt::Result,
+    ) -> Result<(), Error> {
+        let mut value = String::new();
+        for (i, v) in values.iter().enumerate() {
+            if i > 0 {
+                value.push(' ');
+            }
+            format(&mut value, v).map_err(|_| Error::Write(key.to_string()))?;
+        }
+
+        self.write_string(key, &value)
+    }
+
+    fn insert(&mut self, line: Line) {
+        self.lines.insert(self.position, line);
+        self.position += 1;
+    }
+}
+
+impl FromStr for FlipperFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for FlipperFormat {
+    /// Formats the contents as Flipper Format text, one line per key, comment or empty
+    /// line, each terminated by `\n`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for line in &self.lines {
+            match line {
+                Line::Pair { key, value } if value.is_empty() => writeln!(f, "{}:", key)?,
+                Line::Pair { key, value } => writeln!(f, "{}: {}", key, value)?,
+                Line::Comment(comment) if comment.is_empty() => writeln!(f, "#")?,
+                Line::Comment(comment) => writeln!(f, "# {}", comment)?,
+                Line::Empty => writeln!(f)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Line {
+    fn parse(line: &str) -> Option<Self> {
+        let line = line.trim_end_matches('\r');
+
+        if line.trim().is_empty() {
+            Some(Line::Empty)
+        } else if let Some(comment) = line.trim_start().strip_prefix('#') {
+            Some(Line::Comment(comment.trim().to_owned()))
+        } else {
+            let (key, value) = line.split_once(':')?;
+            let key = key.trim();
+            if key.is_empty() {
+                return None;
+            }
+
+            Some(Line::Pair {
+                key: key.to_owned(),
+                value: value.trim_start_matches([' ', '\t']).to_owned(),
+            })
+        }
+    }
+
+    fn has_key(&self, key: &str) -> bool {
+        matches!(self, Line::Pair { key: k, .. } if k == key)
+    }
+}
+
+fn parse_u32(s: &str) -> Option<u32> {
+    s.parse().ok()
+}
+
+fn parse_i32(s: &str) -> Option<i32> {
+    s.parse().ok()
+}
+
+fn parse_float(s: &str) -> Option<f32> {
+    s.parse().ok()
+}
+
+fn parse_bool(s: &str) -> Option<bool> {
+    match s {
+        "true" => Some(true),
+        "false" => Some(false),
+        _ => None,
+    }
+}
+
+fn parse_hex(s: &str) -> Option<u8> {
+    // `from_str_radix` would also accept a leading `+`.
+    if s.len() == 2 && s.bytes().all(|b| b.is_ascii_hexdigit()) {
+        u8::from_str_radix(s, 16).ok()
+    } else {
+        None
+    }
+}
+
+impl Backend for FlipperFormat {
+    fn rewind(&mut self) -> Result<(), Error> {
+        FlipperFormat::rewind(self)
+    }
+
+    fn key_exists(&mut self, key: &str) -> bool {
+        FlipperFormat::key_exists(self, key)
+    }
+
+    fn value_count(&mut self, key: &str) -> Result<usize, Error> {
+        FlipperFormat::value_count(self, key)
+    }
+
+    fn read_string(&mut self, key: &str) -> Result<String, Error> {
+        FlipperFormat::read_string(self, key)
+    }
+
+    fn read_u32_array(&mut self, key: &str, values: &mut [u32]) -> Result<(), Error> {
+        FlipperFormat::read_u32_array(self, key, values)
+    }
+
+    fn read_i32_array(&mut self, key: &str, values: &mut [i32]) -> Result<(), Error> {
+        FlipperFormat::read_i32_array(self, key, values)
+    }
+
+    fn read_float_array(&mut self, key: &str, values: &mut [f32]) -> Result<(), Error> {
+        FlipperFormat::read_float_array(self, key, values)
+    }
+
+    fn read_bool_array(&mut self, key: &str, values: &mut [bool]) -> Result<(), Error> {
+        FlipperFormat::read_bool_array(self, key, values)
+    }
+
+    fn read_hex(&mut self, key: &str, bytes: &mut [u8]) -> Result<(), Error> {
+        FlipperFormat::read_hex(self, key, bytes)
+    }
+
+    fn write_string(&mut self, key: &str, value: &str) -> Result<(), Error> {
+        FlipperFormat::write_string(self, key, value)
+    }
+
+    fn write_u32_array(&mut self, key: &str, values: &[u32]) -> Result<(), Error> {
+        FlipperFormat::write_u32_array(self, key, values)
+    }
+
+    fn write_i32_array(&mut self, key: &str, values: &[i32]) -> Result<(), Error> {
+        FlipperFormat::write_i32_array(self, key, values)
+    }
+
+    fn write_float_array(&mut self, key: &str, values: &[f32]) -> Result<(), Error> {
+        FlipperFormat::write_float_array(self, key, values)
+    }
+
+    fn write_bool_array(&mut self, key: &str, values: &[bool]) -> Result<(), Error> {
+        FlipperFormat::write_bool_array(self, key, values)
+    }
+
+    fn write_hex(&mut self, key: &str, bytes: &[u8]) -> Result<(), Error> {
+        FlipperFormat::write_hex(self, key, bytes)
+    }
+
+    fn delete_key(&mut self, key: &str) -> Result<(), Error> {
+        FlipperFormat::delete_key(self, key)
+    }
+}
@@ -0,0 +1,117 @@
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::{Backend, Error};
+
+/// Types that can be stored as the value of a single key.
+///
+/// Implemented for strings, integers, floats, booleans, byte arrays (stored as hex),
+/// arrays and `Vec`s of numbers and booleans, and `Option`s of all of these. Missing
+/// `Option` keys read as `None`, and `None` values are not written.
+///
+/// `#[derive(FlipperFormat)]` implements this for unit-only enums, which are stored as
+/// the variant name.
+pub trait Value: Sized {
+    /// Reads the value of `key`.
+    fn read<B: Backend + ?Sized>(backend: &mut B, key: &str) -> Result<Self, Error>;
+
+    /// Writes the value under `key`.
+    fn write<B: Backend + ?Sized>(&self, backend: &mut B, key: &str) -> Result<(), Error>;
+}
+
+impl Value for String {
+    fn read<B: Backend + ?Sized>(backend: &mut B, key: &str) -> Result<Self, Error> {
+        backend.read_string(key)
+    }
+
+    fn write<B: Backend + ?Sized>(&self, backend: &mut B, key: &str) -> Result<(), Error> {
+        backend.write_string(key, self)
+    }
+}
+
+macro_rules! impl_value {
+    ($ty:ty, $default:expr, $read:ident, $write:ident) => {
+        impl Value for $ty {
+            fn read<B: Backend + ?Sized>(backend: &mut B, key: &str) -> Result<Self, Error> {
+                let mut value = $default;
+                backend.$read(key, core::slice::from_mut(&mut value))?;
+                Ok(value)
+            }
+
+            fn write<B: Backend + ?Sized>(&self, backend: &mut B, key: &str) -> Result<(), Error> {
+                backend.$write(key, core::slice::from_ref(self))
+            }
+        }
+
+        impl<const N: usize> Value for [$ty; N] {
+            fn read<B: Backend + ?Sized>(backend: &mut B, key: &str) -> Result<Self, Error> {
+                let mut values = [$default; N];
+                backend.$read(key, &mut values)?;
+                Ok(values)
+            }
+
+            fn write<B: Backend + ?Sized>(&self, backend: &mut B, key: &str) -> Result<(), Error> {
+                backend.$write(key, self)
+            }
+        }
+
+        impl Value for Vec<$ty> {
+            fn read<B: Backend + ?Sized>(backend: &mut B, key: &str) -> Result<Self, Error> {
+                let mut values = vec![$default; backend.value_count(key)?];
+                backend.$read(key, &mut values)?;
+                Ok(values)
+            }
+
+            fn write<B: Backend + ?Sized>(&self, backend: &mut B, key: &str) -> Result<(), Error> {
+                backend.$write(key, self)
+            }
+        }
+    };
+}
+
+impl_value!(u32, 0, read_u32_array, write_u32_array);
+impl_value!(i32, 0, read_i32_array, write_i32_array);
+impl_value!(f32, 0.0, read_float_array, write_float_array);
+impl_value!(bool, false, read_bool_array, write_bool_array);
+
+impl<const N: usize> Value for [u8; N] {
+    fn read<B: Backend + ?Sized>(backend: &mut B, key: &str) -> Result<Self, Error> {
+        let mut bytes = [0; N];
+        backend.read_hex(key, &mut bytes)?;
+        Ok(bytes)
+    }
+
+    fn write<B: Backend + ?Sized>(&self, backend: &mut B, key: &str) -> Result<(), Error> {
+        backend.write_hex(key, self)
+    }
+}
+
+impl Value for Vec<u8> {
+    fn read<B: Backend + ?Sized>(backend: &mut B, key: &str) -> Result<Self, Error> {
+        let mut bytes = vec![0; backend.value_count(key)?];
+        backend.read_hex(key, &mut bytes)?;
+        Ok(bytes)
+    }
+
+    fn write<B: Backend + ?Sized>(&self, backend: &mut B, key: &str) -> Result<(), Error> {
+        backend.write_hex(key, self)
+    }
+}
+
+impl<T: Value> Value for Option<T> {
+    fn read<B: Backend + ?Sized>(backend: &mut B, key: &str) -> Result<Self, Error> {
+        if backend.key_exists(key) {
+            T::read(backend, key).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn write<B: Backend + ?Sized>(&self, backend: &mut B, key: &str) -> Result<(), Error> {
+        match self {
+            Some(value) => value.write(backend, key),
+            None => Ok(()),
+        }
+    }
+}
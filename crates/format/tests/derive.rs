@@ -0,0 +1,260 @@
+use flipperzero_format::{Error, FlipperFormat, Load, Save, Value};
+
+#[derive(FlipperFormat, Debug, Clone, Copy, PartialEq)]
+enum Preset {
+    #[flipper_format(rename = "FuriHalSubGhzPresetOok650Async")]
+    Ook650,
+    #[flipper_format(rename = "FuriHalSubGhzPreset2FSKDev238Async")]
+    Fsk238,
+    Custom,
+}
+
+#[derive(FlipperFormat, Debug, PartialEq)]
+#[flipper_format(filetype = "Flipper SubGhz Key File", version = 1)]
+struct Capture {
+    #[flipper_format(rename = "Frequency")]
+    frequency: u32,
+    #[flipper_format(rename = "Preset")]
+    preset: Preset,
+    #[flipper_format(rename = "Protocol")]
+    protocol: String,
+    #[flipper_format(rename = "Bit")]
+    bits: u32,
+    #[flipper_format(rename = "Key")]
+    key: [u8; 8],
+    #[flipper_format(rename = "TE")]
+    te: Option<u32>,
+    #[flipper_format(skip)]
+    dirty: bool,
+}
+
+#[derive(FlipperFormat, Debug, PartialEq)]
+struct Plain {
+    name: String,
+    offset: i32,
+    gain: f32,
+    enabled: bool,
+    data: Vec<u8>,
+    timings: Vec<i32>,
+    weights: [f32; 2],
+    flags: Vec<bool>,
+}
+
+#[derive(FlipperFormat, Debug, PartialEq)]
+#[flipper_format(filetype = "Rust Settings", version = 3, migrate = "Settings::migrate")]
+struct Settings {
+    #[flipper_format(rename = "Volume")]
+    volume: u32,
+    #[flipper_format(rename = "Dark Mode", default)]
+    dark_mode: bool,
+}
+
+impl Settings {
+    fn migrate(&mut self, version: u32) -> Result<(), Error> {
+        match version {
+            // Version 2 stored the volume in percent.
+            2 => self.volume = self.volume * 255 / 100,
+            // Version 1 stored the volume as 0-10.
+            1 => {
+                return Err(Error::Version {
+                    expected: 3,
+                    found: 1,
+                })
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+fn capture() -> Capture {
+    Capture {
+        frequency: 433_920_000,
+        preset: Preset::Ook650,
+        protocol: "Princeton".into(),
+        bits: 24,
+        key: [0x00, 0x00, 0x00, 0x00, 0x00, 0x95, 0xD5, 0xD4],
+        te: Some(400),
+        dirty: false,
+    }
+}
+
+#[test]
+fn round_trip() {
+    let mut ff = FlipperFormat::new();
+    capture().save(&mut ff).unwrap();
+
+    ff.rewind().unwrap();
+    assert_eq!(
+        ff.read_header().unwrap(),
+        ("Flipper SubGhz Key File".into(), 1)
+    );
+    assert_eq!(
+        ff.read_string("Preset").unwrap(),
+        "FuriHalSubGhzPresetOok650Async"
+    );
+    assert_eq!(ff.read_string("Key").unwrap(), "00 00 00 00 00 95 D5 D4");
+
+    assert_eq!(Capture::load(&mut ff).unwrap(), capture());
+}
+
+#[test]
+fn field_order_does_not_matter() {
+    let mut ff = FlipperFormat::new();
+    ff.write_header("Flipper SubGhz Key File", 1).unwrap();
+    ff.write_hex("Key", &capture().key).unwrap();
+    ff.write_u32("Bit", 24).unwrap();
+    ff.write_string("Protocol", "Princeton").unwrap();
+    ff.write_string("Preset", "FuriHalSubGhzPresetOok650Async")
+        .unwrap();
+    ff.write_u32("Frequency", 433_920_000).unwrap();
+    ff.write_u32("TE", 400).unwrap();
+
+    assert_eq!(Capture::load(&mut ff).unwrap(), capture());
+}
+
+#[test]
+fn option_fields() {
+    let without_te = Capture {
+        te: None,
+        ..capture()
+    };
+
+    let mut ff = FlipperFormat::new();
+    without_te.save(&mut ff).unwrap();
+    assert!(!ff.key_exists("TE"));
+
+    assert_eq!(Capture::load(&mut ff).unwrap(), without_te);
+}
+
+#[test]
+fn skipped_fields_use_default() {
+    let mut dirty = capture();
+    dirty.dirty = true;
+
+    let mut ff = FlipperFormat::new();
+    dirty.save(&mut ff).unwrap();
+    assert!(!ff.key_exists("dirty"));
+
+    assert!(!Capture::load(&mut ff).unwrap().dirty);
+}
+
+#[test]
+fn arrays_and_field_names() {
+    let plain = Plain {
+        name: "test".into(),
+        offset: -5,
+        gain: 1.5,
+        enabled: true,
+        data: vec![0xCA, 0xFE],
+        timings: vec![300, -900, 300, -300],
+        weights: [0.25, 0.75],
+        flags: vec![true, false, true],
+    };
+
+    let mut ff = FlipperFormat::new();
+    plain.save(&mut ff).unwrap();
+
+    ff.rewind().unwrap();
+    assert_eq!(ff.read_string("timings").unwrap(), "300 -900 300 -300");
+    assert_eq!(ff.read_string("weights").unwrap(), "0.250000 0.750000");
+
+    assert_eq!(Plain::load(&mut ff).unwrap(), plain);
+}
+
+#[test]
+fn errors_name_the_key() {
+    let mut ff = FlipperFormat::new();
+    ff.write_header("Flipper SubGhz Key File", 1).unwrap();
+    ff.write_u32("Frequency", 433_920_000).unwrap();
+    assert_eq!(
+        Capture::load(&mut ff),
+        Err(Error::MissingKey("Preset".into()))
+    );
+
+    ff.write_string("Preset", "Unknown").unwrap();
+    assert_eq!(
+        Capture::load(&mut ff),
+        Err(Error::InvalidValue("Preset".into()))
+    );
+
+    let mut ff = FlipperFormat::new();
+    ff.write_string("name", "test").unwrap();
+    ff.write_string("offset", "minus five").unwrap();
+    assert_eq!(
+        Plain::load(&mut ff),
+        Err(Error::InvalidValue("offset".into()))
+    );
+}
+
+#[test]
+fn header_is_checked() {
+    let mut ff = FlipperFormat::new();
+    ff.write_header("Flipper RFID key", 1).unwrap();
+    assert_eq!(
+        Capture::load(&mut ff),
+        Err(Error::Filetype {
+            expected: "Flipper SubGhz Key File".into(),
+            found: "Flipper RFID key".into(),
+        })
+    );
+
+    let mut ff = FlipperFormat::new();
+    ff.write_header("Flipper SubGhz Key File", 2).unwrap();
+    assert_eq!(
+        Capture::load(&mut ff),
+        Err(Error::Version {
+            expected: 1,
+            found: 2
+        })
+    );
+}
+
+#[test]
+fn migration() {
+    let mut ff = FlipperFormat::new();
+    ff.write_header("Rust Settings", 2).unwrap();
+    ff.write_u32("Volume", 100).unwrap();
+    assert_eq!(
+        Settings::load(&mut ff).unwrap(),
+        Settings {
+            volume: 255,
+            dark_mode: false,
+        }
+    );
+
+    let mut ff = FlipperFormat::new();
+    ff.write_header("Rust Settings", 1).unwrap();
+    ff.write_u32("Volume", 10).unwrap();
+    assert_eq!(
+        Settings::load(&mut ff),
+        Err(Error::Version {
+            expected: 3,
+            found: 1
+        })
+    );
+
+    // Newer versions are never accepted.
+    let mut ff = FlipperFormat::new();
+    ff.write_header("Rust Settings", 4).unwrap();
+    ff.write_u32("Volume", 10).unwrap();
+    assert_eq!(
+        Settings::load(&mut ff),
+        Err(Error::Version {
+            expected: 3,
+            found: 4
+        })
+    );
+}
+
+#[test]
+fn enum_values() {
+    let mut ff = FlipperFormat::new();
+    Preset::Custom.write(&mut ff, "Preset").unwrap();
+    Preset::Fsk238.write(&mut ff, "Other").unwrap();
+
+    ff.rewind().unwrap();
+    assert_eq!(ff.read_string("Preset").unwrap(), "Custom");
+    ff.rewind().unwrap();
+    assert_eq!(Preset::read(&mut ff, "Other").unwrap(), Preset::Fsk238);
+}
@@ -0,0 +1,173 @@
+use flipperzero_format::{Backend, Error, FlipperFormat};
+
+const SUB: &str = "\
+Filetype: Flipper SubGhz RAW File
+Version: 1
+Frequency: 433920000
+Preset: FuriHalSubGhzPresetOok650Async
+Protocol: RAW
+RAW_Data: 100 -200 300
+RAW_Data: -400 500
+RAW_Data: -600
+";
+
+#[test]
+fn parse_and_display_round_trip() {
+    let text = "\
+Filetype: Flipper NFC device
+Version: 4
+# Device type can be ISO14443-3A, ISO14443-3B, ...
+Device type: ISO14443-3A
+#
+UID: 04 85 92 8A A0 61 81
+
+Empty:
+";
+    let ff = FlipperFormat::parse(text).unwrap();
+    assert_eq!(ff.to_string(), text);
+
+    assert_eq!(
+        ff.entries().collect::<Vec<_>>(),
+        [
+            ("Filetype", "Flipper NFC device"),
+            ("Version", "4"),
+            ("Device type", "ISO14443-3A"),
+            ("UID", "04 85 92 8A A0 61 81"),
+            ("Empty", ""),
+        ]
+    );
+}
+
+#[test]
+fn parse_normalizes_whitespace() {
+    let mut ff: FlipperFormat = "Filetype:Test\r\nVersion:   2\r\n  #indented comment\r\n"
+        .parse()
+        .unwrap();
+    assert_eq!(ff.read_header().unwrap(), ("Test".into(), 2));
+    assert_eq!(
+        ff.to_string(),
+        "Filetype: Test\nVersion: 2\n# indented comment\n"
+    );
+}
+
+#[test]
+fn parse_errors_name_the_line() {
+    assert_eq!(
+        FlipperFormat::parse("Filetype: Test\nVersion: 1\nnot a pair\n"),
+        Err(Error::Parse { line: 3 })
+    );
+    assert_eq!(
+        FlipperFormat::parse(": no key"),
+        Err(Error::Parse { line: 1 })
+    );
+}
+
+#[test]
+fn duplicate_keys_are_read_in_order() {
+    let mut ff = FlipperFormat::parse(
+        "\
+name: Power
+type: parsed
+name: Vol_up
+type: raw
+name: Vol_dn
+",
+    )
+    .unwrap();
+
+    assert_eq!(ff.read_string("name").unwrap(), "Power");
+    assert_eq!(ff.read_string("type").unwrap(), "parsed");
+    assert_eq!(ff.read_string("name").unwrap(), "Vol_up");
+    assert_eq!(ff.read_string("name").unwrap(), "Vol_dn");
+    assert_eq!(
+        ff.read_string("name"),
+        Err(Error::MissingKey("name".into()))
+    );
+}
+
+#[test]
+fn multiline_arrays() {
+    let mut ff = FlipperFormat::parse(SUB).unwrap();
+    assert_eq!(ff.read_u32("Frequency").unwrap(), 433_920_000);
+    assert_eq!(
+        ff.read_multiline::<i32>("RAW_Data").unwrap(),
+        [100, -200, 300, -400, 500, -600]
+    );
+
+    let mut out = FlipperFormat::new();
+    out.write_multiline("RAW_Data", &[1, -2, 3, -4, 5], 2)
+        .unwrap();
+    out.write_multiline::<i32>("Empty", &[], 2).unwrap();
+    assert_eq!(
+        out.to_string(),
+        "RAW_Data: 1 -2\nRAW_Data: 3 -4\nRAW_Data: 5\nEmpty:\n"
+    );
+
+    out.rewind().unwrap();
+    assert_eq!(
+        out.read_multiline::<i32>("RAW_Data").unwrap(),
+        [1, -2, 3, -4, 5]
+    );
+    assert!(out.read_multiline::<i32>("Empty").unwrap().is_empty());
+    assert_eq!(
+        out.read_multiline::<i32>("RAW_Data"),
+        Err(Error::MissingKey("RAW_Data".into()))
+    );
+}
+
+#[test]
+fn number_formats() {
+    let mut ff = FlipperFormat::parse(
+        "\
+Unsigned: 0 +42 4294967295
+Signed: -2147483648 +7 2147483647
+Float: 1.5 -0.25 .5 1e3 2.000000
+Bool: true false
+Hex: 00 7f A0 ff
+",
+    )
+    .unwrap();
+
+    let mut unsigned = [0; 3];
+    ff.read_u32_array("Unsigned", &mut unsigned).unwrap();
+    assert_eq!(unsigned, [0, 42, u32::MAX]);
+
+    let mut signed = [0; 3];
+    ff.read_i32_array("Signed", &mut signed).unwrap();
+    assert_eq!(signed, [i32::MIN, 7, i32::MAX]);
+
+    let mut floats = [0.0; 5];
+    ff.read_float_array("Float", &mut floats).unwrap();
+    assert_eq!(floats, [1.5, -0.25, 0.5, 1000.0, 2.0]);
+
+    let mut bools = [false; 2];
+    ff.read_bool_array("Bool", &mut bools).unwrap();
+    assert_eq!(bools, [true, false]);
+
+    let mut bytes = [0; 4];
+    ff.read_hex("Hex", &mut bytes).unwrap();
+    assert_eq!(bytes, [0x00, 0x7F, 0xA0, 0xFF]);
+}
+
+#[test]
+fn invalid_numbers() {
+    for (key, value) in [
+        ("Unsigned", "-1"),
+        ("Unsigned", "4294967296"),
+        ("Unsigned", "0x10"),
+        ("Hex", "+F"),
+        ("Hex", "ABC"),
+        ("Bool", "TRUE"),
+    ] {
+        let mut ff = FlipperFormat::new();
+        ff.write_string(key, value).unwrap();
+        ff.rewind().unwrap();
+
+        let result = match key {
+            "Unsigned" => ff.read_u32(key).map(drop),
+            "Hex" => ff.read_hex(key, &mut [0]).map(drop),
+            _ => ff.read_bool(key).map(drop),
+        };
+        assert_eq!(result, Err(Error::InvalidValue(key.into())), "{value}");
+    }
+}
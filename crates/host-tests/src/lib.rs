@@ -0,0 +1,6 @@
+//! Host tests for the modules of the `flipperzero` crate that don't depend on the SDK.
+//!
+//! This library is empty: the tests in `tests/` include the modules they test with
+//! `#[path]`.
+
+#![no_std]
@@ -0,0 +1,266 @@
+//! The gesture recognizer of `flipperzero::gui::input`, which refers to the input types
+//! through `super`, so this crate root stands in for the `input` module.
+
+#[path = "../../flipperzero/src/gui/input/gesture.rs"]
+mod gesture;
+
+use gesture::{Gesture, GestureRecognizer, Keys};
+
+/// Stand-in for `flipperzero::gui::input::Key`, which depends on the SDK.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Key {
+    Up,
+    Down,
+    Right,
+    Left,
+    Ok,
+    Back,
+}
+
+/// Stand-in for `flipperzero::gui::input::InputType`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InputType {
+    Press,
+    Release,
+    Short,
+    Long,
+    Repeat,
+}
+
+/// Stand-in for `flipperzero::gui::input::InputEvent`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InputEvent {
+    pub key: Key,
+    pub kind: InputType,
+    pub sequence: u32,
+}
+
+/// Feeds `events` to `recognizer`, ticking every 10 ms until `end`, and returns the
+/// gestures recognized with the time they were reported.
+fn run(
+    recognizer: &mut GestureRecognizer,
+    events: &[(u32, Key, InputType)],
+    end: u32,
+) -> Vec<(u32, Gesture)> {
+    let mut gestures = Vec::new();
+    let mut events = events.iter().peekable();
+    for now in (0..=end).step_by(10) {
+        while let Some(&(at, key, kind)) = events.next_if(|(at, ..)| *at <= now) {
+            let event = InputEvent {
+                key,
+                kind,
+                sequence: 0,
+            };
+            recognizer.input(&event, at, |g| gestures.push((at, g)));
+        }
+        recognizer.tick(now, |g| gestures.push((now, g)));
+    }
+    gestures
+}
+
+/// Like `run`, without hold progress or times.
+fn gestures(recognizer: &mut GestureRecognizer, events: &[(u32, Key, InputType)]) -> Vec<Gesture> {
+    run(recognizer, events, 3000)
+        .into_iter()
+        .map(|(_, g)| g)
+        .filter(|g| !matches!(g, Gesture::HoldProgress { .. }))
+        .collect()
+}
+
+use InputType::{Long, Press, Release, Short};
+
+#[test]
+fn tap() {
+    let mut recognizer = GestureRecognizer::new();
+    let events = [
+        (100, Key::Ok, Press),
+        (180, Key::Ok, Short),
+        (180, Key::Ok, Release),
+    ];
+    let gestures = run(&mut recognizer, &events, 1000);
+    let taps: Vec<_> = gestures
+        .iter()
+        .filter(|(_, g)| !matches!(g, Gesture::HoldProgress { .. }))
+        .collect();
+    // Reported once the double tap window has passed.
+    assert_eq!(
+        taps,
+        [
+            &(180, Gesture::HoldCancelled(Key::Ok)),
+            &(430, Gesture::Tap(Key::Ok))
+        ]
+    );
+
+    // Without double taps, the tap is reported on release.
+    let mut recognizer = GestureRecognizer::new().double_tap_time(0);
+    let gestures = run(&mut recognizer, &events, 1000);
+    assert_eq!(gestures.last(), Some(&(180, Gesture::Tap(Key::Ok))));
+}
+
+#[test]
+fn double_tap() {
+    let mut recognizer = GestureRecognizer::new();
+    let events = [
+        (100, Key::Up, Press),
+        (150, Key::Up, Release),
+        (300, Key::Up, Press),
+        (350, Key::Up, Release),
+    ];
+    assert_eq!(
+        gestures(&mut recognizer, &events),
+        [
+            Gesture::HoldCancelled(Key::Up),
+            Gesture::HoldCancelled(Key::Up),
+            Gesture::DoubleTap(Key::Up)
+        ]
+    );
+
+    // Too slow, or different keys.
+    for events in [
+        [
+            (100, Key::Up, Press),
+            (150, Key::Up, Release),
+            (500, Key::Up, Press),
+            (550, Key::Up, Release),
+        ],
+        [
+            (100, Key::Up, Press),
+            (150, Key::Up, Release),
+            (200, Key::Down, Press),
+            (250, Key::Down, Release),
+        ],
+    ] {
+        let taps: Vec<_> = gestures(&mut recognizer, &events)
+            .into_iter()
+            .filter(|g| matches!(g, Gesture::Tap(_)))
+            .collect();
+        assert_eq!(taps, [Gesture::Tap(events[0].1), Gesture::Tap(events[2].1)]);
+    }
+}
+
+#[test]
+fn hold() {
+    let mut recognizer = GestureRecognizer::new().hold_time(500);
+    let events = [
+        (0, Key::Back, Press),
+        (300, Key::Back, Long),
+        (800, Key::Back, Release),
+    ];
+    let gestures = run(&mut recognizer, &events, 1000);
+
+    let progress: Vec<_> = gestures
+        .iter()
+        .filter_map(|(_, g)| match g {
+            Gesture::HoldProgress { key, percent } => {
+                assert_eq!(*key, Key::Back);
+                Some(*percent)
+            }
+            _ => None,
+        })
+        .collect();
+    assert_eq!(progress.len(), 49);
+    assert!(progress.windows(2).all(|w| w[0] < w[1]));
+    assert_eq!(progress.last(), Some(&98));
+
+    // Reported once, and the release is not.
+    assert_eq!(gestures.last(), Some(&(500, Gesture::Hold(Key::Back))));
+
+    // A double tap whose second press is held is a tap and a hold.
+    let events = [
+        (0, Key::Ok, Press),
+        (50, Key::Ok, Release),
+        (100, Key::Ok, Press),
+        (900, Key::Ok, Release),
+    ];
+    assert_eq!(
+        self::gestures(&mut recognizer, &events)[1..],
+        [Gesture::Tap(Key::Ok), Gesture::Hold(Key::Ok)]
+    );
+}
+
+#[test]
+fn chord() {
+    let mut recognizer = GestureRecognizer::new();
+    let events = [
+        (0, Key::Left, Press),
+        (40, Key::Right, Press),
+        (60, Key::Ok, Press),
+        (2000, Key::Left, Release),
+        (2010, Key::Ok, Release),
+        (2020, Key::Right, Release),
+    ];
+    let keys = Keys::new().with(Key::Left).with(Key::Right).with(Key::Ok);
+    assert_eq!(
+        gestures(&mut recognizer, &events),
+        [Gesture::HoldCancelled(Key::Left), Gesture::Chord(keys)]
+    );
+    assert_eq!(keys.len(), 3);
+    assert_eq!(
+        keys.iter().collect::<Vec<_>>(),
+        [Key::Right, Key::Left, Key::Ok]
+    );
+    assert!(!keys.contains(Key::Back));
+
+    // A key held past the hold time can start a chord.
+    let events = [
+        (0, Key::Back, Press),
+        (1500, Key::Ok, Press),
+        (1600, Key::Ok, Release),
+        (1700, Key::Back, Release),
+    ];
+    assert_eq!(
+        gestures(&mut recognizer, &events),
+        [
+            Gesture::Hold(Key::Back),
+            Gesture::Chord(Keys::from(Key::Back).with(Key::Ok))
+        ]
+    );
+
+    // A double tap whose second press starts a chord is a tap and a chord.
+    let events = [
+        (0, Key::Ok, Press),
+        (50, Key::Ok, Release),
+        (100, Key::Ok, Press),
+        (105, Key::Left, Press),
+        (200, Key::Left, Release),
+        (210, Key::Ok, Release),
+    ];
+    assert_eq!(
+        gestures(&mut recognizer, &events),
+        [
+            Gesture::HoldCancelled(Key::Ok),
+            Gesture::Tap(Key::Ok),
+            Gesture::Chord(Keys::from(Key::Ok).with(Key::Left))
+        ]
+    );
+}
+
+#[test]
+fn ignored_events() {
+    let mut recognizer = GestureRecognizer::new().double_tap_time(0);
+    let events = [
+        // Released before the recognizer saw the press.
+        (0, Key::Down, Release),
+        (100, Key::Up, Short),
+        (200, Key::Up, Press),
+        (250, Key::Down, Release),
+        (300, Key::Up, Release),
+    ];
+    assert_eq!(
+        gestures(&mut recognizer, &events),
+        [Gesture::HoldCancelled(Key::Up), Gesture::Tap(Key::Up)]
+    );
+
+    recognizer.input(
+        &InputEvent {
+            key: Key::Up,
+            kind: Press,
+            sequence: 0,
+        },
+        0,
+        |_| panic!(),
+    );
+    recognizer.reset();
+    recognizer.tick(5000, |_| panic!());
+}
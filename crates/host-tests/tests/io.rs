@@ -0,0 +1,334 @@
+//! The SDK-independent parts of `flipperzero::io`, which refer to each other through
+//! `super`, so this crate root stands in for the `io` module.
+
+#![allow(dead_code)]
+
+#[path = "../../flipperzero/src/io/buffered.rs"]
+mod buffered;
+#[path = "../../flipperzero/src/io/cursor.rs"]
+mod cursor;
+#[path = "../../flipperzero/src/io/traits.rs"]
+mod traits;
+#[path = "../../flipperzero/src/io/util.rs"]
+mod util;
+
+extern crate alloc;
+
+use buffered::{BufReader, BufWriter};
+use cursor::Cursor;
+use traits::{BufRead, Read, Seek, SeekFrom, Write};
+use util::{copy, copy_buf, Chain, Take};
+
+const DEFAULT_BUF_SIZE: usize = 512;
+
+/// Stand-in for `flipperzero::io::Error`, which depends on the SDK.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    InvalidParameter,
+    WriteZero,
+    UnexpectedEof,
+    InvalidData,
+}
+
+/// Stand-in for `flipperzero::furi::string`.
+mod furi {
+    pub mod string {
+        #[derive(Debug, Default)]
+        pub struct FuriString(pub String);
+
+        impl FuriString {
+            pub fn push_str(&mut self, string: &str) {
+                self.0.push_str(string);
+            }
+        }
+    }
+}
+
+use furi::string::FuriString;
+
+/// Reader that returns at most `chunk` bytes per read, like a slow device.
+struct Trickle<'a> {
+    data: &'a [u8],
+    chunk: usize,
+    reads: usize,
+}
+
+impl<'a> Trickle<'a> {
+    fn new(data: &'a [u8], chunk: usize) -> Self {
+        Self {
+            data,
+            chunk,
+            reads: 0,
+        }
+    }
+}
+
+impl Read for Trickle<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        self.reads += 1;
+        let n = buf.len().min(self.chunk);
+        self.data.read(&mut buf[..n])
+    }
+}
+
+/// Writer that records each call to `write`, accepting at most `limit` bytes in total.
+#[derive(Default)]
+struct Recorder {
+    writes: Vec<Vec<u8>>,
+    limit: Option<usize>,
+    flushed: usize,
+}
+
+impl Recorder {
+    fn data(&self) -> Vec<u8> {
+        self.writes.concat()
+    }
+}
+
+impl Write for Recorder {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        let written = self.writes.iter().map(Vec::len).sum::<usize>();
+        let n = match self.limit {
+            Some(limit) => buf.len().min(limit - written),
+            None => buf.len(),
+        };
+        if n > 0 {
+            self.writes.push(buf[..n].to_vec());
+        }
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> Result<(), Error> {
+        self.flushed += 1;
+        Ok(())
+    }
+}
+
+#[test]
+fn read_exact() {
+    let mut reader = Trickle::new(b"hello world", 3);
+    let mut buf = [0; 5];
+    reader.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"hello");
+    assert_eq!(reader.reads, 2);
+
+    let mut buf = [0; 10];
+    assert_eq!(reader.read_exact(&mut buf), Err(Error::UnexpectedEof));
+}
+
+#[test]
+fn read_to_end_and_string() {
+    let data: Vec<u8> = (0..2000u32).map(|i| i as u8).collect();
+    let mut buf = vec![1, 2, 3];
+    assert_eq!(Trickle::new(&data, 100).read_to_end(&mut buf), Ok(2000));
+    assert_eq!(&buf[..3], [1, 2, 3]);
+    assert_eq!(&buf[3..], data);
+
+    let mut string = String::from("> ");
+    assert_eq!(
+        Trickle::new(b"caf\xc3\xa9", 1).read_to_string(&mut string),
+        Ok(5)
+    );
+    assert_eq!(string, "> café");
+
+    assert_eq!(
+        b"\xff\xfe".as_slice().read_to_string(&mut string),
+        Err(Error::InvalidData)
+    );
+    assert_eq!(string, "> café");
+
+    let mut furi = FuriString::default();
+    assert_eq!(
+        b"Filetype: IR".as_slice().read_to_furi_string(&mut furi),
+        Ok(12)
+    );
+    assert_eq!(furi.0, "Filetype: IR");
+}
+
+#[test]
+fn cursor() {
+    let mut cursor = Cursor::new(b"0123456789".as_slice());
+    let mut buf = [0; 4];
+    assert_eq!(cursor.read(&mut buf), Ok(4));
+    assert_eq!(&buf, b"0123");
+    assert_eq!(cursor.position(), 4);
+
+    assert_eq!(cursor.seek(SeekFrom::End(-2)), Ok(8));
+    assert_eq!(cursor.remaining_slice(), b"89");
+    assert_eq!(cursor.seek(SeekFrom::Current(-3)), Ok(5));
+    assert_eq!(cursor.fill_buf(), Ok(b"56789".as_slice()));
+    assert_eq!(
+        cursor.seek(SeekFrom::Current(-6)),
+        Err(Error::InvalidParameter)
+    );
+    assert_eq!(cursor.seek(SeekFrom::Start(20)), Ok(20));
+    assert_eq!(cursor.read(&mut buf), Ok(0));
+    assert!(cursor.is_empty());
+    assert_eq!(cursor.stream_len(), Ok(10));
+
+    let mut storage = [0; 4];
+    let mut cursor = Cursor::new(&mut storage[..]);
+    assert_eq!(cursor.write_all(b"abcdef"), Err(Error::WriteZero));
+    assert_eq!(&storage, b"abcd");
+
+    let mut cursor = Cursor::new(Vec::new());
+    cursor.write_all(b"hello").unwrap();
+    cursor.set_position(1);
+    cursor.write_all(b"EL").unwrap();
+    cursor.seek(SeekFrom::End(2)).unwrap();
+    cursor.write_all(b"!").unwrap();
+    assert_eq!(cursor.into_inner(), b"hELlo\0\0!");
+}
+
+#[test]
+fn buf_reader() {
+    let data = b"line one\nline two\r\n\nlast";
+    let mut reader = BufReader::with_buffer([0; 8], Trickle::new(data, 64));
+    assert_eq!(reader.capacity(), 8);
+
+    let mut byte = [0];
+    reader.read_exact(&mut byte).unwrap();
+    assert_eq!(reader.buffer(), b"ine one");
+
+    let mut line = String::new();
+    assert_eq!(reader.read_line(&mut line), Ok(8));
+    assert_eq!(line, "ine one\n");
+
+    let lines: Vec<_> = reader.lines().map(Result::unwrap).collect();
+    assert_eq!(lines, ["line two", "", "last"]);
+
+    // Large reads bypass the empty buffer.
+    let mut reader = BufReader::with_capacity(4, Trickle::new(data, 64));
+    let mut buf = [0; 16];
+    assert_eq!(reader.read(&mut buf), Ok(16));
+    assert_eq!(reader.get_ref().reads, 1);
+    assert!(reader.buffer().is_empty());
+}
+
+#[test]
+fn buf_reader_seek() {
+    let mut reader = BufReader::with_capacity(4, Cursor::new(b"0123456789".as_slice()));
+    let mut byte = [0];
+    reader.read_exact(&mut byte).unwrap();
+    assert_eq!(reader.get_ref().position(), 4);
+
+    // Relative to the reader's logical position, not the underlying cursor's.
+    assert_eq!(reader.seek(SeekFrom::Current(2)), Ok(3));
+    reader.read_exact(&mut byte).unwrap();
+    assert_eq!(&byte, b"3");
+    assert_eq!(reader.stream_position(), Ok(4));
+}
+
+#[test]
+fn buf_writer() {
+    let mut writer = BufWriter::with_buffer([0; 8], Recorder::default());
+    writer.write_all(b"abc").unwrap();
+    writer.write_all(b"def").unwrap();
+    assert!(writer.get_ref().writes.is_empty());
+    assert_eq!(writer.buffer(), b"abcdef");
+
+    // Doesn't fit, so the buffer is written out first.
+    writer.write_all(b"ghi").unwrap();
+    assert_eq!(writer.get_ref().writes, [b"abcdef".to_vec()]);
+
+    // Too large to buffer, so it is written directly.
+    writer.write_all(b"0123456789").unwrap();
+    assert_eq!(writer.get_ref().data(), b"abcdefghi0123456789");
+
+    writer.write_all(b"x").unwrap();
+    writer.flush().unwrap();
+    assert_eq!(writer.get_ref().flushed, 1);
+
+    writer.write_all(b"yz").unwrap();
+    let recorder = writer.into_inner().unwrap();
+    assert_eq!(recorder.data(), b"abcdefghi0123456789xyz");
+
+    let mut out = Vec::new();
+    BufWriter::new(&mut out)
+        .write_all(b"flushed on drop")
+        .unwrap();
+    assert_eq!(out, b"flushed on drop");
+}
+
+#[test]
+fn buf_writer_errors() {
+    let recorder = Recorder {
+        limit: Some(4),
+        ..Default::default()
+    };
+    let mut writer = BufWriter::with_capacity(8, recorder);
+    writer.write_all(b"abcdef").unwrap();
+    assert_eq!(writer.flush(), Err(Error::WriteZero));
+    // Unwritten data stays buffered.
+    assert_eq!(writer.buffer(), b"ef");
+    assert_eq!(writer.into_inner().err(), Some(Error::WriteZero));
+}
+
+#[test]
+fn take_and_chain() {
+    let mut reader = b"hello".as_slice().chain(b" world".as_slice());
+    let mut string = String::new();
+    reader.by_ref().take(7).read_to_string(&mut string).unwrap();
+    assert_eq!(string, "hello w");
+
+    string.clear();
+    reader.read_to_string(&mut string).unwrap();
+    assert_eq!(string, "orld");
+
+    let mut take = Cursor::new(b"a\nb\nc".as_slice()).take(3);
+    let lines: Vec<_> = take.by_ref().lines().map(Result::unwrap).collect();
+    assert_eq!(lines, ["a", "b"]);
+    assert_eq!(take.limit(), 0);
+
+    let chained = b"one\ntw".as_slice().chain(b"o\nthree".as_slice());
+    let lines: Vec<_> = chained.lines().map(Result::unwrap).collect();
+    assert_eq!(lines, ["one", "two", "three"]);
+}
+
+#[test]
+fn copy_between() {
+    let data: Vec<u8> = (0..1500u32).map(|i| i as u8).collect();
+
+    let mut out = Vec::new();
+    assert_eq!(copy(&mut Trickle::new(&data, 100), &mut out), Ok(1500));
+    assert_eq!(out, data);
+
+    let mut out = Cursor::new(Vec::new());
+    let mut reader = BufReader::with_capacity(64, data.as_slice());
+    assert_eq!(copy_buf(&mut reader, &mut out), Ok(1500));
+    assert_eq!(out.into_inner(), data);
+
+    let mut full = [0; 10];
+    assert_eq!(
+        copy(&mut data.as_slice(), &mut Cursor::new(&mut full[..])),
+        Err(Error::WriteZero)
+    );
+}
+
+#[test]
+fn write_fmt() {
+    let mut out = Vec::new();
+    let name = "a";
+    write!(out, "{name}-{:02}", 7).unwrap();
+    writeln!(out, "!").unwrap();
+    assert_eq!(out, b"a-07!\n");
+
+    ufmt::uwrite!(out.fmt_writer(), "{} {}", 42, "ufmt").unwrap();
+    assert_eq!(out, b"a-07!\n42 ufmt");
+
+    let mut small = [0; 4];
+    let mut cursor = Cursor::new(&mut small[..]);
+    assert_eq!(write!(cursor, "{}", 123456), Err(Error::WriteZero));
+    assert_eq!(
+        ufmt::uwrite!(cursor.fmt_writer(), "{}", 1),
+        Err(Error::WriteZero)
+    );
+
+    struct Failing;
+    impl core::fmt::Display for Failing {
+        fn fmt(&self, _: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            Err(core::fmt::Error)
+        }
+    }
+    assert_eq!(write!(Vec::new(), "{Failing}"), Err(Error::InvalidData));
+}
@@ -0,0 +1,97 @@
+#[path = "../../flipperzero/src/storage/path.rs"]
+#[allow(dead_code)]
+mod path;
+
+use path::{join_parts, with_extension_parts, Path};
+
+fn join(base: &str, path: &str) -> String {
+    join_parts(Path::new(base), Path::new(path)).concat()
+}
+
+fn with_extension(path: &str, extension: &str) -> String {
+    with_extension_parts(Path::new(path), extension).concat()
+}
+
+#[test]
+fn parent() {
+    let parent = |p| Path::new(p).parent().map(Path::as_str);
+    assert_eq!(parent("/ext/subghz/garage.sub"), Some("/ext/subghz"));
+    assert_eq!(parent("/ext/subghz/"), Some("/ext"));
+    assert_eq!(parent("/ext"), Some("/"));
+    assert_eq!(parent("//ext//a"), Some("//ext"));
+    assert_eq!(parent("a"), Some(""));
+    assert_eq!(parent("a/b"), Some("a"));
+    assert_eq!(parent("/"), None);
+    assert_eq!(parent("///"), None);
+    assert_eq!(parent(""), None);
+}
+
+#[test]
+fn file_name_and_extension() {
+    let path = Path::new("/ext/subghz/garage.sub");
+    assert_eq!(path.file_name(), Some("garage.sub"));
+    assert_eq!(path.file_stem(), Some("garage"));
+    assert_eq!(path.extension(), Some("sub"));
+
+    let path = Path::new("/ext/archive.tar.gz/");
+    assert_eq!(path.file_name(), Some("archive.tar.gz"));
+    assert_eq!(path.file_stem(), Some("archive.tar"));
+    assert_eq!(path.extension(), Some("gz"));
+
+    assert_eq!(Path::new("/ext/.hidden").extension(), None);
+    assert_eq!(Path::new("/ext/.hidden").file_stem(), Some(".hidden"));
+    assert_eq!(Path::new("/ext/noext").extension(), None);
+    assert_eq!(Path::new("/ext/trailing.").extension(), Some(""));
+    assert_eq!(Path::new("/ext/..").file_name(), None);
+    assert_eq!(Path::new("/").file_name(), None);
+    assert_eq!(Path::new("").file_name(), None);
+}
+
+#[test]
+fn components_and_prefixes() {
+    let path = Path::new("/ext//subghz/garage.sub");
+    assert!(path.is_absolute());
+    assert!(!Path::new("ext").is_absolute());
+    assert!(Path::ROOT.is_root());
+    assert_eq!(
+        path.components().collect::<Vec<_>>(),
+        ["ext", "subghz", "garage.sub"]
+    );
+
+    assert!(path.starts_with(Path::new("/ext")));
+    assert!(path.starts_with(Path::new("/ext/subghz/")));
+    assert!(path.starts_with(Path::ROOT));
+    assert!(!path.starts_with(Path::new("/ex")));
+    assert!(!path.starts_with(Path::new("ext")));
+    assert!(!Path::new("/ext").starts_with(Path::new("/ext/subghz")));
+}
+
+#[test]
+fn join_paths() {
+    assert_eq!(join("/ext", "subghz"), "/ext/subghz");
+    assert_eq!(join("/ext/", "subghz"), "/ext/subghz");
+    assert_eq!(join("/", "ext"), "/ext");
+    assert_eq!(join("/ext", "/int"), "/int");
+    assert_eq!(join("", "a"), "a");
+    assert_eq!(join("a", "b/c"), "a/b/c");
+}
+
+#[test]
+fn replace_extension() {
+    assert_eq!(with_extension("/ext/tv.ir", "txt"), "/ext/tv.txt");
+    assert_eq!(with_extension("/ext/tv.ir", ".txt"), "/ext/tv.txt");
+    assert_eq!(with_extension("/ext/tv.ir", ""), "/ext/tv");
+    assert_eq!(with_extension("/ext/tv", "ir"), "/ext/tv.ir");
+    assert_eq!(with_extension("/ext/dir/", "ir"), "/ext/dir.ir");
+    assert_eq!(with_extension("/ext/.hidden", "bak"), "/ext/.hidden.bak");
+    assert_eq!(with_extension("/ext/a.tar.gz", "zip"), "/ext/a.tar.zip");
+    assert_eq!(with_extension("/", "ir"), "/");
+}
+
+#[test]
+fn formatting() {
+    let path = Path::new("/ext/tv.ir");
+    assert_eq!(format!("{path}"), "/ext/tv.ir");
+    assert_eq!(format!("{path:?}"), "\"/ext/tv.ir\"");
+    assert_eq!(path, "/ext/tv.ir");
+}
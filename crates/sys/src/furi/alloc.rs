@@ -78,8 +78,12 @@ impl<T> FuriBox<T> {
 
 impl<T: ?Sized> Drop for FuriBox<T> {
     fn drop(&mut self) {
-        // SAFETY: Pointer was allocated by `aligned_malloc`
-        unsafe { crate::aligned_free(self.0.as_ptr().cast()) }
+        unsafe {
+            // SAFETY: Pointer is non-null, aligned and represents a valid `T`
+            core::ptr::drop_in_place(self.0.as_ptr());
+            // SAFETY: Pointer was allocated by `aligned_malloc`
+            crate::aligned_free(self.0.as_ptr().cast());
+        }
     }
 }
 
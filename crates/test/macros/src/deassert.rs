@@ -1,5 +1,5 @@
 use quote::quote;
-use syn::{parse, Block, Expr, ExprMacro, ExprTuple, Stmt};
+use syn::{parse, Block, Expr, ExprMacro, ExprTuple, Stmt, StmtMacro};
 
 /// Find and replace macro assertions inside the given block with `return Err(..)`.
 ///
@@ -23,12 +23,23 @@ fn block_stmts(stmts: Vec<Stmt>) -> parse::Result<Vec<Stmt>> {
     stmts
         .into_iter()
         .map(|stmt| match stmt {
-            Stmt::Expr(Expr::Block(mut e)) => {
+            Stmt::Expr(Expr::Block(mut e), semi) => {
                 e.block.stmts = block_stmts(e.block.stmts)?;
-                Ok(Stmt::Expr(Expr::Block(e)))
+                Ok(Stmt::Expr(Expr::Block(e), semi))
             }
-            Stmt::Expr(Expr::Macro(m)) => expr_macro(m).map(Stmt::Expr),
-            Stmt::Semi(Expr::Macro(m), trailing) => expr_macro(m).map(|m| Stmt::Semi(m, trailing)),
+            Stmt::Expr(Expr::Macro(m), semi) => expr_macro(m).map(|m| Stmt::Expr(m, semi)),
+            Stmt::Macro(StmtMacro {
+                attrs,
+                mac,
+                semi_token,
+            }) => expr_macro(ExprMacro { attrs, mac }).map(|e| match e {
+                Expr::Macro(m) => Stmt::Macro(StmtMacro {
+                    attrs: m.attrs,
+                    mac: m.mac,
+                    semi_token,
+                }),
+                e => Stmt::Expr(e, semi_token),
+            }),
             _ => Ok(stmt),
         })
         .collect::<Result<_, _>>()
@@ -1,3 +1,5 @@
+use std::mem;
+
 use proc_macro::TokenStream;
 use proc_macro2::Span;
 use quote::{quote, ToTokens};
@@ -5,7 +7,7 @@ use syn::{
     parse::{self, Parse},
     punctuated::Punctuated,
     spanned::Spanned,
-    token, Expr, ExprArray, Ident, Item, ItemMod, ReturnType, Stmt, Token,
+    token, BinOp, Expr, ExprArray, Ident, Item, ItemMod, ReturnType, Stmt, Token,
 };
 
 mod deassert;
@@ -78,19 +80,31 @@ fn tests_runner_impl(args: TokenStream) -> parse::Result<TokenStream> {
     let test_suites = test_suites
         .elems
         .into_iter()
-        .map(|attr| {
+        .map(|mut attr| {
+            // Suites can be gated with `#[cfg(..)]`, such as on a crate feature.
+            let cfg = match &mut attr {
+                Expr::Path(path) => mem::take(&mut path.attrs),
+                _ => vec![],
+            };
+            if let Some(other) = cfg.iter().find(|a| !a.path().is_ident("cfg")) {
+                return Err(parse::Error::new(
+                    other.span(),
+                    "Only `#[cfg(..)]` attributes are supported on test suites",
+                ));
+            }
+
             let mut module = String::new();
             for token in attr.to_token_stream() {
                 module.push_str(&token.to_string());
             }
             let module = module.trim_start_matches("crate::");
 
-            (
-                quote!(#attr::__test_list().len()),
-                quote!(#attr::__test_list().iter().copied().map(|(name, test_fn)| (#module, name, test_fn))),
-            )
+            Ok((
+                quote!(#(#cfg)* let ret = ret + #attr::__test_list().len();),
+                quote!(#(#cfg)* let ret = ret.chain(#attr::__test_list().iter().copied().map(|(name, test_fn)| (#module, name, test_fn)));),
+            ))
         })
-        .collect::<Vec<_>>();
+        .collect::<parse::Result<Vec<_>>>()?;
 
     let test_counts = test_suites.iter().map(|(count, _)| count);
     let test_lists = test_suites.iter().map(|(_, list)| list);
@@ -120,13 +134,13 @@ fn tests_runner_impl(args: TokenStream) -> parse::Result<TokenStream> {
 
             const fn test_count() -> usize {
                 let ret = 0;
-                #( let ret = ret + #test_counts; )*
+                #( #test_counts )*
                 ret
             }
 
             fn test_list() -> impl Iterator<Item = (&'static str, &'static str, ::flipperzero_test::TestFn)> + Clone {
                 let ret = ::core::iter::empty();
-                #( let ret = ret.chain(#test_lists); )*
+                #( #test_lists )*
                 ret
             }
 
@@ -194,11 +208,11 @@ fn tests_impl(args: TokenStream, input: TokenStream) -> parse::Result<TokenStrea
 
                 // Find and extract the `#[test]` and `#[cfg(..)] attributes, if present.
                 f.attrs.retain(|attr| {
-                    if attr.path.is_ident("test") {
+                    if attr.path().is_ident("test") {
                         is_test = true;
                         false
                     } else {
-                        if attr.path.is_ident("cfg") {
+                        if attr.path().is_ident("cfg") {
                             cfg.push(attr.clone());
                         }
                         true
@@ -234,9 +248,10 @@ fn tests_impl(args: TokenStream, input: TokenStream) -> parse::Result<TokenStrea
                     check_ret_block(&mut f.block.stmts)?;
 
                     // Append an `Ok(())` to the test.
-                    f.block.stmts.push(Stmt::Expr(syn::parse(
-                        quote!(::core::result::Result::Ok(())).into(),
-                    )?));
+                    f.block.stmts.push(Stmt::Expr(
+                        syn::parse(quote!(::core::result::Result::Ok(())).into())?,
+                        None,
+                    ));
 
                     tests.push(f);
                     test_cfgs.push(cfg);
@@ -276,14 +291,20 @@ fn tests_impl(args: TokenStream, input: TokenStream) -> parse::Result<TokenStrea
 }
 
 fn check_ret_block(stmts: &mut [Stmt]) -> parse::Result<()> {
-    if let Some(stmt) = stmts.last_mut() {
-        if let Stmt::Expr(expr) = stmt {
+    match stmts.last_mut() {
+        Some(Stmt::Expr(expr, None)) => {
             if let Some(new_stmt) = check_ret_expr(expr)? {
-                *stmt = new_stmt;
+                *stmts.last_mut().unwrap() = new_stmt;
             }
+            Ok(())
         }
+        // A trailing macro without a semicolon is an expression.
+        Some(Stmt::Macro(m)) if m.semi_token.is_none() => Err(parse::Error::new(
+            m.span(),
+            "`#[test]` function must not return anything",
+        )),
+        _ => Ok(()),
     }
-    Ok(())
 }
 
 fn check_ret_expr(expr: &mut Expr) -> parse::Result<Option<Stmt>> {
@@ -310,11 +331,12 @@ fn check_ret_expr(expr: &mut Expr) -> parse::Result<Option<Stmt>> {
         Expr::TryBlock(e) => check_ret_block(&mut e.block.stmts).map(|()| None),
         Expr::Unsafe(e) => check_ret_block(&mut e.block.stmts).map(|()| None),
         // If `expr` implicitly returns `()`, append a semicolon.
-        Expr::Assign(_) | Expr::AssignOp(_) => {
-            Ok(Some(Stmt::Semi(expr.clone(), Token!(;)(expr.span()))))
+        Expr::Assign(_) => Ok(Some(Stmt::Expr(expr.clone(), Some(Token!(;)(expr.span()))))),
+        Expr::Binary(bin) if is_assign_op(&bin.op) => {
+            Ok(Some(Stmt::Expr(expr.clone(), Some(Token!(;)(expr.span())))))
         }
         Expr::Break(brk) if brk.expr.is_none() => {
-            Ok(Some(Stmt::Semi(expr.clone(), Token!(;)(expr.span()))))
+            Ok(Some(Stmt::Expr(expr.clone(), Some(Token!(;)(expr.span())))))
         }
         // For all other expressions, raise an error.
         _ => Err(parse::Error::new(
@@ -323,3 +345,20 @@ fn check_ret_expr(expr: &mut Expr) -> parse::Result<Option<Stmt>> {
         )),
     }
 }
+
+/// Returns `true` if `op` is a compound assignment, such as `+=`.
+fn is_assign_op(op: &BinOp) -> bool {
+    matches!(
+        op,
+        BinOp::AddAssign(_)
+            | BinOp::SubAssign(_)
+            | BinOp::MulAssign(_)
+            | BinOp::DivAssign(_)
+            | BinOp::RemAssign(_)
+            | BinOp::BitXorAssign(_)
+            | BinOp::BitAndAssign(_)
+            | BinOp::BitOrAssign(_)
+            | BinOp::ShlAssign(_)
+            | BinOp::ShrAssign(_)
+    )
+}
@@ -0,0 +1,180 @@
+//! Flipper Format CLI.
+//!
+//! Inspects and converts Flipper Format files (`.sub`, `.ir`, `.nfc`, settings, ...),
+//! either local or read from the device.
+
+use std::io::Read;
+use std::path::PathBuf;
+use std::process;
+use std::time::Duration;
+use std::{fs, io};
+
+use clap::{Args, Parser, Subcommand};
+use flipperzero_format::FlipperFormat;
+use flipperzero_tools::storage::FlipperPath;
+use flipperzero_tools::{serial, storage};
+use serde::{Deserialize, Serialize};
+
+/// Flipper Format tool
+#[derive(Parser)]
+#[command(author, version, about, long_about = None)]
+struct Cli {
+    /// Commands
+    #[command(subcommand)]
+    command: Commands,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Check that a file parses, and print its header
+    Check {
+        #[command(flatten)]
+        input: Input,
+    },
+    /// Print the value of every occurrence of a key
+    Get {
+        #[command(flatten)]
+        input: Input,
+        /// Key
+        key: String,
+    },
+    /// Convert a file to JSON
+    ToJson {
+        #[command(flatten)]
+        input: Input,
+    },
+    /// Convert JSON (as produced by `to-json`) to a file
+    FromJson {
+        /// Local JSON path (`-` for stdin)
+        json_path: PathBuf,
+        /// Local output path (default: stdout)
+        output_path: Option<PathBuf>,
+    },
+}
+
+#[derive(Args)]
+struct Input {
+    /// Local path (`-` for stdin), or Flipper path with `--flipper`
+    path: String,
+    /// Read the file from the Flipper Zero
+    #[arg(short, long)]
+    flipper: bool,
+    /// Serial port (e.g. `COM3` on Windows or `/dev/ttyUSB0` on Linux)
+    #[arg(short, long, requires = "flipper")]
+    port: Option<String>,
+}
+
+/// A key and its value, as stored in the file.
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    key: String,
+    value: String,
+}
+
+impl Input {
+    fn read(&self) -> io::Result<String> {
+        if self.flipper {
+            let port_info = serial::find_flipperzero(self.port.as_deref())
+                .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "Flipper Zero not found"))?;
+            let port = serialport::new(port_info.port_name, serial::BAUD_115200)
+                .timeout(Duration::from_secs(30))
+                .open()?;
+
+            let mut store = storage::FlipperStorage::new(port);
+            store.start()?;
+
+            let data = store.read_file(&FlipperPath::from(self.path.clone()))?;
+            String::from_utf8(data.to_vec())
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+        } else {
+            read_local(&self.path)
+        }
+    }
+
+    fn parse(&self) -> io::Result<FlipperFormat> {
+        self.read()?
+            .parse()
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+}
+
+fn read_local(path: &str) -> io::Result<String> {
+    if path == "-" {
+        let mut text = String::new();
+        io::stdin().read_to_string(&mut text)?;
+        Ok(text)
+    } else {
+        fs::read_to_string(path)
+    }
+}
+
+fn main() {
+    let cli = Cli::parse();
+
+    let result = match cli.command {
+        Commands::Check { input } => input.parse().and_then(|mut ff| {
+            let (filetype, version) = ff
+                .read_header()
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+            println!(
+                "{filetype} (version {version}), {} keys",
+                ff.entries().count()
+            );
+
+            Ok(())
+        }),
+        Commands::Get { input, key } => input.parse().and_then(|ff| {
+            let mut found = false;
+            for (_, value) in ff.entries().filter(|(k, _)| *k == key) {
+                println!("{value}");
+                found = true;
+            }
+
+            if found {
+                Ok(())
+            } else {
+                Err(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("missing key `{key}`"),
+                ))
+            }
+        }),
+        Commands::ToJson { input } => input.parse().and_then(|ff| {
+            let entries: Vec<_> = ff
+                .entries()
+                .map(|(key, value)| Entry {
+                    key: key.to_owned(),
+                    value: value.to_owned(),
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&entries)?);
+
+            Ok(())
+        }),
+        Commands::FromJson {
+            json_path,
+            output_path,
+        } => read_local(&json_path.to_string_lossy()).and_then(|json| {
+            let entries: Vec<Entry> = serde_json::from_str(&json)?;
+
+            let mut ff = FlipperFormat::new();
+            for entry in &entries {
+                ff.write_string(&entry.key, &entry.value)
+                    .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+            }
+
+            match output_path {
+                Some(path) => fs::write(path, ff.to_string()),
+                None => {
+                    print!("{ff}");
+                    Ok(())
+                }
+            }
+        }),
+    };
+
+    if let Err(err) = result {
+        eprintln!("ERROR: {err}");
+        process::exit(1);
+    }
+}